@@ -0,0 +1,146 @@
+//! Key comparators
+//!
+//! Every db type has a comparator parameter `C`, which defaults to
+//! [`DefaultComparator`] (lexicographic order of the encoded keys).
+//! Iteration order, and all range bounds, are interpreted according to the
+//! comparator: a range contains the keys that are after the start bound and
+//! before the end bound in comparator order.
+//! A range with a start bound after its end bound is empty.
+//! Keys that are equal according to the comparator are the same key, even
+//! if their encodings differ.
+//!
+//! The comparator is not stored in the db, so a db must always be opened
+//! with the same comparator that it was created with. Opening a db with a
+//! different comparator will result in incorrect lookups and iteration
+//! order.
+//!
+//! Scoped views of a db (see [`crate::DatabaseUnique::scoped`]) rely on
+//! lexicographic order, and are only available with the default comparator.
+
+use std::{cmp::Ordering, marker::PhantomData};
+
+pub use heed::{Comparator, DefaultComparator, LexicographicComparator};
+
+/// Comparator that reverses the order of another comparator.
+///
+/// A db using `Reverse<DefaultComparator>` stores keys in descending
+/// order, so iterators yield the greatest key first, and range bounds are
+/// interpreted in descending order: the start bound of a range is the
+/// greatest key in the range.
+///
+/// ```
+/// # use sneed::{comparator::Reverse, make_guard, DatabaseUnique, Env, EnvOpenOptions};
+/// # use fallible_iterator::FallibleIterator as _;
+/// use sneed::{comparator::DefaultComparator, types::{BigEndian, U64}};
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let mut rwtxn = env.write_txn()?;
+/// let db: DatabaseUnique<U64<BigEndian>, U64<BigEndian>, Reverse<DefaultComparator>> =
+///     DatabaseUnique::create(&env, &mut rwtxn, "descending")?;
+/// for key in 0..10 {
+///     let () = db.put(&mut rwtxn, &key, &key)?;
+/// }
+/// let keys: Vec<u64> = db.iter_keys(&rwtxn)?.collect()?;
+/// assert_eq!(keys, (0..10).rev().collect::<Vec<_>>());
+/// let keys: Vec<u64> = db.range(&rwtxn, &(7..=3))?.map(|(k, _)| Ok(k)).collect()?;
+/// assert_eq!(keys, [7, 6, 5, 4, 3]);
+/// // The start bound is after the end bound in descending order
+/// assert_eq!(db.range(&rwtxn, &(3..=7))?.count()?, 0);
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+///
+/// ```
+/// # use sneed::{comparator::Reverse, make_guard, DatabaseUnique, Env, EnvOpenOptions};
+/// # use fallible_iterator::FallibleIterator as _;
+/// use std::ops::Bound;
+///
+/// use sneed::{comparator::DefaultComparator, types::Str};
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let mut rwtxn = env.write_txn()?;
+/// let db: DatabaseUnique<Str, Str, Reverse<DefaultComparator>> =
+///     DatabaseUnique::create(&env, &mut rwtxn, "descending-str")?;
+/// for key in ["a", "aa", "ab", "b", "ba"] {
+///     let () = db.put(&mut rwtxn, key, key)?;
+/// }
+/// // Equivalent to `"b".."a"`, which is not a range of `str`
+/// let range = (Bound::Included("b"), Bound::Excluded("a"));
+/// let keys: Vec<&str> = db.range_keys(&rwtxn, &range)?.collect()?;
+/// assert_eq!(keys, ["b", "ab", "aa"]);
+/// let keys: Vec<&str> = db.rev_range_keys(&rwtxn, &range)?.collect()?;
+/// assert_eq!(keys, ["aa", "ab", "b"]);
+/// assert_eq!(db.first(&rwtxn)?, Some(("ba", "ba")));
+/// assert_eq!(db.last(&rwtxn)?, Some(("a", "a")));
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+pub struct Reverse<C>(PhantomData<C>);
+
+impl<C> Comparator for Reverse<C>
+where
+    C: Comparator,
+{
+    #[inline(always)]
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        C::compare(a, b).reverse()
+    }
+}
+
+/// Comparator that ignores ASCII case.
+///
+/// Keys are ordered lexicographically by their encoded bytes, with ASCII
+/// letters compared as if they were lowercase.
+/// Keys that differ only in ASCII case are the same key, so writing `"KEY"`
+/// to a db that contains `"key"` replaces the value for `"key"`.
+/// Which of the two encodings is stored afterwards is unspecified.
+///
+/// ```
+/// # use sneed::{comparator::CaseInsensitive, make_guard, DatabaseUnique, Env, EnvOpenOptions};
+/// # use fallible_iterator::FallibleIterator as _;
+/// use std::ops::Bound;
+///
+/// use sneed::types::Str;
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let mut rwtxn = env.write_txn()?;
+/// let db: DatabaseUnique<Str, Str, CaseInsensitive> =
+///     DatabaseUnique::create(&env, &mut rwtxn, "case-insensitive")?;
+/// for key in ["apple", "Banana", "cherry"] {
+///     let () = db.put(&mut rwtxn, key, key)?;
+/// }
+/// assert_eq!(db.try_get(&rwtxn, "BANANA")?, Some("Banana"));
+/// let range = (Bound::Excluded("APPLE"), Bound::Included("CHERRY"));
+/// let keys: Vec<&str> = db.range_keys(&rwtxn, &range)?.collect()?;
+/// assert_eq!(keys, ["Banana", "cherry"]);
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+pub struct CaseInsensitive;
+
+impl Comparator for CaseInsensitive {
+    #[inline(always)]
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        a.iter()
+            .map(u8::to_ascii_lowercase)
+            .cmp(b.iter().map(u8::to_ascii_lowercase))
+    }
+}
@@ -0,0 +1,313 @@
+//! Batches of writes, to be applied atomically in a single write txn
+
+use heed::{types::Bytes, BytesEncode, PutFlags};
+
+use crate::{
+    db::{error as db_error, DatabaseDup, DatabaseUnique, DbWrapper},
+    RwTxn,
+};
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::db;
+
+    #[derive(Debug, Error)]
+    #[error("Failed to encode {item} for write batch to db `{db_name}` at `{db_path}`")]
+    pub struct Encode {
+        pub(crate) db_name: String,
+        pub(crate) db_path: PathBuf,
+        /// `key` or `value`
+        pub(crate) item: &'static str,
+        pub(crate) source: Box<dyn std::error::Error + Send + Sync>,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Failed to apply op {index} of write batch")]
+    pub struct Apply {
+        /// Index of the failing op in the batch
+        pub(crate) index: usize,
+        pub(crate) source: db::error::Error,
+    }
+
+    impl Apply {
+        /// Index of the failing op in the batch
+        pub fn index(&self) -> usize {
+            self.index
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{Apply, Encode};
+        use crate::serde_utils::serialize_error_struct;
+
+        serialize_error_struct!(Encode {
+            db_name: Plain,
+            db_path: PathStr,
+            item: Plain,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(Apply {
+            index: Plain,
+            source: Plain,
+        });
+    }
+}
+
+/// Type-erased target db for a batched op
+trait Target<'env_id>: Send + Sync {
+    fn put(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), db_error::Put>;
+
+    fn delete(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, db_error::Delete>;
+
+    fn clear(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), db_error::Clear>;
+}
+
+impl<'env_id, C> Target<'env_id> for DbWrapper<'env_id, Bytes, Bytes, C>
+where
+    C: Send + Sync,
+{
+    fn put(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), db_error::Put> {
+        self.put_with_flags(rwtxn, PutFlags::empty(), key, value)
+    }
+
+    fn delete(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, db_error::Delete> {
+        DbWrapper::delete(self, rwtxn, key)
+    }
+
+    fn clear(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), db_error::Clear> {
+        DbWrapper::clear(self, rwtxn)
+    }
+}
+
+enum Op<'env_id> {
+    Put {
+        db: Box<dyn Target<'env_id> + 'env_id>,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        db: Box<dyn Target<'env_id> + 'env_id>,
+        key: Vec<u8>,
+    },
+    Clear {
+        db: Box<dyn Target<'env_id> + 'env_id>,
+    },
+}
+
+fn encode<'a, KC, DC, C>(
+    db: &DbWrapper<'_, KC, DC, C>,
+    key: &'a KC::EItem,
+    value: &'a DC::EItem,
+) -> Result<(Vec<u8>, Vec<u8>), error::Encode>
+where
+    KC: BytesEncode<'a>,
+    DC: BytesEncode<'a>,
+{
+    let key = encode_key(db, key)?;
+    let value = DC::bytes_encode(value).map_err(|err| error::Encode {
+        db_name: (*db.name).to_owned(),
+        db_path: (*db.path).to_owned(),
+        item: "value",
+        source: err,
+    })?;
+    Ok((key, value.into_owned()))
+}
+
+fn encode_key<'a, KC, DC, C>(
+    db: &DbWrapper<'_, KC, DC, C>,
+    key: &'a KC::EItem,
+) -> Result<Vec<u8>, error::Encode>
+where
+    KC: BytesEncode<'a>,
+{
+    let key = KC::bytes_encode(key).map_err(|err| error::Encode {
+        db_name: (*db.name).to_owned(),
+        db_path: (*db.path).to_owned(),
+        item: "key",
+        source: err,
+    })?;
+    Ok(key.into_owned())
+}
+
+/// Writes that are encoded up-front, and applied in order with
+/// [`WriteBatch::apply`].
+/// Batches can be built on other threads, eg. while holding a read txn,
+/// and sent to a single writer.
+pub struct WriteBatch<'env_id> {
+    ops: Vec<Op<'env_id>>,
+}
+
+impl<'env_id> WriteBatch<'env_id> {
+    pub fn new() -> Self {
+        Self { ops: Vec::new() }
+    }
+
+    /// Number of ops in the batch
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub fn put_unique<'a, KC, DC, C>(
+        &mut self,
+        db: &DatabaseUnique<'env_id, KC, DC, C>,
+        key: &'a KC::EItem,
+        value: &'a DC::EItem,
+    ) -> Result<(), error::Encode>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        let (key, value) = encode(db, key, value)?;
+        self.ops.push(Op::Put {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+            key,
+            value,
+        });
+        Ok(())
+    }
+
+    pub fn delete_unique<'a, KC, DC, C>(
+        &mut self,
+        db: &DatabaseUnique<'env_id, KC, DC, C>,
+        key: &'a KC::EItem,
+    ) -> Result<(), error::Encode>
+    where
+        KC: BytesEncode<'a>,
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        let key = encode_key(db, key)?;
+        self.ops.push(Op::Delete {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+            key,
+        });
+        Ok(())
+    }
+
+    pub fn clear_unique<KC, DC, C>(
+        &mut self,
+        db: &DatabaseUnique<'env_id, KC, DC, C>,
+    ) where
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        self.ops.push(Op::Clear {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+        });
+    }
+
+    pub fn put_dup<'a, KC, DC, C>(
+        &mut self,
+        db: &DatabaseDup<'env_id, KC, DC, C>,
+        key: &'a KC::EItem,
+        value: &'a DC::EItem,
+    ) -> Result<(), error::Encode>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        let (key, value) = encode(db, key, value)?;
+        self.ops.push(Op::Put {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+            key,
+            value,
+        });
+        Ok(())
+    }
+
+    /// Delete each item with the specified key
+    pub fn delete_each_dup<'a, KC, DC, C>(
+        &mut self,
+        db: &DatabaseDup<'env_id, KC, DC, C>,
+        key: &'a KC::EItem,
+    ) -> Result<(), error::Encode>
+    where
+        KC: BytesEncode<'a>,
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        let key = encode_key(db, key)?;
+        self.ops.push(Op::Delete {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+            key,
+        });
+        Ok(())
+    }
+
+    pub fn clear_dup<KC, DC, C>(&mut self, db: &DatabaseDup<'env_id, KC, DC, C>)
+    where
+        C: Send + Sync + 'env_id,
+    {
+        let db = &db.inner.inner;
+        self.ops.push(Op::Clear {
+            db: Box::new(db.remap_types::<Bytes, Bytes>()),
+        });
+    }
+
+    /// Apply each op in the batch, in order.
+    /// If an op fails, the txn should be aborted, as earlier ops in the
+    /// batch will already have been applied.
+    // The error wraps the general db error, since ops may fail in any way
+    #[allow(clippy::result_large_err)]
+    pub fn apply(
+        self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), error::Apply> {
+        for (index, op) in self.ops.into_iter().enumerate() {
+            let res: Result<(), db_error::Error> = match op {
+                Op::Put { db, key, value } => {
+                    db.put(rwtxn, &key, &value).map_err(Into::into)
+                }
+                Op::Delete { db, key } => {
+                    db.delete(rwtxn, &key).map(|_| ()).map_err(Into::into)
+                }
+                Op::Clear { db } => db.clear(rwtxn).map_err(Into::into),
+            };
+            let () = res.map_err(|err| error::Apply { index, source: err })?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for WriteBatch<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
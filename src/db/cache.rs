@@ -0,0 +1,221 @@
+//! Read-through caches for databases
+
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashSet},
+    hash::BuildHasher as _,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use heed::{BytesDecode, BytesEncode, DefaultComparator};
+use lru::LruCache;
+
+use crate::{
+    db::{error, RoDatabaseUnique},
+    Env, RoTxn,
+};
+
+/// Number of independently locked shards in each cache
+const SHARDS: usize = 16;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Hit and miss counts for a [`CachedDatabase`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Commit sequence numbers of the last write txns to write to each db in
+/// an env, shared by every clone of the env.
+/// Sequence numbers are recorded before each write txn is committed, so
+/// that a cache can tell whether a read txn might not see the latest write
+/// to a db.
+#[derive(Debug, Default)]
+pub(crate) struct LastWriteSeqs {
+    /// Sequence number of the last write txn that wrote with raw handles,
+    /// and might have written to any db
+    any: Arc<AtomicU64>,
+    by_db: Mutex<BTreeMap<Arc<str>, Arc<AtomicU64>>>,
+}
+
+impl LastWriteSeqs {
+    /// Sequence number of the last write txn to write to the db with the
+    /// specified name
+    fn db(&self, db_name: &str) -> Arc<AtomicU64> {
+        let mut by_db = lock(&self.by_db);
+        if let Some(seq) = by_db.get(db_name) {
+            return seq.clone();
+        }
+        by_db.entry(Arc::from(db_name)).or_default().clone()
+    }
+
+    /// Record the sequence number of a write txn that is about to be
+    /// committed.
+    /// If `raw_write` is `true`, the txn is treated as writing to every db.
+    pub(crate) fn record(
+        &self,
+        commit_seq: u64,
+        dbs_written: &HashSet<Arc<str>>,
+        raw_write: bool,
+    ) {
+        if raw_write {
+            self.any.fetch_max(commit_seq, Ordering::SeqCst);
+        }
+        if dbs_written.is_empty() {
+            return;
+        }
+        let mut by_db = lock(&self.by_db);
+        for db_name in dbs_written {
+            by_db
+                .entry(db_name.clone())
+                .or_default()
+                .fetch_max(commit_seq, Ordering::SeqCst);
+        }
+    }
+}
+
+/// A shard of a cache
+struct Shard<V> {
+    /// Read txns with a snapshot sequence number of at least this value
+    /// see every write to the db that has been observed by the shard.
+    /// Reads from other txns bypass the shard.
+    valid_from_seq: u64,
+    values: LruCache<Vec<u8>, V>,
+}
+
+/// Read-through cache over a db, with an LRU cache for each shard of
+/// encoded keys.
+/// Values are decoded once, and cloned on each cache hit.
+///
+/// The cache is cleared when a write txn that wrote to the db is
+/// committed, including writes with [`crate::dump::restore_env`] and writes
+/// with raw handles. The commit sequence number of each write txn is
+/// recorded before it is committed, and reads using txns that might not see
+/// the latest write to the db bypass the cache.
+/// See [`Env::last_commit_seq`].
+pub struct CachedDatabase<'env_id, KC, DC, V, C = DefaultComparator> {
+    db: RoDatabaseUnique<'env_id, KC, DC, C>,
+    /// Sequence number of the last write txn to write to the db
+    db_write_seq: Arc<AtomicU64>,
+    /// Sequence number of the last write txn to write with raw handles
+    any_write_seq: Arc<AtomicU64>,
+    hasher: RandomState,
+    shards: Vec<Mutex<Shard<V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<'env_id, KC, DC, V, C> CachedDatabase<'env_id, KC, DC, V, C> {
+    /// Cache up to approximately `capacity` values
+    pub fn new(
+        env: &Env<'env_id>,
+        db: RoDatabaseUnique<'env_id, KC, DC, C>,
+        capacity: NonZeroUsize,
+    ) -> Self
+    where
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        let shard_capacity = NonZeroUsize::new(capacity.get().div_ceil(SHARDS))
+            .expect("Shard capacity should be non-zero");
+        let last_write_seqs = env.last_write_seqs();
+        let mut res = Self {
+            db_write_seq: last_write_seqs.db(db.name()),
+            any_write_seq: last_write_seqs.any.clone(),
+            db,
+            hasher: RandomState::new(),
+            shards: Vec::with_capacity(SHARDS),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+        let valid_from_seq = res.last_write_seq();
+        res.shards = (0..SHARDS)
+            .map(|_| {
+                Mutex::new(Shard {
+                    valid_from_seq,
+                    values: LruCache::new(shard_capacity),
+                })
+            })
+            .collect();
+        res
+    }
+
+    pub fn db(&self) -> &RoDatabaseUnique<'env_id, KC, DC, C> {
+        &self.db
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sequence number of the last write txn that might have written to
+    /// the db
+    fn last_write_seq(&self) -> u64 {
+        std::cmp::max(
+            self.db_write_seq.load(Ordering::SeqCst),
+            self.any_write_seq.load(Ordering::SeqCst),
+        )
+    }
+
+    /// Lock a shard, clearing it if the db has been written to since the
+    /// shard was last checked
+    fn lock_shard(&self, shard_index: usize) -> MutexGuard<'_, Shard<V>> {
+        let mut shard = lock(&self.shards[shard_index]);
+        let last_write_seq = self.last_write_seq();
+        if last_write_seq > shard.valid_from_seq {
+            // Sequence numbers are recorded before commit, so txns with a
+            // snapshot of at least this sequence number see the latest
+            // write to the db
+            shard.valid_from_seq = last_write_seq;
+            shard.values.clear();
+        }
+        shard
+    }
+
+    /// Get the value for a key, from the cache if possible
+    pub fn try_get_cached<'a>(
+        &self,
+        rotxn: &RoTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+    ) -> Result<Option<V>, error::TryGet>
+    where
+        KC: BytesEncode<'a>,
+        DC: for<'b> BytesDecode<'b, DItem = V>,
+        V: Clone,
+    {
+        let Ok(key_bytes) = KC::bytes_encode(key) else {
+            // Report the encoding error
+            return self.db.try_get(rotxn, key);
+        };
+        let shard_index = self.hasher.hash_one(&*key_bytes) as usize % SHARDS;
+        {
+            // Hold the lock while reading, so that the shard cannot be
+            // invalidated and refilled between the check and the read
+            let mut shard = self.lock_shard(shard_index);
+            if rotxn.snapshot_seq >= shard.valid_from_seq {
+                if let Some(value) = shard.values.get(&*key_bytes) {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(Some(value.clone()));
+                }
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = self.db.try_get(rotxn, key)?;
+        if let Some(value) = &value {
+            let mut shard = self.lock_shard(shard_index);
+            if rotxn.snapshot_seq >= shard.valid_from_seq {
+                shard.values.put(key_bytes.into_owned(), value.clone());
+            }
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,346 @@
+//! Helpers for collecting the items in a db
+
+use fallible_iterator::FallibleIterator;
+use heed::{types::Bytes, BytesDecode};
+
+use crate::{
+    db::{error, DbWrapper, RoDatabaseDup, RoDatabaseUnique},
+    Txn,
+};
+
+/// How to handle keys with more than one value when collecting items
+/// into a map
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicateKeys {
+    /// Return an [`error::DuplicateKey`] error
+    #[default]
+    Error,
+    /// Keep the least value for each key
+    KeepFirst,
+    /// Keep the greatest value for each key
+    KeepLast,
+}
+
+impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
+    fn iter_collect_vec<'env, Tx, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<Vec<(K, V)>, error::Iter>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        let items = self.iter_remapped::<_, KC, DC>(txn)?.collect()?;
+        Ok(items)
+    }
+
+    fn iter_collect_map<'env, Tx, M, K, V>(
+        &self,
+        txn: &Tx,
+        duplicate_keys: DuplicateKeys,
+    ) -> Result<M, error::IterCollectMap>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+        M: FromIterator<(K, V)>,
+    {
+        let mut items = self.iter_remapped::<_, Bytes, DC>(txn)?.peekable();
+        let db_name = &*self.name;
+        let db_path = &*self.path;
+        fallible_iterator::from_fn(|| {
+            let Some((key_bytes, mut value)) = items.next()? else {
+                return Ok(None);
+            };
+            while let Some((_, next_value)) = items
+                .next_if(|(next_key_bytes, _)| *next_key_bytes == key_bytes)?
+            {
+                match duplicate_keys {
+                    DuplicateKeys::Error => {
+                        return Err(error::DuplicateKey {
+                            db_name: db_name.to_owned(),
+                            db_path: db_path.to_owned(),
+                            key_bytes: key_bytes.to_vec(),
+                        }
+                        .into())
+                    }
+                    DuplicateKeys::KeepFirst => (),
+                    DuplicateKeys::KeepLast => value = next_value,
+                }
+            }
+            let key =
+                KC::bytes_decode(key_bytes).map_err(|err| error::IterItem {
+                    db_name: db_name.to_owned(),
+                    db_path: db_path.to_owned(),
+                    source: heed::Error::Decoding(err),
+                })?;
+            Ok(Some((key, value)))
+        })
+        .collect()
+    }
+
+    fn try_for_each_entry<'a, 'env, 'txn, Tx, F, E>(
+        &'a self,
+        txn: &'txn Tx,
+        f: F,
+    ) -> Result<(), E>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut((KC::DItem, DC::DItem)) -> Result<(), E>,
+        E: From<error::Iter>,
+    {
+        let mut f = f;
+        let mut items = self
+            .iter_remapped::<_, KC, DC>(txn)
+            .map_err(error::Iter::from)?;
+        while let Some(item) = items.next().map_err(error::Iter::from)? {
+            let () = f(item)?;
+        }
+        Ok(())
+    }
+
+    fn count_while<'a, 'env, 'txn, Tx, F>(
+        &'a self,
+        txn: &'txn Tx,
+        f: F,
+    ) -> Result<u64, error::Iter>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(&KC::DItem, &DC::DItem) -> bool,
+    {
+        let mut f = f;
+        let mut items = self.iter_remapped::<_, KC, DC>(txn)?;
+        let mut count = 0;
+        while let Some((key, value)) = items.next()? {
+            if !f(&key, &value) {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn fold<'a, 'env, 'txn, Tx, B, F>(
+        &'a self,
+        txn: &'txn Tx,
+        init: B,
+        f: F,
+    ) -> Result<B, error::Iter>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(B, KC::DItem, DC::DItem) -> B,
+    {
+        let mut f = f;
+        let acc = self
+            .iter_remapped::<_, KC, DC>(txn)?
+            .fold(init, |acc, (key, value)| Ok(f(acc, key, value)))?;
+        Ok(acc)
+    }
+
+    fn try_fold<'a, 'env, 'txn, Tx, B, F, E>(
+        &'a self,
+        txn: &'txn Tx,
+        init: B,
+        f: F,
+    ) -> Result<B, E>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(B, KC::DItem, DC::DItem) -> Result<B, E>,
+        E: From<error::Iter>,
+    {
+        let mut f = f;
+        let mut items = self
+            .iter_remapped::<_, KC, DC>(txn)
+            .map_err(error::Iter::from)?;
+        let mut acc = init;
+        while let Some((key, value)) =
+            items.next().map_err(error::Iter::from)?
+        {
+            acc = f(acc, key, value)?;
+        }
+        Ok(acc)
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Collect all items, in key order
+    #[inline(always)]
+    pub fn iter_collect_vec<'env, Tx, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<Vec<(K, V)>, error::Iter>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        self.inner.iter_collect_vec(txn)
+    }
+
+    /// Collect all items into a map, eg. a [`std::collections::HashMap`] or
+    /// [`std::collections::BTreeMap`]
+    #[inline(always)]
+    pub fn iter_collect_map<'env, Tx, M, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<M, error::IterCollectMap>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+        M: FromIterator<(K, V)>,
+    {
+        self.inner.iter_collect_map(txn, DuplicateKeys::Error)
+    }
+
+    /// Call `f` on each item, in key order, stopping at the first error
+    #[inline(always)]
+    pub fn try_for_each_entry<'a, 'env, 'txn, Tx, F, E>(
+        &'a self,
+        txn: &'txn Tx,
+        f: F,
+    ) -> Result<(), E>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut((KC::DItem, DC::DItem)) -> Result<(), E>,
+        E: From<error::Iter>,
+    {
+        self.inner.try_for_each_entry(txn, f)
+    }
+
+    /// Count the leading items, in key order, for which `f` returns
+    /// `true`.
+    /// Stops reading at the first item for which `f` returns `false`.
+    #[inline(always)]
+    pub fn count_while<'a, 'env, 'txn, Tx, F>(
+        &'a self,
+        txn: &'txn Tx,
+        f: F,
+    ) -> Result<u64, error::Iter>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(&KC::DItem, &DC::DItem) -> bool,
+    {
+        self.inner.count_while(txn, f)
+    }
+
+    /// Fold over all items, in key order
+    #[inline(always)]
+    pub fn fold<'a, 'env, 'txn, Tx, B, F>(
+        &'a self,
+        txn: &'txn Tx,
+        init: B,
+        f: F,
+    ) -> Result<B, error::Iter>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(B, KC::DItem, DC::DItem) -> B,
+    {
+        self.inner.fold(txn, init, f)
+    }
+
+    /// Fold over all items, in key order, stopping at the first error
+    #[inline(always)]
+    pub fn try_fold<'a, 'env, 'txn, Tx, B, F, E>(
+        &'a self,
+        txn: &'txn Tx,
+        init: B,
+        f: F,
+    ) -> Result<B, E>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut(B, KC::DItem, DC::DItem) -> Result<B, E>,
+        E: From<error::Iter>,
+    {
+        self.inner.try_fold(txn, init, f)
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
+    /// Collect all items, in key-then-value order.
+    /// Each duplicate value is collected with its key.
+    #[inline(always)]
+    pub fn iter_collect_vec<'env, Tx, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<Vec<(K, V)>, error::Iter>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        self.inner.iter_collect_vec(txn)
+    }
+
+    /// Collect all items into a map, eg. a [`std::collections::HashMap`] or
+    /// [`std::collections::BTreeMap`].
+    /// Keys with more than one value are handled according to
+    /// `duplicate_keys`.
+    #[inline(always)]
+    pub fn iter_collect_map<'env, Tx, M, K, V>(
+        &self,
+        txn: &Tx,
+        duplicate_keys: DuplicateKeys,
+    ) -> Result<M, error::IterCollectMap>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+        M: FromIterator<(K, V)>,
+    {
+        self.inner.iter_collect_map(txn, duplicate_keys)
+    }
+
+    /// Call `f` on each item, in key-then-value order, stopping at the
+    /// first error
+    #[inline(always)]
+    pub fn try_for_each_entry<'a, 'env, 'txn, Tx, F, E>(
+        &'a self,
+        txn: &'txn Tx,
+        f: F,
+    ) -> Result<(), E>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        F: FnMut((KC::DItem, DC::DItem)) -> Result<(), E>,
+        E: From<error::Iter>,
+    {
+        self.inner.try_for_each_entry(txn, f)
+    }
+}
@@ -0,0 +1,142 @@
+//! Consistency checks for related databases
+
+// Checks return the general db error, so that inconsistencies can be
+// reported alongside the read errors that interrupt a check
+#![allow(clippy::result_large_err)]
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{types::Bytes, BytesEncode};
+
+use crate::{
+    db::{
+        error::{
+            self,
+            inconsistent::{self, ByKey, ByValue},
+        },
+        RoDatabaseUnique,
+    },
+    Txn,
+};
+
+/// Determines how inconsistencies are handled when checking databases
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Return an error for the first inconsistency found
+    FailFast,
+    /// Collect inconsistencies into a report,
+    /// stopping once `max_errors` inconsistencies have been found
+    Report { max_errors: usize },
+}
+
+/// Inconsistencies found when checking databases
+#[derive(Debug, Default)]
+pub struct ConsistencyReport {
+    pub inconsistencies: Vec<inconsistent::Error>,
+    /// `true` if checking stopped early due to reaching the error limit
+    pub truncated: bool,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Record an inconsistency.
+    /// Returns `Ok(true)` if checking should continue.
+    fn record(
+        &mut self,
+        mode: Mode,
+        err: impl Into<inconsistent::Error>,
+    ) -> Result<bool, error::Error> {
+        match mode {
+            Mode::FailFast => Err(error::Error::Inconsistent(err.into())),
+            Mode::Report { max_errors } => {
+                if self.inconsistencies.len() >= max_errors {
+                    self.truncated = true;
+                    return Ok(false);
+                }
+                self.inconsistencies.push(err.into());
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// Check that `forward` and `reverse` form a bijection,
+/// ie. that `forward` maps `k` to `v` if and only if `reverse` maps `v` to
+/// `k`.
+/// Keys and values are compared by their encoded bytes.
+/// Entries that are missing from the other db are reported as
+/// [`inconsistent::Xor`], and entries that map to a different key or value
+/// in the other db are reported as [`inconsistent::Mismatch`].
+pub fn check_bijection<'a, 'env, 'txn, 'env_id, Tx, K, V, C0, C1>(
+    txn: &'txn Tx,
+    forward: &'a RoDatabaseUnique<'env_id, K, V, C0>,
+    reverse: &'a RoDatabaseUnique<'env_id, V, K, C1>,
+    mode: Mode,
+) -> Result<ConsistencyReport, error::Error>
+where
+    'env: 'txn,
+    Tx: Txn<'env, 'env_id>,
+    K: BytesEncode<'a>,
+    V: BytesEncode<'a>,
+    K::EItem: Sized,
+    V::EItem: Sized,
+{
+    let mut report = ConsistencyReport::default();
+    let forward_bytes = forward.remap_types::<Bytes, Bytes>();
+    let reverse_bytes = reverse.remap_types::<Bytes, Bytes>();
+    let mut forward_items = forward_bytes.iter(txn)?;
+    while let Some((key_bytes, value_bytes)) = forward_items.next()? {
+        let reverse_value = reverse_bytes.try_get(txn, value_bytes)?;
+        let err: inconsistent::Error = match reverse_value {
+            None => inconsistent::Xor::from_bytes(
+                value_bytes.to_vec(),
+                ByValue(forward),
+                ByKey(reverse),
+            )
+            .into(),
+            Some(reverse_value_bytes) if reverse_value_bytes != key_bytes => {
+                inconsistent::Mismatch::from_bytes(
+                    value_bytes.to_vec(),
+                    ByValue(forward),
+                    key_bytes.to_vec(),
+                    ByKey(reverse),
+                    reverse_value_bytes.to_vec(),
+                )
+                .into()
+            }
+            Some(_) => continue,
+        };
+        if !report.record(mode, err)? {
+            return Ok(report);
+        }
+    }
+    let mut reverse_items = reverse_bytes.iter(txn)?;
+    while let Some((key_bytes, value_bytes)) = reverse_items.next()? {
+        let forward_value = forward_bytes.try_get(txn, value_bytes)?;
+        let err: inconsistent::Error = match forward_value {
+            None => inconsistent::Xor::from_bytes(
+                value_bytes.to_vec(),
+                ByValue(reverse),
+                ByKey(forward),
+            )
+            .into(),
+            Some(forward_value_bytes) if forward_value_bytes != key_bytes => {
+                inconsistent::Mismatch::from_bytes(
+                    value_bytes.to_vec(),
+                    ByValue(reverse),
+                    key_bytes.to_vec(),
+                    ByKey(forward),
+                    forward_value_bytes.to_vec(),
+                )
+                .into()
+            }
+            Some(_) => continue,
+        };
+        if !report.record(mode, err)? {
+            return Ok(report);
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,296 @@
+//! Constraints enforced across related databases
+
+// Errors wrap any of the errors from writing to the primary and index
+// dbs, so they are as large as the largest of those
+#![allow(clippy::result_large_err)]
+
+use heed::{types::Bytes, BytesDecode, BytesEncode, DefaultComparator};
+
+use crate::{
+    db::{
+        error::{
+            inconsistent::{ByKey, ByValue, Mismatch, Xor},
+            Inconsistent,
+        },
+        DatabaseUnique, RoDatabaseUnique,
+    },
+    RwTxn,
+};
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::db;
+
+    #[derive(Debug, Error)]
+    #[error(
+        "Duplicate key `{}` in db `{db_name}` at `{db_path}`: key is used by id `{}`, attempted to use for id `{}`",
+        hex::encode(.key_bytes),
+        hex::encode(.existing_id),
+        hex::encode(.attempted_id),
+    )]
+    pub struct DuplicateKey {
+        pub(crate) db_name: String,
+        pub(crate) db_path: PathBuf,
+        /// Encoded id that the key is already used for
+        pub(crate) existing_id: Vec<u8>,
+        /// Encoded id that the key was attempted to be used for
+        pub(crate) attempted_id: Vec<u8>,
+        pub(crate) key_bytes: Vec<u8>,
+    }
+
+    /// Error type for [`super::Unique`] operations
+    #[derive(Debug, Error)]
+    pub enum Unique {
+        #[error(transparent)]
+        Delete(#[from] db::error::Delete),
+        #[error(transparent)]
+        DuplicateKey(#[from] DuplicateKey),
+        #[error(transparent)]
+        Inconsistent(#[from] db::error::Inconsistent),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+        #[error(transparent)]
+        TryGet(#[from] db::error::TryGet),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{DuplicateKey, Unique};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(DuplicateKey {
+            db_name: Plain,
+            db_path: PathStr,
+            existing_id: Hex,
+            attempted_id: Hex,
+            key_bytes: Hex,
+        });
+
+        serialize_error_enum!(Unique {
+            Delete,
+            DuplicateKey,
+            Inconsistent,
+            Put,
+            TryGet
+        });
+    }
+}
+
+/// A primary db, mapping ids to records, in which each record has a unique
+/// key.
+/// Keys are extracted from records with `F`, and a lookup db maps each key
+/// to the id of the record with that key.
+pub struct Unique<
+    'env_id,
+    PK,
+    PV,
+    LK,
+    F,
+    C0 = DefaultComparator,
+    C1 = DefaultComparator,
+> {
+    primary: DatabaseUnique<'env_id, PK, PV, C0>,
+    lookup: DatabaseUnique<'env_id, LK, PK, C1>,
+    extract: F,
+}
+
+impl<'env_id, PK, PV, LK, F, C0, C1> Unique<'env_id, PK, PV, LK, F, C0, C1> {
+    /// The lookup db should be empty, or consistent with the primary db.
+    pub fn new(
+        primary: DatabaseUnique<'env_id, PK, PV, C0>,
+        lookup: DatabaseUnique<'env_id, LK, PK, C1>,
+        extract: F,
+    ) -> Self {
+        Self {
+            primary,
+            lookup,
+            extract,
+        }
+    }
+
+    /// The primary db.
+    /// Writes must go through [`Self::insert`], [`Self::rename`] and
+    /// [`Self::remove`], so that the lookup db is kept in sync.
+    pub fn primary(&self) -> &RoDatabaseUnique<'env_id, PK, PV, C0> {
+        &self.primary
+    }
+
+    /// The lookup db
+    pub fn lookup(&self) -> &RoDatabaseUnique<'env_id, LK, PK, C1> {
+        &self.lookup
+    }
+
+    /// Remove the lookup entry for a record's key, checking that it
+    /// refers to the record's id
+    fn remove_lookup<Record, Key>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id_bytes: &[u8],
+        record: &Record,
+    ) -> Result<(), error::Unique>
+    where
+        PV: for<'a> BytesEncode<'a, EItem = Record>,
+        LK: for<'a> BytesEncode<'a, EItem = Key>,
+        F: Fn(&Record) -> Key,
+    {
+        let key = (self.extract)(record);
+        let key_bytes = LK::bytes_encode(&key)
+            .expect("Key should encode, as the record was previously written")
+            .into_owned();
+        let lookup_bytes = self.lookup.remap_types::<Bytes, Bytes>();
+        match lookup_bytes.try_get(rwtxn, &key_bytes)? {
+            None => {
+                let err = Xor::from_bytes(
+                    key_bytes,
+                    ByValue(&*self.primary),
+                    ByKey(&*self.lookup),
+                );
+                Err(Inconsistent::from(err).into())
+            }
+            Some(lookup_id_bytes) if lookup_id_bytes != id_bytes => {
+                let err = Mismatch::from_bytes(
+                    key_bytes,
+                    ByValue(&*self.primary),
+                    id_bytes.to_vec(),
+                    ByKey(&*self.lookup),
+                    lookup_id_bytes.to_vec(),
+                );
+                Err(Inconsistent::from(err).into())
+            }
+            Some(_) => {
+                let _deleted: bool = self.lookup.delete(rwtxn, &key)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Claim the key for a record in the lookup db
+    fn claim_lookup<Id, Key>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        key: &Key,
+    ) -> Result<(), error::Unique>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>
+            + for<'a> BytesDecode<'a, DItem = Id>,
+        LK: for<'a> BytesEncode<'a, EItem = Key>,
+    {
+        let Some(existing_id) = self.lookup.try_put(rwtxn, key, id)? else {
+            return Ok(());
+        };
+        let id_bytes = PK::bytes_encode(id)
+            .expect("Id should encode, as try_put succeeded")
+            .into_owned();
+        let existing_id = PK::bytes_encode(&existing_id)
+            .expect("Id should encode, as it was previously written")
+            .into_owned();
+        if existing_id == id_bytes {
+            return Ok(());
+        }
+        let key_bytes = LK::bytes_encode(key)
+            .expect("Key should encode, as try_put succeeded")
+            .into_owned();
+        Err(error::DuplicateKey {
+            db_name: self.lookup.name().to_owned(),
+            db_path: (*self.lookup.inner.inner.path).to_owned(),
+            existing_id,
+            attempted_id: id_bytes,
+            key_bytes,
+        }
+        .into())
+    }
+
+    /// Insert a record.
+    /// If a record already exists for the id, it is replaced.
+    /// Returns a [`error::DuplicateKey`] error if the record's key is used
+    /// by a different record.
+    pub fn insert<Id, Record, Key>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        record: &Record,
+    ) -> Result<(), error::Unique>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>
+            + for<'a> BytesDecode<'a, DItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>
+            + for<'a> BytesDecode<'a, DItem = Record>,
+        LK: for<'a> BytesEncode<'a, EItem = Key>,
+        F: Fn(&Record) -> Key,
+    {
+        let key = (self.extract)(record);
+        let () = self.claim_lookup(rwtxn, id, &key)?;
+        if let Some(old_record) = self.primary.try_get(rwtxn, id)? {
+            let id_bytes = PK::bytes_encode(id)
+                .expect("Id should encode, as try_put succeeded")
+                .into_owned();
+            let old_key = (self.extract)(&old_record);
+            let old_key_bytes = LK::bytes_encode(&old_key).expect(
+                "Key should encode, as the record was previously written",
+            );
+            let new_key_bytes = LK::bytes_encode(&key)
+                .expect("Key should encode, as try_put succeeded");
+            if old_key_bytes != new_key_bytes {
+                let () = self.remove_lookup(rwtxn, &id_bytes, &old_record)?;
+            }
+        }
+        let () = self.primary.put(rwtxn, id, record)?;
+        Ok(())
+    }
+
+    /// Replace an existing record, which may have a different key.
+    /// Returns `false` if the record did not exist.
+    /// Returns a [`error::DuplicateKey`] error if the new key is used by a
+    /// different record.
+    pub fn rename<Id, Record, Key>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        record: &Record,
+    ) -> Result<bool, error::Unique>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>
+            + for<'a> BytesDecode<'a, DItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>
+            + for<'a> BytesDecode<'a, DItem = Record>,
+        LK: for<'a> BytesEncode<'a, EItem = Key>,
+        F: Fn(&Record) -> Key,
+    {
+        if self.primary.try_get(rwtxn, id)?.is_none() {
+            return Ok(false);
+        }
+        let () = self.insert(rwtxn, id, record)?;
+        Ok(true)
+    }
+
+    /// Remove a record and its lookup entry.
+    /// Returns `false` if the record did not exist.
+    pub fn remove<Id, Record, Key>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+    ) -> Result<bool, error::Unique>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>
+            + for<'a> BytesDecode<'a, DItem = Record>,
+        LK: for<'a> BytesEncode<'a, EItem = Key>,
+        F: Fn(&Record) -> Key,
+    {
+        let Some(record) = self.primary.try_get(rwtxn, id)? else {
+            return Ok(false);
+        };
+        let id_bytes = PK::bytes_encode(id)
+            .expect("Id should encode, as try_get succeeded")
+            .into_owned();
+        let () = self.remove_lookup(rwtxn, &id_bytes, &record)?;
+        let _deleted: bool = self.primary.delete(rwtxn, id)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,197 @@
+//! Entry API for [`DatabaseUnique`]
+
+use heed::{BytesDecode, BytesEncode};
+
+use crate::{db::error, DatabaseUnique, RwTxn};
+
+/// A view into a single entry in a [`DatabaseUnique`], which may or may not
+/// exist.
+/// Modifications are written through the write txn immediately.
+/// See [`DatabaseUnique::entry`].
+pub enum Entry<'a, 'env, 'env_id, KC, DC, C, K, V>
+where
+    K: ?Sized,
+{
+    Occupied(OccupiedEntry<'a, 'env, 'env_id, KC, DC, C, K, V>),
+    Vacant(VacantEntry<'a, 'env, 'env_id, KC, DC, C, K>),
+}
+
+impl<'a, 'env, 'env_id, KC, DC, C, K, V>
+    Entry<'a, 'env, 'env_id, KC, DC, C, K, V>
+where
+    KC: for<'k> BytesEncode<'k, EItem = K>,
+    DC: for<'v> BytesEncode<'v, EItem = V> + for<'v> BytesDecode<'v, DItem = V>,
+    K: ?Sized,
+{
+    #[inline(always)]
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(occupied) => occupied.key,
+            Self::Vacant(vacant) => vacant.key,
+        }
+    }
+
+    /// Returns the stored value, if it exists
+    #[inline(always)]
+    pub fn get(&self) -> Option<&V> {
+        match self {
+            Self::Occupied(occupied) => Some(&occupied.value),
+            Self::Vacant(_) => None,
+        }
+    }
+
+    /// If the entry exists, modify the value and write it to the db.
+    pub fn and_modify<F>(mut self, f: F) -> Result<Self, error::Put>
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Self::Occupied(occupied) = &mut self {
+            let () = f(&mut occupied.value);
+            let () = occupied.db.put(
+                occupied.rwtxn,
+                occupied.key,
+                &occupied.value,
+            )?;
+        }
+        Ok(self)
+    }
+
+    /// If the entry does not exist, write the default value to the db.
+    /// Returns the value for the entry.
+    #[inline(always)]
+    pub fn or_insert(self, default: V) -> Result<V, error::Put> {
+        self.or_insert_with(|| default)
+    }
+
+    /// If the entry does not exist, write the result of the default
+    /// function to the db.
+    /// Returns the value for the entry.
+    pub fn or_insert_with<F>(self, default: F) -> Result<V, error::Put>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Self::Occupied(occupied) => Ok(occupied.value),
+            Self::Vacant(vacant) => {
+                let occupied = vacant.insert_with(default)?;
+                Ok(occupied.value)
+            }
+        }
+    }
+}
+
+/// An entry in a [`DatabaseUnique`] that exists.
+/// See [`Entry`].
+pub struct OccupiedEntry<'a, 'env, 'env_id, KC, DC, C, K, V>
+where
+    K: ?Sized,
+{
+    pub(crate) db: &'a DatabaseUnique<'env_id, KC, DC, C>,
+    pub(crate) rwtxn: &'a mut RwTxn<'env, 'env_id>,
+    pub(crate) key: &'a K,
+    pub(crate) value: V,
+}
+
+impl<'a, 'env, 'env_id, KC, DC, C, K, V>
+    OccupiedEntry<'a, 'env, 'env_id, KC, DC, C, K, V>
+where
+    KC: for<'k> BytesEncode<'k, EItem = K>,
+    DC: for<'v> BytesEncode<'v, EItem = V>,
+    K: ?Sized,
+{
+    #[inline(always)]
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    /// Returns the stored value
+    #[inline(always)]
+    pub fn get(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the stored value, consuming the entry
+    #[inline(always)]
+    pub fn into_value(self) -> V {
+        self.value
+    }
+
+    /// Write a new value to the db, returning the previous value
+    pub fn replace(&mut self, value: V) -> Result<V, error::Put> {
+        let () = self.db.put(self.rwtxn, self.key, &value)?;
+        Ok(std::mem::replace(&mut self.value, value))
+    }
+
+    /// Delete the entry from the db.
+    /// Returns the deleted value, and the entry, which is now vacant.
+    #[allow(clippy::type_complexity)]
+    pub fn delete(
+        self,
+    ) -> Result<(V, VacantEntry<'a, 'env, 'env_id, KC, DC, C, K>), error::Delete>
+    {
+        let deleted: bool = self.db.delete(self.rwtxn, self.key)?;
+        debug_assert!(deleted);
+        let vacant = VacantEntry {
+            db: self.db,
+            rwtxn: self.rwtxn,
+            key: self.key,
+        };
+        Ok((self.value, vacant))
+    }
+}
+
+/// An entry in a [`DatabaseUnique`] that does not exist.
+/// See [`Entry`].
+pub struct VacantEntry<'a, 'env, 'env_id, KC, DC, C, K>
+where
+    K: ?Sized,
+{
+    pub(crate) db: &'a DatabaseUnique<'env_id, KC, DC, C>,
+    pub(crate) rwtxn: &'a mut RwTxn<'env, 'env_id>,
+    pub(crate) key: &'a K,
+}
+
+impl<'a, 'env, 'env_id, KC, DC, C, K>
+    VacantEntry<'a, 'env, 'env_id, KC, DC, C, K>
+where
+    KC: for<'k> BytesEncode<'k, EItem = K>,
+    K: ?Sized,
+{
+    #[inline(always)]
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    /// Write the value to the db.
+    /// Returns the entry, which is now occupied.
+    #[inline(always)]
+    pub fn insert<V>(
+        self,
+        value: V,
+    ) -> Result<OccupiedEntry<'a, 'env, 'env_id, KC, DC, C, K, V>, error::Put>
+    where
+        DC: for<'v> BytesEncode<'v, EItem = V>,
+    {
+        self.insert_with(|| value)
+    }
+
+    /// Write the result of the function to the db.
+    /// Returns the entry, which is now occupied.
+    pub fn insert_with<V, F>(
+        self,
+        f: F,
+    ) -> Result<OccupiedEntry<'a, 'env, 'env_id, KC, DC, C, K, V>, error::Put>
+    where
+        DC: for<'v> BytesEncode<'v, EItem = V>,
+        F: FnOnce() -> V,
+    {
+        let value = f();
+        let () = self.db.put(self.rwtxn, self.key, &value)?;
+        Ok(OccupiedEntry {
+            db: self.db,
+            rwtxn: self.rwtxn,
+            key: self.key,
+            value,
+        })
+    }
+}
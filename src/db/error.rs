@@ -15,6 +15,14 @@ fn display_key_bytes(key_bytes: &Result<Vec<u8>, heed::BoxedError>) -> String {
     }
 }
 
+#[derive(Debug, Error)]
+#[error("Failed to clear db `{db_name}` at `{db_path}`")]
+pub struct Clear {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
 #[derive(Debug, Error)]
 #[error(
     "Failed to delete from db `{db_name}` at `{db_path}` ({})",
@@ -28,6 +36,17 @@ pub struct Delete {
     pub(crate) source: heed::Error,
 }
 
+#[derive(Debug, Error)]
+#[error(
+    "Duplicate key in db `{db_name}` at `{db_path}` (key: `{}`)",
+    hex::encode(.key_bytes)
+)]
+pub struct DuplicateKey {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes: Vec<u8>,
+}
+
 #[derive(Debug, Error)]
 #[error("Failed to read first item from db `{db_name}` at `{db_path}`")]
 pub struct First {
@@ -85,6 +104,114 @@ pub enum Iter {
     Item(#[from] IterItem),
 }
 
+/// Error type for collecting items into a map
+#[derive(Debug, Error)]
+pub enum IterCollectMap {
+    #[error(transparent)]
+    DuplicateKey(#[from] DuplicateKey),
+    #[error(transparent)]
+    Init(#[from] IterInit),
+    #[error(transparent)]
+    Item(#[from] IterItem),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateRange {
+    #[error(transparent)]
+    Init(#[from] RangeMutInit),
+    #[error(transparent)]
+    Item(#[from] RangeMutItem),
+    #[error(transparent)]
+    Put(#[from] Put),
+}
+
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+#[error(
+    "Failed to write line {line} of JSON Lines export of db `{db_name}` at `{db_path}`"
+)]
+pub struct WriteJsonl {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) line: u64,
+    pub(crate) source: serde_json::Error,
+}
+
+/// Error type for exporting a db as JSON Lines
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+pub enum ExportJsonl {
+    #[error(transparent)]
+    Init(#[from] IterInit),
+    #[error(transparent)]
+    Item(#[from] IterItem),
+    #[error(transparent)]
+    Write(#[from] WriteJsonl),
+}
+
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+#[error(
+    "Failed to read line {line} of JSON Lines import into db `{db_name}` at `{db_path}`"
+)]
+pub struct ReadJsonl {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) line: u64,
+    pub(crate) source: std::io::Error,
+}
+
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+#[error(
+    "Failed to parse line {line} of JSON Lines import into db `{db_name}` at `{db_path}`"
+)]
+pub struct ParseJsonl {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) line: u64,
+    pub(crate) source: serde_json::Error,
+}
+
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+#[error("Failed to write line {line} of JSON Lines import")]
+pub struct PutJsonl {
+    pub(crate) line: u64,
+    pub(crate) source: Put,
+}
+
+/// Error type for importing JSON Lines into a db
+#[cfg(feature = "jsonl")]
+#[derive(Debug, Error)]
+pub enum ImportJsonl {
+    #[error(transparent)]
+    Parse(#[from] ParseJsonl),
+    #[error(transparent)]
+    Put(#[from] PutJsonl),
+    #[error(transparent)]
+    Read(#[from] ReadJsonl),
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to read last item from db `{db_name}` at `{db_path}`")]
+pub struct Last {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+/// Error type for [`super::DatabaseUnique::increment`]
+#[derive(Debug, Error)]
+pub enum Increment {
+    #[error(transparent)]
+    Overflow(#[from] Overflow),
+    #[error(transparent)]
+    Put(#[from] Put),
+    #[error(transparent)]
+    TryGet(#[from] TryGet),
+}
+
 #[derive(Debug, Error)]
 #[error("Failed to read length for db `{db_name}` at `{db_path}`")]
 pub struct Len {
@@ -93,6 +220,53 @@ pub struct Len {
     pub(crate) source: heed::Error,
 }
 
+#[derive(Debug, Error)]
+#[error("Length of db `{db_name}` at `{db_path}` ({len}) exceeds `usize::MAX`")]
+pub struct LenOverflow {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) len: u64,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Incrementing value ({value}) by {by} overflows, in db `{db_name}` at `{db_path}` (key: {})",
+    hex::encode(.key_bytes)
+)]
+pub struct Overflow {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes: Vec<u8>,
+    pub(crate) value: i64,
+    pub(crate) by: i64,
+}
+
+/// Error type for reading the length of a db as a `usize`
+#[derive(Debug, Error)]
+pub enum LenUsize {
+    #[error(transparent)]
+    Len(#[from] Len),
+    #[error(transparent)]
+    Overflow(#[from] LenOverflow),
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to read stats for db `{db_name}` at `{db_path}`")]
+pub struct Stat {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+/// Error type for [`super::DatabaseUnique::swap_keys`]
+#[derive(Debug, Error)]
+pub enum SwapKeys {
+    #[error(transparent)]
+    Put(#[from] Put),
+    #[error(transparent)]
+    TryGet(#[from] TryGet),
+}
+
 fn display_value_bytes(
     value_bytes: &Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
 ) -> String {
@@ -106,6 +280,91 @@ fn display_value_bytes(
     }
 }
 
+/// Returned by [`super::RoDatabaseUnique::par_scan`] if the env was not
+/// opened with [`heed::EnvFlags::NO_TLS`]
+#[cfg(feature = "rayon")]
+#[derive(Debug, Error)]
+#[error(
+    "Parallel scan of db `{db_name}` at `{db_path}` requires an env opened with `NO_TLS`"
+)]
+pub struct ParScanRequiresNoTls {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+}
+
+/// Error type for [`super::RoDatabaseUnique::par_scan`]
+#[cfg(feature = "rayon")]
+#[derive(Debug, Error)]
+pub enum ParScan {
+    #[error(transparent)]
+    IterInit(#[from] IterInit),
+    #[error(transparent)]
+    IterItem(#[from] IterItem),
+    #[error(transparent)]
+    Len(#[from] Len),
+    #[error(transparent)]
+    RangeInit(#[from] RangeInit),
+    #[error(transparent)]
+    ReadTxn(#[from] crate::env::error::ReadTxn),
+    #[error(transparent)]
+    RequiresNoTls(#[from] ParScanRequiresNoTls),
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to pop first item from db `{db_name}` at `{db_path}`")]
+pub struct PopFirst {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid put flags `0x{bits:x}` for db `{db_name}` at `{db_path}`")]
+pub struct InvalidPutFlags {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) bits: u32,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Key already exists in db `{db_name}` at `{db_path}` ({})",
+    display_key_bytes(.key_bytes)
+)]
+pub struct KeyExists {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes:
+        Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
+}
+
+/// The encoded value is larger than the free space in the env's map.
+/// See [`super::DatabaseUnique::check_value_size`].
+#[derive(Debug, Error)]
+#[error(
+    "Value of {size} bytes exceeds the free space in the map ({free} of {map_size} bytes free)"
+)]
+pub struct ValueTooLarge {
+    pub(crate) size: usize,
+    pub(crate) free: u64,
+    pub(crate) map_size: u64,
+}
+
+/// Cause of a [`Put`] error.
+///
+/// The source of a [`Put`] error was previously always a [`heed::Error`].
+/// Code that downcasts the source of a [`Put`] error to a [`heed::Error`]
+/// must now downcast to a `PutSource`, and match on [`PutSource::Heed`].
+#[derive(Debug, Error)]
+pub enum PutSource {
+    /// Error returned by heed
+    #[error(transparent)]
+    Heed(#[from] heed::Error),
+    /// Rejected by [`super::DatabaseUnique::check_value_size`]
+    #[error(transparent)]
+    ValueTooLarge(#[from] ValueTooLarge),
+}
+
 #[derive(Debug, Error)]
 #[error(
     "Failed to write to db `{db_name}` at `{db_path}` ({}, {})",
@@ -115,9 +374,138 @@ fn display_value_bytes(
 pub struct Put {
     pub(crate) db_name: String,
     pub(crate) db_path: PathBuf,
+    // Boxed to keep `Result<_, Put>`, and the many errors that wrap `Put`,
+    // small
     pub(crate) key_bytes:
-        Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
+        Box<Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>>,
     pub(crate) value_bytes:
+        Box<Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>>,
+    pub(crate) source: PutSource,
+}
+
+/// Error type for [`super::DatabaseUnique::put_with_flags`] and
+/// [`super::DatabaseDup::put_with_flags`]
+#[derive(Debug, Error)]
+pub enum PutWithFlags {
+    /// The flags are not valid for the db
+    #[error(transparent)]
+    InvalidFlags(#[from] InvalidPutFlags),
+    /// LMDB returned `MDB_KEYEXIST`
+    #[error(transparent)]
+    KeyExists(#[from] KeyExists),
+    #[error(transparent)]
+    Put(#[from] Put),
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to write reserved value to db `{db_name}` at `{db_path}` ({})",
+    display_key_bytes(.key_bytes)
+)]
+pub struct PutReservedWrite {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes:
+        Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
+    pub(crate) source: heed::Error,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Only {written} of {reserved} reserved bytes were written to db `{db_name}` at `{db_path}` (key: {})",
+    hex::encode(.key_bytes)
+)]
+pub struct ReservedUnderrun {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes: Vec<u8>,
+    pub(crate) reserved: usize,
+    pub(crate) written: usize,
+}
+
+/// Error type for [`super::DatabaseUnique::put_reserved`]
+#[derive(Debug, Error)]
+pub enum PutReserved {
+    #[error(transparent)]
+    ReservedUnderrun(#[from] ReservedUnderrun),
+    #[error(transparent)]
+    Write(#[from] PutReservedWrite),
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to initialize read-only range iterator for db `{db_name}` at `{db_path}`"
+)]
+pub struct RangeInit {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to initialize read-write range iterator for db `{db_name}` at `{db_path}`"
+)]
+pub struct RangeMutInit {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to read item of read-write range iterator for db `{db_name}` at `{db_path}`")]
+pub struct RangeMutItem {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) source: heed::Error,
+}
+
+/// Error type for [`super::DatabaseUnique::rename_key`]
+#[derive(Debug, Error)]
+pub enum RenameKey {
+    #[error(transparent)]
+    Delete(#[from] Delete),
+    /// The new key already exists, and overwriting was not enabled
+    #[error(transparent)]
+    DuplicateKey(#[from] DuplicateKey),
+    #[error(transparent)]
+    Put(#[from] Put),
+    #[error(transparent)]
+    TryGet(#[from] TryGet),
+}
+
+#[derive(Debug, Error)]
+pub enum ScanChunked {
+    #[error(transparent)]
+    Init(#[from] RangeInit),
+    #[error(transparent)]
+    Item(#[from] IterItem),
+    #[error(transparent)]
+    ReadTxn(#[from] crate::env::error::ReadTxn),
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to search db `{db_name}` at `{db_path}` ({})",
+    display_key_bytes(.key_bytes)
+)]
+pub struct Search {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes:
+        Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
+    pub(crate) source: heed::Error,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to take from db `{db_name}` at `{db_path}` ({})",
+    display_key_bytes(.key_bytes)
+)]
+pub struct Take {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+    pub(crate) key_bytes:
         Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
     pub(crate) source: heed::Error,
 }
@@ -150,6 +538,22 @@ pub enum Get {
     },
 }
 
+/// Error type for [`super::DatabaseUnique::insert`]
+#[derive(Debug, Error)]
+pub enum Insert {
+    #[error(
+        "Key already exists in db `{db_name}` at `{db_path}` (key: {})",
+        hex::encode(.key_bytes)
+    )]
+    AlreadyExists {
+        db_name: String,
+        db_path: PathBuf,
+        key_bytes: Vec<u8>,
+    },
+    #[error(transparent)]
+    Put(#[from] Put),
+}
+
 pub mod inconsistent {
     use heed::BytesEncode;
     use thiserror::Error;
@@ -238,8 +642,21 @@ pub mod inconsistent {
                 <ByDb0::BE as BytesEncode>::bytes_encode(on).expect(
                     "Encoding should succeed when constructing inconsistent DBs error"
                 );
+            Self::from_bytes(on_bytes.to_vec(), db0, db1)
+        }
+
+        /// Construct from bytes that are already encoded
+        fn from_bytes<'a, ByDb0, ByDb1>(
+            on: Vec<u8>,
+            db0: ByDb0,
+            db1: ByDb1,
+        ) -> Self
+        where
+            ByDb0: ByKeyOrValue<'a>,
+            ByDb1: ByKeyOrValue<'a>,
+        {
             Self {
-                on: on_bytes.to_vec(),
+                on,
                 db0_by: ByDb0::KEY_OR_VALUE,
                 db0_name: db0.into_inner().name().to_owned(),
                 db1_by: ByDb1::KEY_OR_VALUE,
@@ -277,6 +694,89 @@ pub mod inconsistent {
         }
     }
 
+    /// Maximum number of bytes to display for each stored value in a
+    /// [`Mismatch`] error
+    const MISMATCH_DISPLAY_MAX_BYTES: usize = 32;
+
+    fn display_truncated(bytes: &[u8]) -> String {
+        if bytes.len() <= MISMATCH_DISPLAY_MAX_BYTES {
+            hex::encode(bytes)
+        } else {
+            format!(
+                "{}... ({} bytes)",
+                hex::encode(&bytes[..MISMATCH_DISPLAY_MAX_BYTES]),
+                bytes.len()
+            )
+        }
+    }
+
+    #[derive(Debug)]
+    struct MismatchInner {
+        inner: Inner,
+        db0_stored: Vec<u8>,
+        db1_stored: Vec<u8>,
+    }
+
+    /// Both dbs contain an entry, but the stored values disagree
+    #[derive(Debug, Error)]
+    #[error(
+        "Inconsistent dbs: `{}` exists in db `{}` (as {}) with `{}`, and in db `{}` (as {}) with `{}`",
+        hex::encode(&.0.inner.on),
+        .0.inner.db0_name,
+        .0.inner.db0_by,
+        display_truncated(&.0.db0_stored),
+        .0.inner.db1_name,
+        .0.inner.db1_by,
+        display_truncated(&.0.db1_stored),
+    )]
+    #[repr(transparent)]
+    pub struct Mismatch(MismatchInner);
+
+    impl Mismatch {
+        /// `db0_stored` and `db1_stored` are the encoded bytes stored
+        /// alongside `on` in each db.
+        #[inline(always)]
+        pub fn new<'a, ByDb0, ByDb1>(
+            on: &'a <ByDb0::BE as BytesEncode<'a>>::EItem,
+            db0: ByDb0,
+            db0_stored: &[u8],
+            db1: ByDb1,
+            db1_stored: &[u8],
+        ) -> Self
+        where
+            ByDb0: ByKeyOrValue<'a>,
+            ByDb1: ByKeyOrValue<'a>,
+            ByDb1::BE:
+                BytesEncode<'a, EItem = <ByDb0::BE as BytesEncode<'a>>::EItem>,
+        {
+            Self(MismatchInner {
+                inner: Inner::new(on, db0, db1),
+                db0_stored: db0_stored.to_vec(),
+                db1_stored: db1_stored.to_vec(),
+            })
+        }
+
+        /// Construct from bytes that are already encoded
+        #[inline(always)]
+        pub(crate) fn from_bytes<'a, ByDb0, ByDb1>(
+            on: Vec<u8>,
+            db0: ByDb0,
+            db0_stored: Vec<u8>,
+            db1: ByDb1,
+            db1_stored: Vec<u8>,
+        ) -> Self
+        where
+            ByDb0: ByKeyOrValue<'a>,
+            ByDb1: ByKeyOrValue<'a>,
+        {
+            Self(MismatchInner {
+                inner: Inner::from_bytes(on, db0, db1),
+                db0_stored,
+                db1_stored,
+            })
+        }
+    }
+
     #[derive(Debug, Error)]
     #[error(
         "Inconsistent dbs: `{}` does not exist in db `{}` (as {}) or in db `{}` (as {})",
@@ -333,6 +833,114 @@ pub mod inconsistent {
         {
             Self(Inner::new(on, db0, db1))
         }
+
+        /// Construct from bytes that are already encoded
+        #[inline(always)]
+        pub(crate) fn from_bytes<'a, ByDb0, ByDb1>(
+            on: Vec<u8>,
+            db0: ByDb0,
+            db1: ByDb1,
+        ) -> Self
+        where
+            ByDb0: ByKeyOrValue<'a>,
+            ByDb1: ByKeyOrValue<'a>,
+        {
+            Self(Inner::from_bytes(on, db0, db1))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+        use super::{And, Error, Inner, Mismatch, Nor, Xor};
+        use crate::serde_utils::{
+            serialize_error_enum, DisplayStr, Hex, Plain,
+        };
+
+        impl Inner {
+            fn serialize_as<S>(
+                &self,
+                kind: &'static str,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct(kind, 6)?;
+                let () = self.serialize_fields(kind, &mut state)?;
+                state.end()
+            }
+
+            fn serialize_fields<S>(
+                &self,
+                kind: &'static str,
+                state: &mut S,
+            ) -> Result<(), S::Error>
+            where
+                S: serde::ser::SerializeStruct,
+            {
+                let () = state.serialize_field("kind", kind)?;
+                let () = state.serialize_field("on", &Hex(&self.on))?;
+                let () = state
+                    .serialize_field("db0_by", &DisplayStr(&self.db0_by))?;
+                let () = state
+                    .serialize_field("db0_name", &Plain(&self.db0_name))?;
+                let () = state
+                    .serialize_field("db1_by", &DisplayStr(&self.db1_by))?;
+                state.serialize_field("db1_name", &Plain(&self.db1_name))
+            }
+        }
+
+        impl Serialize for And {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize_as("And", serializer)
+            }
+        }
+
+        impl Serialize for Mismatch {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state = serializer.serialize_struct("Mismatch", 8)?;
+                let () =
+                    self.0.inner.serialize_fields("Mismatch", &mut state)?;
+                let () = state
+                    .serialize_field("db0_stored", &Hex(&self.0.db0_stored))?;
+                let () = state
+                    .serialize_field("db1_stored", &Hex(&self.0.db1_stored))?;
+                state.end()
+            }
+        }
+
+        impl Serialize for Nor {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize_as("Nor", serializer)
+            }
+        }
+
+        impl Serialize for Xor {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize_as("Xor", serializer)
+            }
+        }
+
+        serialize_error_enum!(Error {
+            And,
+            Mismatch,
+            Nor,
+            Xor
+        });
     }
 
     #[derive(Debug, Error)]
@@ -340,6 +948,8 @@ pub mod inconsistent {
         #[error(transparent)]
         And(#[from] And),
         #[error(transparent)]
+        Mismatch(#[from] Mismatch),
+        #[error(transparent)]
         Nor(#[from] Nor),
         #[error(transparent)]
         Xor(#[from] Xor),
@@ -348,20 +958,40 @@ pub mod inconsistent {
 
 pub use inconsistent::Error as Inconsistent;
 
+#[cfg(feature = "observe")]
+#[derive(Debug, Error)]
+#[error("Watch channel closed for db `{db_name}` at `{db_path}`")]
+pub struct WatchClosed {
+    pub(crate) db_name: String,
+    pub(crate) db_path: PathBuf,
+}
+
 /// General error type for DB operations
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error(transparent)]
+    Clear(#[from] Clear),
     #[error(transparent)]
     Delete(#[from] Delete),
     #[error(transparent)]
+    DuplicateKey(#[from] DuplicateKey),
+    #[error(transparent)]
     First(#[from] First),
     #[error(transparent)]
     Get(#[from] Get),
     #[error(transparent)]
+    Increment(#[from] Increment),
+    #[error(transparent)]
     Inconsistent(#[from] inconsistent::Error),
     #[error(transparent)]
+    Insert(#[from] Insert),
+    #[error(transparent)]
+    InvalidPutFlags(#[from] InvalidPutFlags),
+    #[error(transparent)]
     Iter(#[from] Iter),
     #[error(transparent)]
+    IterCollectMap(#[from] IterCollectMap),
+    #[error(transparent)]
     IterDuplicatesInit(#[from] IterDuplicatesInit),
     #[error(transparent)]
     IterDuplicates(#[from] IterDuplicates),
@@ -370,9 +1000,435 @@ pub enum Error {
     #[error(transparent)]
     IterItem(#[from] IterItem),
     #[error(transparent)]
+    KeyExists(#[from] KeyExists),
+    #[error(transparent)]
+    Last(#[from] Last),
+    #[error(transparent)]
     Len(#[from] Len),
     #[error(transparent)]
+    LenOverflow(#[from] LenOverflow),
+    #[error(transparent)]
+    LenUsize(#[from] LenUsize),
+    #[error(transparent)]
+    Overflow(#[from] Overflow),
+    #[error(transparent)]
+    PopFirst(#[from] PopFirst),
+    #[error(transparent)]
     Put(#[from] Put),
     #[error(transparent)]
+    PutReserved(#[from] PutReserved),
+    #[error(transparent)]
+    PutWithFlags(#[from] PutWithFlags),
+    #[error(transparent)]
+    RangeInit(#[from] RangeInit),
+    #[error(transparent)]
+    RangeMutInit(#[from] RangeMutInit),
+    #[error(transparent)]
+    RangeMutItem(#[from] RangeMutItem),
+    #[error(transparent)]
+    RenameKey(#[from] RenameKey),
+    #[error(transparent)]
+    ReservedUnderrun(#[from] ReservedUnderrun),
+    #[error(transparent)]
+    ScanChunked(#[from] ScanChunked),
+    #[error(transparent)]
+    Search(#[from] Search),
+    #[error(transparent)]
+    Stat(#[from] Stat),
+    #[error(transparent)]
+    SwapKeys(#[from] SwapKeys),
+    #[error(transparent)]
+    Take(#[from] Take),
+    #[error(transparent)]
     TryGet(#[from] TryGet),
+    #[error(transparent)]
+    UpdateRange(#[from] UpdateRange),
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+    #[cfg(feature = "observe")]
+    use super::WatchClosed;
+    use super::{
+        Clear, Delete, DuplicateKey, Error, First, Get, Increment, Insert,
+        InvalidPutFlags, Iter, IterCollectMap, IterDuplicates,
+        IterDuplicatesInit, IterInit, IterItem, KeyExists, Last, Len,
+        LenOverflow, LenUsize, Overflow, PopFirst, Put, PutReserved,
+        PutReservedWrite, PutWithFlags, RangeInit, RangeMutInit, RangeMutItem,
+        RenameKey, ReservedUnderrun, ScanChunked, Search, Stat, SwapKeys, Take,
+        TryGet, UpdateRange, ValueTooLarge,
+    };
+    #[cfg(feature = "jsonl")]
+    use super::{
+        ExportJsonl, ImportJsonl, ParseJsonl, PutJsonl, ReadJsonl, WriteJsonl,
+    };
+    #[cfg(feature = "rayon")]
+    use super::{ParScan, ParScanRequiresNoTls};
+    use crate::serde_utils::{
+        serialize_error_enum, serialize_error_struct, Hex, PathStr,
+    };
+
+    serialize_error_struct!(Clear {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(Delete {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(DuplicateKey {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: Hex,
+    });
+
+    serialize_error_struct!(First {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(InvalidPutFlags {
+        db_name: Plain,
+        db_path: PathStr,
+        bits: Plain,
+    });
+
+    serialize_error_struct!(IterDuplicatesInit {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(IterInit {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(IterItem {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_enum!(IterDuplicates { Init, Item });
+
+    serialize_error_enum!(Iter {
+        DuplicatesInit,
+        Init,
+        Item
+    });
+
+    serialize_error_enum!(IterCollectMap {
+        DuplicateKey,
+        Init,
+        Item
+    });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_struct!(WriteJsonl {
+        db_name: Plain,
+        db_path: PathStr,
+        line: Plain,
+        source: DisplayStr,
+    });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_enum!(ExportJsonl { Init, Item, Write });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_struct!(ReadJsonl {
+        db_name: Plain,
+        db_path: PathStr,
+        line: Plain,
+        source: DisplayStr,
+    });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_struct!(ParseJsonl {
+        db_name: Plain,
+        db_path: PathStr,
+        line: Plain,
+        source: DisplayStr,
+    });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_struct!(PutJsonl {
+        line: Plain,
+        source: Plain,
+    });
+
+    #[cfg(feature = "jsonl")]
+    serialize_error_enum!(ImportJsonl { Parse, Put, Read });
+
+    serialize_error_struct!(KeyExists {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+    });
+
+    serialize_error_struct!(Last {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(Len {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(LenOverflow {
+        db_name: Plain,
+        db_path: PathStr,
+        len: Plain,
+    });
+
+    serialize_error_enum!(LenUsize { Len, Overflow });
+
+    serialize_error_enum!(Increment {
+        Overflow,
+        Put,
+        TryGet
+    });
+
+    serialize_error_struct!(Overflow {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: Hex,
+        value: Plain,
+        by: Plain,
+    });
+
+    serialize_error_struct!(Stat {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    #[cfg(feature = "rayon")]
+    serialize_error_struct!(ParScanRequiresNoTls {
+        db_name: Plain,
+        db_path: PathStr,
+    });
+
+    #[cfg(feature = "rayon")]
+    serialize_error_enum!(ParScan {
+        IterInit,
+        IterItem,
+        Len,
+        RangeInit,
+        ReadTxn,
+        RequiresNoTls
+    });
+
+    serialize_error_struct!(PopFirst {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(ValueTooLarge {
+        size: Plain,
+        free: Plain,
+        map_size: Plain,
+    });
+
+    serialize_error_struct!(Put {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        value_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_enum!(PutReserved {
+        ReservedUnderrun,
+        Write
+    });
+
+    serialize_error_struct!(PutReservedWrite {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_enum!(PutWithFlags {
+        InvalidFlags,
+        KeyExists,
+        Put
+    });
+
+    serialize_error_struct!(RangeInit {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(RangeMutInit {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(RangeMutItem {
+        db_name: Plain,
+        db_path: PathStr,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(Search {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(Take {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    serialize_error_struct!(TryGet {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: EncodeResult,
+        source: DisplayStr,
+    });
+
+    impl Serialize for Get {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self {
+                Self::TryGet(err) => err.serialize(serializer),
+                Self::MissingValue {
+                    db_name,
+                    db_path,
+                    key_bytes,
+                } => {
+                    let mut state =
+                        serializer.serialize_struct("MissingValue", 4)?;
+                    let () = state.serialize_field("kind", "MissingValue")?;
+                    let () = state.serialize_field("db_name", db_name)?;
+                    let () =
+                        state.serialize_field("db_path", &PathStr(db_path))?;
+                    let () =
+                        state.serialize_field("key_bytes", &Hex(key_bytes))?;
+                    state.end()
+                }
+            }
+        }
+    }
+
+    impl Serialize for Insert {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self {
+                Self::AlreadyExists {
+                    db_name,
+                    db_path,
+                    key_bytes,
+                } => {
+                    let mut state =
+                        serializer.serialize_struct("AlreadyExists", 4)?;
+                    let () = state.serialize_field("kind", "AlreadyExists")?;
+                    let () = state.serialize_field("db_name", db_name)?;
+                    let () =
+                        state.serialize_field("db_path", &PathStr(db_path))?;
+                    let () =
+                        state.serialize_field("key_bytes", &Hex(key_bytes))?;
+                    state.end()
+                }
+                Self::Put(err) => err.serialize(serializer),
+            }
+        }
+    }
+
+    serialize_error_enum!(ScanChunked {
+        Init,
+        Item,
+        ReadTxn
+    });
+
+    serialize_error_enum!(RenameKey {
+        Delete,
+        DuplicateKey,
+        Put,
+        TryGet
+    });
+
+    serialize_error_struct!(ReservedUnderrun {
+        db_name: Plain,
+        db_path: PathStr,
+        key_bytes: Hex,
+        reserved: Plain,
+        written: Plain,
+    });
+
+    serialize_error_enum!(SwapKeys { Put, TryGet });
+
+    serialize_error_enum!(UpdateRange { Init, Item, Put });
+
+    #[cfg(feature = "observe")]
+    serialize_error_struct!(WatchClosed {
+        db_name: Plain,
+        db_path: PathStr,
+    });
+
+    serialize_error_enum!(Error {
+        Clear,
+        Delete,
+        DuplicateKey,
+        First,
+        Get,
+        Increment,
+        Inconsistent,
+        Insert,
+        InvalidPutFlags,
+        Iter,
+        IterCollectMap,
+        IterDuplicatesInit,
+        IterDuplicates,
+        IterInit,
+        IterItem,
+        KeyExists,
+        Last,
+        Len,
+        LenOverflow,
+        LenUsize,
+        Overflow,
+        PopFirst,
+        Put,
+        PutReserved,
+        PutWithFlags,
+        RangeInit,
+        RangeMutInit,
+        RangeMutItem,
+        RenameKey,
+        ReservedUnderrun,
+        ScanChunked,
+        Search,
+        Stat,
+        SwapKeys,
+        Take,
+        TryGet,
+        UpdateRange,
+    });
 }
@@ -0,0 +1,188 @@
+//! Databases with expiring entries
+
+// Operations span three dbs, and return the general db error, which is
+// as large as the largest db error
+#![allow(clippy::result_large_err)]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{
+    byteorder::BigEndian,
+    types::{Bytes, U64},
+    BytesDecode, BytesEncode, Comparator, DefaultComparator,
+};
+
+use crate::{
+    db::{error, DatabaseDup, DatabaseUnique, RoDatabaseUnique},
+    env, Env, RwTxn, Txn,
+};
+
+/// Deadlines are stored as milliseconds since the unix epoch
+type Deadline = U64<BigEndian>;
+
+/// Milliseconds since the unix epoch, saturating
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis();
+    u64::try_from(millis).unwrap_or(u64::MAX)
+}
+
+/// A db in which each entry expires after a TTL.
+/// Expired entries are treated as absent by [`Self::try_get`], and are
+/// deleted by [`Self::purge_expired`].
+/// The current time is always provided by the caller.
+///
+/// Internally, a deadlines db maps each encoded key to its deadline, and an
+/// expiries db maps each deadline to the encoded keys that expire at that
+/// deadline.
+/// As the expiries db has duplicate keys, encoded keys must not be larger
+/// than the max key size of the env.
+pub struct ExpiringDatabase<'env_id, KC, DC, C = DefaultComparator> {
+    data: DatabaseUnique<'env_id, KC, DC, C>,
+    deadlines: DatabaseUnique<'env_id, Bytes, Deadline>,
+    expiries: DatabaseDup<'env_id, Deadline, Bytes>,
+}
+
+impl<'env_id, KC, DC, C> ExpiringDatabase<'env_id, KC, DC, C> {
+    /// Create the db, with name `name`, and the internal dbs, with names
+    /// `{name}_deadlines` and `{name}_expiries`.
+    pub fn create(
+        env: &Env<'env_id>,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        name: &str,
+    ) -> Result<Self, env::error::CreateDb>
+    where
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        let data = DatabaseUnique::create(env, rwtxn, name)?;
+        let deadlines =
+            DatabaseUnique::create(env, rwtxn, &format!("{name}_deadlines"))?;
+        let expiries =
+            DatabaseDup::create(env, rwtxn, &format!("{name}_expiries"))?;
+        Ok(Self {
+            data,
+            deadlines,
+            expiries,
+        })
+    }
+
+    /// The underlying data db.
+    /// Reads from the data db do not check whether entries have expired.
+    pub fn data(&self) -> &RoDatabaseUnique<'env_id, KC, DC, C> {
+        &self.data
+    }
+
+    /// Delete the deadline for an encoded key, if any
+    fn delete_deadline(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_bytes: &[u8],
+    ) -> Result<(), error::Error> {
+        if let Some(deadline) = self.deadlines.try_get(rwtxn, key_bytes)? {
+            let _deleted: bool = self.deadlines.delete(rwtxn, key_bytes)?;
+            let _deleted: bool =
+                self.expiries.delete_one(rwtxn, &deadline, key_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Write an entry that expires after `ttl` has elapsed since `now`.
+    /// If an entry already exists for the key, it is replaced, along with
+    /// its deadline.
+    pub fn put_with_ttl<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        value: &'a DC::EItem,
+        ttl: Duration,
+        now: SystemTime,
+    ) -> Result<(), error::Error>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let () = self.data.put(rwtxn, key, value)?;
+        let key_bytes = KC::bytes_encode(key)
+            .expect("Key should encode, as put succeeded")
+            .into_owned();
+        let () = self.delete_deadline(rwtxn, &key_bytes)?;
+        let deadline = millis_since_epoch(now)
+            .saturating_add(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX));
+        let () = self.deadlines.put(rwtxn, &key_bytes, &deadline)?;
+        let () = self.expiries.put(rwtxn, &deadline, &key_bytes)?;
+        Ok(())
+    }
+
+    /// Get the value for a key, if it exists and has not expired at `now`.
+    /// Entries expire once `now` is at or after their deadline.
+    pub fn try_get<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+        now: SystemTime,
+    ) -> Result<Option<DC::DItem>, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        let Some(value) = self.data.try_get(txn, key)? else {
+            return Ok(None);
+        };
+        let key_bytes = KC::bytes_encode(key)
+            .expect("Key should encode, as try_get succeeded");
+        match self.deadlines.try_get(txn, &key_bytes)? {
+            Some(deadline) if deadline <= millis_since_epoch(now) => Ok(None),
+            Some(_) | None => Ok(Some(value)),
+        }
+    }
+
+    /// Delete an entry and its deadline.
+    /// Returns `false` if the entry did not exist.
+    pub fn delete<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+    ) -> Result<bool, error::Error>
+    where
+        KC: BytesEncode<'a>,
+    {
+        if !self.data.delete(rwtxn, key)? {
+            return Ok(false);
+        }
+        let key_bytes = KC::bytes_encode(key)
+            .expect("Key should encode, as delete succeeded")
+            .into_owned();
+        let () = self.delete_deadline(rwtxn, &key_bytes)?;
+        Ok(true)
+    }
+
+    /// Delete each entry that has expired at `now`.
+    /// Returns the number of entries that were deleted.
+    pub fn purge_expired(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        now: SystemTime,
+    ) -> Result<u64, error::Error> {
+        let now = millis_since_epoch(now);
+        let expired: Vec<(u64, Vec<u8>)> = self
+            .expiries
+            .range(rwtxn, &(..=now))?
+            .map(|(deadline, key_bytes)| Ok((deadline, key_bytes.to_vec())))
+            .collect()?;
+        let data = self.data.inner.inner.remap_types::<Bytes, Bytes>();
+        for (deadline, key_bytes) in &expired {
+            let _deleted: bool = data.delete(rwtxn, key_bytes)?;
+            let _deleted: bool = self.deadlines.delete(rwtxn, key_bytes)?;
+            let _deleted: bool =
+                self.expiries.delete_one(rwtxn, deadline, key_bytes)?;
+        }
+        Ok(expired.len() as u64)
+    }
+}
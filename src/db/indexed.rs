@@ -0,0 +1,199 @@
+//! Primary databases with secondary indexes
+
+// Operations span the primary and index dbs, and return the general db
+// error, which is as large as the largest db error
+#![allow(clippy::result_large_err)]
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{BytesDecode, BytesEncode, DefaultComparator};
+
+use crate::{
+    db::{
+        error::{
+            self,
+            inconsistent::{ByKey, ByValue, Xor},
+        },
+        DatabaseDup, DatabaseUnique, RoDatabaseDup, RoDatabaseUnique,
+    },
+    RwTxn, Txn,
+};
+
+/// A primary db, mapping ids to records, with a secondary index mapping
+/// field values to the ids of each record with that field value.
+/// Field values are extracted from records with `F`.
+/// The index is kept in sync with the primary db by each write, within the
+/// caller's txn.
+pub struct Indexed<
+    'env_id,
+    PK,
+    PV,
+    IK,
+    F,
+    C0 = DefaultComparator,
+    C1 = DefaultComparator,
+> {
+    primary: DatabaseUnique<'env_id, PK, PV, C0>,
+    index: DatabaseDup<'env_id, IK, PK, C1>,
+    extract: F,
+}
+
+impl<'env_id, PK, PV, IK, F, C0, C1> Indexed<'env_id, PK, PV, IK, F, C0, C1> {
+    /// The index should be empty, or consistent with the primary db.
+    pub fn new(
+        primary: DatabaseUnique<'env_id, PK, PV, C0>,
+        index: DatabaseDup<'env_id, IK, PK, C1>,
+        extract: F,
+    ) -> Self {
+        Self {
+            primary,
+            index,
+            extract,
+        }
+    }
+
+    /// The primary db.
+    /// Writes must go through [`Self::put`], [`Self::update`] and
+    /// [`Self::delete`], so that the index is kept in sync.
+    pub fn primary(&self) -> &RoDatabaseUnique<'env_id, PK, PV, C0> {
+        &self.primary
+    }
+
+    /// The index db
+    pub fn index(&self) -> &RoDatabaseDup<'env_id, IK, PK, C1> {
+        &self.index
+    }
+
+    /// Delete the index entries for a record
+    fn delete_index_entries<Id, Record, Field>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        record: &Record,
+    ) -> Result<(), error::Delete>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        IK: for<'a> BytesEncode<'a, EItem = Field>,
+        F: Fn(&Record) -> Vec<Field>,
+    {
+        for field in (self.extract)(record) {
+            let _deleted: bool = self.index.delete_one(rwtxn, &field, id)?;
+        }
+        Ok(())
+    }
+
+    /// Write a record and its index entries
+    fn put_record<Id, Record, Field>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        record: &Record,
+    ) -> Result<(), error::Put>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>,
+        IK: for<'a> BytesEncode<'a, EItem = Field>,
+        F: Fn(&Record) -> Vec<Field>,
+    {
+        let () = self.primary.put(rwtxn, id, record)?;
+        for field in (self.extract)(record) {
+            let () = self.index.put(rwtxn, &field, id)?;
+        }
+        Ok(())
+    }
+
+    /// Write a record, replacing the existing record for the id, if any,
+    /// and updating the index.
+    pub fn put<Id, Record, Field>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        record: &Record,
+    ) -> Result<(), error::Error>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>
+            + for<'a> BytesDecode<'a, DItem = Record>,
+        IK: for<'a> BytesEncode<'a, EItem = Field>,
+        F: Fn(&Record) -> Vec<Field>,
+    {
+        if let Some(old_record) = self.primary.try_get(rwtxn, id)? {
+            let () = self.delete_index_entries(rwtxn, id, &old_record)?;
+        }
+        let () = self.put_record(rwtxn, id, record)?;
+        Ok(())
+    }
+
+    /// Delete a record and its index entries.
+    /// Returns `false` if the record did not exist.
+    pub fn delete<Id, Record, Field>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+    ) -> Result<bool, error::Error>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        PV: for<'a> BytesDecode<'a, DItem = Record>,
+        IK: for<'a> BytesEncode<'a, EItem = Field>,
+        F: Fn(&Record) -> Vec<Field>,
+    {
+        let Some(old_record) = self.primary.try_get(rwtxn, id)? else {
+            return Ok(false);
+        };
+        let () = self.delete_index_entries(rwtxn, id, &old_record)?;
+        let _deleted: bool = self.primary.delete(rwtxn, id)?;
+        Ok(true)
+    }
+
+    /// Modify an existing record with `f`, updating the index.
+    /// Returns `false` if the record did not exist.
+    pub fn update<Id, Record, Field, G>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        id: &Id,
+        f: G,
+    ) -> Result<bool, error::Error>
+    where
+        PK: for<'a> BytesEncode<'a, EItem = Id>,
+        PV: for<'a> BytesEncode<'a, EItem = Record>
+            + for<'a> BytesDecode<'a, DItem = Record>,
+        IK: for<'a> BytesEncode<'a, EItem = Field>,
+        F: Fn(&Record) -> Vec<Field>,
+        G: FnOnce(Record) -> Record,
+    {
+        let Some(old_record) = self.primary.try_get(rwtxn, id)? else {
+            return Ok(false);
+        };
+        let () = self.delete_index_entries(rwtxn, id, &old_record)?;
+        let record = f(old_record);
+        let () = self.put_record(rwtxn, id, &record)?;
+        Ok(true)
+    }
+
+    /// Get each record with the specified field value, with its id.
+    /// Returns an [`Xor`] inconsistency error if the index contains an id
+    /// that does not exist in the primary db.
+    pub fn get_by_index<'env, Tx, Id, Record, Field>(
+        &self,
+        txn: &Tx,
+        field: &Field,
+    ) -> Result<Vec<(Id, Record)>, error::Error>
+    where
+        Tx: Txn<'env, 'env_id>,
+        PK: for<'a> BytesEncode<'a, EItem = Id>
+            + for<'a> BytesDecode<'a, DItem = Id>,
+        PV: for<'a> BytesDecode<'a, DItem = Record>,
+        IK: for<'a> BytesEncode<'a, EItem = Field> + for<'a> BytesDecode<'a>,
+    {
+        let ids: Vec<Id> = self.index.get(txn, field)?.collect()?;
+        let mut res = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some(record) = self.primary.try_get(txn, &id)? else {
+                let err =
+                    Xor::new(&id, ByValue(&*self.index), ByKey(&*self.primary));
+                return Err(error::Error::Inconsistent(err.into()));
+            };
+            res.push((id, record));
+        }
+        Ok(res)
+    }
+}
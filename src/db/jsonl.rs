@@ -0,0 +1,276 @@
+//! Export and import of dbs as JSON Lines, eg. for debugging or for use
+//! with non-Rust tooling.
+//!
+//! Each line is a JSON object with `key` and `value` fields.
+//! Dbs with duplicate keys are exported with one line per item.
+//! Exports can also be written with hex-encoded key and value bytes, which
+//! does not require the key and value types to implement `Serialize`.
+
+use std::io::{BufRead, Write};
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{types::Bytes, BytesDecode, BytesEncode, PutFlags};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    db::{
+        error, DatabaseDup, DatabaseUnique, DbWrapper, RoDatabaseDup,
+        RoDatabaseUnique,
+    },
+    serde_utils::Hex,
+    RwTxn, Txn,
+};
+
+/// A single line of JSON Lines
+#[derive(Deserialize, Serialize)]
+struct Line<K, V> {
+    key: K,
+    value: V,
+}
+
+impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
+    fn write_jsonl_line<W, K, V>(
+        &self,
+        writer: &mut W,
+        line: u64,
+        key: K,
+        value: V,
+    ) -> Result<(), error::WriteJsonl>
+    where
+        W: Write,
+        K: Serialize,
+        V: Serialize,
+    {
+        let write = |writer: &mut W| {
+            let () = serde_json::to_writer(&mut *writer, &Line { key, value })?;
+            writer.write_all(b"\n").map_err(serde_json::Error::io)
+        };
+        write(writer).map_err(|err| error::WriteJsonl {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            line,
+            source: err,
+        })
+    }
+
+    fn export_jsonl<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        mut writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        KC::DItem: Serialize,
+        DC::DItem: Serialize,
+        W: Write,
+    {
+        let mut items = self.iter(txn)?;
+        let mut lines = 0;
+        while let Some((key, value)) = items.next()? {
+            lines += 1;
+            let () = self.write_jsonl_line(&mut writer, lines, key, value)?;
+        }
+        Ok(lines)
+    }
+
+    fn export_jsonl_hex<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        mut writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        W: Write,
+    {
+        let mut items = self.iter_remapped::<_, Bytes, Bytes>(txn)?;
+        let mut lines = 0;
+        while let Some((key_bytes, value_bytes)) = items.next()? {
+            lines += 1;
+            let () = self.write_jsonl_line(
+                &mut writer,
+                lines,
+                Hex(key_bytes),
+                Hex(value_bytes),
+            )?;
+        }
+        Ok(lines)
+    }
+
+    fn import_jsonl<R, K, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        reader: R,
+    ) -> Result<u64, error::ImportJsonl>
+    where
+        KC: for<'k> BytesEncode<'k, EItem = K>,
+        DC: for<'v> BytesEncode<'v, EItem = V>,
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+        R: BufRead,
+    {
+        let mut imported = 0;
+        for (line, line_str) in (1..).zip(reader.lines()) {
+            let line_str = line_str.map_err(|err| error::ReadJsonl {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                line,
+                source: err,
+            })?;
+            if line_str.trim().is_empty() {
+                continue;
+            }
+            let Line { key, value }: Line<K, V> =
+                serde_json::from_str(&line_str).map_err(|err| {
+                    error::ParseJsonl {
+                        db_name: (*self.name).to_owned(),
+                        db_path: (*self.path).to_owned(),
+                        line,
+                        source: err,
+                    }
+                })?;
+            let () = self
+                .put_with_flags(rwtxn, PutFlags::empty(), &key, &value)
+                .map_err(|err| error::PutJsonl { line, source: err })?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Write every item as a line of JSON Lines, in key order.
+    /// Returns the number of lines written.
+    /// Writes are not buffered, so a buffered writer should usually be
+    /// used.
+    #[inline(always)]
+    pub fn export_jsonl<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        KC::DItem: Serialize,
+        DC::DItem: Serialize,
+        W: Write,
+    {
+        self.inner.export_jsonl(txn, writer)
+    }
+
+    /// Like [`Self::export_jsonl`], but keys and values are written as
+    /// hex-encoded bytes, without decoding them.
+    #[inline(always)]
+    pub fn export_jsonl_hex<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        W: Write,
+    {
+        self.inner.export_jsonl_hex(txn, writer)
+    }
+}
+
+impl<'env_id, KC, DC, C> DatabaseUnique<'env_id, KC, DC, C> {
+    /// Write each line of JSON Lines, as written by
+    /// [`RoDatabaseUnique::export_jsonl`], to the db.
+    /// Blank lines are skipped, and later lines overwrite earlier lines with
+    /// the same key.
+    /// Returns the number of items written.
+    /// Errors include the (1-indexed) line number of the failing line.
+    #[inline(always)]
+    pub fn import_jsonl<R, K, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        reader: R,
+    ) -> Result<u64, error::ImportJsonl>
+    where
+        KC: for<'k> BytesEncode<'k, EItem = K>,
+        DC: for<'v> BytesEncode<'v, EItem = V>,
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+        R: BufRead,
+    {
+        self.inner.inner.import_jsonl(rwtxn, reader)
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
+    /// Write every item as a line of JSON Lines, in key order, with one
+    /// line for each duplicate value.
+    /// Returns the number of lines written.
+    /// Writes are not buffered, so a buffered writer should usually be
+    /// used.
+    #[inline(always)]
+    pub fn export_jsonl<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        KC::DItem: Serialize,
+        DC::DItem: Serialize,
+        W: Write,
+    {
+        self.inner.export_jsonl(txn, writer)
+    }
+
+    /// Like [`Self::export_jsonl`], but keys and values are written as
+    /// hex-encoded bytes, without decoding them.
+    #[inline(always)]
+    pub fn export_jsonl_hex<'a, 'env, 'txn, Tx, W>(
+        &'a self,
+        txn: &'txn Tx,
+        writer: W,
+    ) -> Result<u64, error::ExportJsonl>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        W: Write,
+    {
+        self.inner.export_jsonl_hex(txn, writer)
+    }
+}
+
+impl<'env_id, KC, DC, C> DatabaseDup<'env_id, KC, DC, C> {
+    /// Write each line of JSON Lines, as written by
+    /// [`RoDatabaseDup::export_jsonl`], to the db.
+    /// Blank lines are skipped.
+    /// Returns the number of lines imported.
+    /// Errors include the (1-indexed) line number of the failing line.
+    #[inline(always)]
+    pub fn import_jsonl<R, K, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        reader: R,
+    ) -> Result<u64, error::ImportJsonl>
+    where
+        KC: for<'k> BytesEncode<'k, EItem = K>,
+        DC: for<'v> BytesEncode<'v, EItem = V>,
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+        R: BufRead,
+    {
+        self.inner.inner.import_jsonl(rwtxn, reader)
+    }
+}
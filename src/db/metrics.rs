@@ -0,0 +1,106 @@
+//! Read counters for databases
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Read counts for a db, and the time of the last read.
+/// See [`super::RoDatabaseUnique::read_stats`] and
+/// [`crate::Env::read_stats`].
+///
+/// Counts are shared by every handle to the db in the same env, and are
+/// not persisted.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReadStats {
+    /// Number of point reads, such as `try_get`, `contains_key`, `first`,
+    /// and `last`
+    pub gets: u64,
+    /// Number of iterators created over the whole db
+    pub iter_inits: u64,
+    /// Number of range iterators and range lookups created
+    pub range_inits: u64,
+    /// Time of the last read, with a resolution of one second.
+    /// `None` if the db has not been read since the env was opened.
+    pub last_read: Option<SystemTime>,
+}
+
+/// Counters for a single db, shared by every handle to the db
+#[derive(Debug, Default)]
+pub(crate) struct ReadCounters {
+    gets: AtomicU64,
+    iter_inits: AtomicU64,
+    range_inits: AtomicU64,
+    /// Seconds since the unix epoch of the last read, or `0` if the db has
+    /// not been read
+    last_read_secs: AtomicU64,
+}
+
+impl ReadCounters {
+    /// Update the last read time, writing at most once per second
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if self.last_read_secs.load(Ordering::Relaxed) != now {
+            self.last_read_secs.store(now, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_get(&self) {
+        self.gets.fetch_add(1, Ordering::Relaxed);
+        self.touch()
+    }
+
+    pub(crate) fn record_iter_init(&self) {
+        self.iter_inits.fetch_add(1, Ordering::Relaxed);
+        self.touch()
+    }
+
+    pub(crate) fn record_range_init(&self) {
+        self.range_inits.fetch_add(1, Ordering::Relaxed);
+        self.touch()
+    }
+
+    pub(crate) fn stats(&self) -> ReadStats {
+        let last_read_secs = self.last_read_secs.load(Ordering::Relaxed);
+        ReadStats {
+            gets: self.gets.load(Ordering::Relaxed),
+            iter_inits: self.iter_inits.load(Ordering::Relaxed),
+            range_inits: self.range_inits.load(Ordering::Relaxed),
+            last_read: (last_read_secs != 0)
+                .then(|| UNIX_EPOCH + Duration::from_secs(last_read_secs)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use std::time::UNIX_EPOCH;
+
+    use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+    use super::ReadStats;
+
+    impl Serialize for ReadStats {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let last_read_secs = self.last_read.map(|last_read| {
+                last_read
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            });
+            let mut state = serializer.serialize_struct("ReadStats", 4)?;
+            let () = state.serialize_field("gets", &self.gets)?;
+            let () = state.serialize_field("iter_inits", &self.iter_inits)?;
+            let () = state.serialize_field("range_inits", &self.range_inits)?;
+            let () =
+                state.serialize_field("last_read_secs", &last_read_secs)?;
+            state.end()
+        }
+    }
+}
@@ -1,19 +1,69 @@
 //! Database types
 
-use std::{path::Path, sync::Arc};
+#[cfg(feature = "observe")]
+use std::time::Duration;
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    num::NonZeroUsize,
+    ops::{Bound, RangeBounds},
+    path::Path,
+    sync::Arc,
+};
 
 use educe::Educe;
 use fallible_iterator::{FallibleIterator, IteratorExt as _};
+pub use heed::ReservedSpace;
 use heed::{
-    types::LazyDecode, BytesDecode, BytesEncode, Comparator, DatabaseFlags,
-    DefaultComparator, PutFlags,
+    types::{Bytes, DecodeIgnore, LazyDecode},
+    BytesDecode, BytesEncode, Comparator, DatabaseFlags, DefaultComparator,
+    PutFlags,
 };
 #[cfg(feature = "observe")]
 use tokio::sync::watch;
 
-use crate::{env, Env, RwTxn, Txn};
+use crate::{codec::Raw, env, Env, RwTxn, Txn};
 
+pub mod batch;
+#[cfg(feature = "cache")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
+pub mod cache;
+pub mod collect;
+pub mod consistency;
+pub mod constraint;
+pub mod entry;
 pub mod error;
+pub mod expiry;
+pub mod indexed;
+#[cfg(feature = "jsonl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jsonl")))]
+pub mod jsonl;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub mod metrics;
+pub mod owned;
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub mod par_scan;
+pub mod scan;
+pub mod scoped;
+#[cfg(feature = "stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+pub mod stream;
+
+pub use batch::WriteBatch;
+#[cfg(feature = "cache")]
+pub use cache::CachedDatabase;
+pub use collect::DuplicateKeys;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use expiry::ExpiringDatabase;
+pub use indexed::Indexed;
+#[cfg(feature = "metrics")]
+pub use metrics::ReadStats;
+pub use scan::ScanStats;
+pub use scoped::ScopedDatabase;
+#[cfg(feature = "stream")]
+pub use stream::IterStream;
 
 pub trait Database {
     type KC;
@@ -33,20 +83,281 @@ where
     }
 }
 
+/// Boxed iterator over raw items, returned by [`DatabaseRead::iter_raw`]
+pub type RawIter<'txn> = Box<
+    dyn FallibleIterator<
+            Item = (&'txn [u8], &'txn [u8]),
+            Error = error::IterItem,
+        > + 'txn,
+>;
+
+/// Reads that do not depend on the key and value codecs of a db, for code
+/// that works with any db, such as count, dump, or verify routines.
+/// Keys and values are encoded bytes.
+///
+/// Implemented by [`RoDatabaseUnique`] and [`RoDatabaseDup`].
+/// The trait is object safe, so dbs with different codecs can be used
+/// together as `dyn DatabaseRead`. Methods take the txn as a `&dyn Txn`,
+/// so both read and write txns can be used.
+/// For dbs with duplicate keys, items are key/value pairs, so a key
+/// with several values is counted and iterated once per value.
+///
+/// ```
+/// # use sneed::{make_guard, DatabaseDup, DatabaseUnique, Env, EnvOpenOptions};
+/// use fallible_iterator::FallibleIterator as _;
+/// use sneed::{db::DatabaseRead, types::Str};
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let mut rwtxn = env.write_txn()?;
+/// let unique: DatabaseUnique<Str, Str> =
+///     DatabaseUnique::create(&env, &mut rwtxn, "unique")?;
+/// let dup: DatabaseDup<Str, Str> =
+///     DatabaseDup::create(&env, &mut rwtxn, "dup")?;
+/// let () = unique.put(&mut rwtxn, "a", "1")?;
+/// let () = dup.put(&mut rwtxn, "a", "1")?;
+/// let () = dup.put(&mut rwtxn, "a", "2")?;
+/// let dbs: Vec<Box<dyn DatabaseRead<'_> + '_>> =
+///     vec![Box::new(&*unique), Box::new(&*dup)];
+/// let lens: Vec<u64> = dbs
+///     .iter()
+///     .map(|db| db.len(&rwtxn))
+///     .collect::<Result<_, _>>()?;
+/// assert_eq!(lens, [1, 2]);
+/// for db in &dbs {
+///     assert!(db.contains_key_raw(&rwtxn, b"a")?);
+///     assert_eq!(db.first_raw(&rwtxn)?, Some((&b"a"[..], &b"1"[..])));
+///     assert_eq!(db.iter_raw(&rwtxn)?.count()?, db.len(&rwtxn)? as usize);
+/// }
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+pub trait DatabaseRead<'env_id> {
+    /// Number of items in the db
+    fn len(&self, txn: &dyn Txn<'_, 'env_id>) -> Result<u64, error::Len>;
+
+    /// Iterate over all items, without decoding
+    fn iter_raw<'a, 'env, 'txn>(
+        &'a self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<RawIter<'txn>, error::IterInit>
+    where
+        'a: 'txn,
+        'env: 'txn;
+
+    /// Check if the provided encoded key exists in the db
+    fn contains_key_raw(
+        &self,
+        txn: &dyn Txn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, error::TryGet>;
+
+    /// First item in the db, without decoding
+    #[allow(clippy::type_complexity)]
+    fn first_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::First>
+    where
+        'env: 'txn;
+
+    /// Last item in the db, without decoding
+    #[allow(clippy::type_complexity)]
+    fn last_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::Last>
+    where
+        'env: 'txn;
+}
+
+impl<'env_id, DB> DatabaseRead<'env_id> for &DB
+where
+    DB: DatabaseRead<'env_id> + ?Sized,
+{
+    fn len(&self, txn: &dyn Txn<'_, 'env_id>) -> Result<u64, error::Len> {
+        <DB as DatabaseRead>::len(*self, txn)
+    }
+
+    fn iter_raw<'a, 'env, 'txn>(
+        &'a self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<RawIter<'txn>, error::IterInit>
+    where
+        'a: 'txn,
+        'env: 'txn,
+    {
+        <DB as DatabaseRead>::iter_raw(*self, txn)
+    }
+
+    fn contains_key_raw(
+        &self,
+        txn: &dyn Txn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, error::TryGet> {
+        <DB as DatabaseRead>::contains_key_raw(*self, txn, key)
+    }
+
+    fn first_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::First>
+    where
+        'env: 'txn,
+    {
+        <DB as DatabaseRead>::first_raw(*self, txn)
+    }
+
+    fn last_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::Last>
+    where
+        'env: 'txn,
+    {
+        <DB as DatabaseRead>::last_raw(*self, txn)
+    }
+}
+
+/// Result of searching for a key.
+/// See [`RoDatabaseUnique::search`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchResult<K> {
+    Found(K),
+    /// The greatest key less than the target, and the least key greater
+    /// than the target, if they exist
+    NotFound {
+        prev: Option<K>,
+        next: Option<K>,
+    },
+}
+
+/// Encode a range bound as bytes
+fn encode_bound<'a, KC>(
+    bound: Bound<&'a KC::EItem>,
+) -> Result<Bound<Cow<'a, [u8]>>, heed::BoxedError>
+where
+    KC: BytesEncode<'a>,
+{
+    match bound {
+        Bound::Included(key) => Ok(Bound::Included(KC::bytes_encode(key)?)),
+        Bound::Excluded(key) => Ok(Bound::Excluded(KC::bytes_encode(key)?)),
+        Bound::Unbounded => Ok(Bound::Unbounded),
+    }
+}
+
+fn as_slice_bound<'a>(bound: &'a Bound<Cow<'_, [u8]>>) -> Bound<&'a [u8]> {
+    match bound {
+        Bound::Included(bytes) => Bound::Included(bytes),
+        Bound::Excluded(bytes) => Bound::Excluded(bytes),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Check if a key is after the start bound, according to the comparator
+fn is_after_start<C>(key_bytes: &[u8], start: &Bound<Cow<'_, [u8]>>) -> bool
+where
+    C: Comparator,
+{
+    match start {
+        Bound::Included(start) => {
+            C::compare(key_bytes, start) != Ordering::Less
+        }
+        Bound::Excluded(start) => {
+            C::compare(key_bytes, start) == Ordering::Greater
+        }
+        Bound::Unbounded => true,
+    }
+}
+
+/// Skip the first `n` items of an iterator.
+/// Skipped items are still produced by the inner iterator, so any decoding
+/// should be done after skipping.
+fn skip_while_n<I>(
+    items: I,
+    n: u64,
+) -> impl FallibleIterator<Item = I::Item, Error = I::Error>
+where
+    I: FallibleIterator,
+{
+    let mut remaining = n;
+    items.skip_while(move |_| {
+        if remaining == 0 {
+            return Ok(false);
+        }
+        remaining -= 1;
+        Ok(true)
+    })
+}
+
+/// Check if a key is before the end bound, according to the comparator
+fn is_before_end<C>(key_bytes: &[u8], end: &Bound<Cow<'_, [u8]>>) -> bool
+where
+    C: Comparator,
+{
+    match end {
+        Bound::Included(end) => C::compare(key_bytes, end) != Ordering::Greater,
+        Bound::Excluded(end) => C::compare(key_bytes, end) == Ordering::Less,
+        Bound::Unbounded => true,
+    }
+}
+
 /// Wrapper for [`heed::Database`] with better errors
 #[derive(Educe)]
 #[educe(Clone, Debug)]
 struct DbWrapper<'env_id, KC, DC, C = DefaultComparator> {
+    #[educe(Debug(ignore))]
     unique_guard: Arc<generativity::Guard<'env_id>>,
+    #[educe(Debug(ignore))]
     heed_db: heed::Database<KC, DC, C>,
     name: Arc<str>,
     path: Arc<Path>,
+    /// `true` if the db was created with [`DatabaseFlags::DUP_FIXED`]
+    dup_fixed: bool,
+    /// `true` if values should be checked against the free space in the
+    /// map before writing.
+    /// See [`DatabaseUnique::check_value_size`].
+    check_value_size: bool,
     #[cfg(feature = "observe")]
+    #[educe(Debug(ignore))]
     watch: (watch::Sender<()>, watch::Receiver<()>),
+    #[cfg(feature = "metrics")]
+    #[educe(Debug(ignore))]
+    read_counters: Arc<metrics::ReadCounters>,
+}
+
+/// Read the flags that a named db was created with, from its record in the
+/// unnamed db.
+/// LMDB stores each named db's `MDB_db` record as the value for its name,
+/// with the flags as a native-endian `u16` following a `u32` pad.
+/// Returns `None` if the record cannot be read.
+fn persisted_db_flags<'env, 'env_id, Tx>(
+    env: &Env<'env_id>,
+    txn: &Tx,
+    name: &str,
+) -> Option<DatabaseFlags>
+where
+    Tx: Txn<'env, 'env_id>,
+{
+    let main_db = env
+        .database_options()
+        .types::<Bytes, Bytes>()
+        .open(txn.read_txn())
+        .ok()??;
+    let record = main_db.get(txn.read_txn(), name.as_bytes()).ok()??;
+    let flags = record.get(4..6)?;
+    let flags = u16::from_ne_bytes([flags[0], flags[1]]);
+    Some(DatabaseFlags::from_bits_truncate(u32::from(flags)))
 }
 
 impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
     /// Create a DB, if it does not already exist, and open it if it does.
+    /// If the DB already exists, it keeps the flags it was created with.
     fn create(
         env: &Env<'env_id>,
         rwtxn: &mut RwTxn<'_, 'env_id>,
@@ -71,16 +382,88 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
                 source: err,
             }
         })?;
+        let dup_fixed = persisted_db_flags(env, &*rwtxn, name)
+            .is_some_and(|flags| flags.contains(DatabaseFlags::DUP_FIXED));
         Ok(Self {
             unique_guard: env.unique_guard().clone(),
             heed_db,
             name: Arc::from(name),
             path,
+            dup_fixed,
+            check_value_size: false,
             #[cfg(feature = "observe")]
             watch: watch::channel(()),
+            #[cfg(feature = "metrics")]
+            read_counters: env.read_counters(name),
         })
     }
 
+    /// Open a DB, if it exists.
+    fn open<'env, Tx>(
+        env: &Env<'env_id>,
+        txn: &Tx,
+        name: &str,
+    ) -> Result<Option<Self>, env::error::OpenDb>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        let path = env.path().clone();
+        let heed_db = env
+            .database_options()
+            .name(name)
+            .types()
+            .key_comparator()
+            .open(txn.read_txn())
+            .map_err(|err| env::error::OpenDb {
+                name: name.to_owned(),
+                path: (*path).to_owned(),
+                source: err,
+            })?;
+        let Some(heed_db) = heed_db else {
+            return Ok(None);
+        };
+        let dup_fixed = persisted_db_flags(env, txn, name)
+            .is_some_and(|flags| flags.contains(DatabaseFlags::DUP_FIXED));
+        Ok(Some(Self {
+            unique_guard: env.unique_guard().clone(),
+            heed_db,
+            name: Arc::from(name),
+            path,
+            dup_fixed,
+            check_value_size: false,
+            #[cfg(feature = "observe")]
+            watch: watch::channel(()),
+            #[cfg(feature = "metrics")]
+            read_counters: env.read_counters(name),
+        }))
+    }
+
+    /// Record a point read, if the `metrics` feature is enabled
+    #[inline(always)]
+    fn record_get(&self) {
+        #[cfg(feature = "metrics")]
+        self.read_counters.record_get()
+    }
+
+    /// Record an iterator over the whole db, if the `metrics` feature is
+    /// enabled
+    #[inline(always)]
+    fn record_iter_init(&self) {
+        #[cfg(feature = "metrics")]
+        self.read_counters.record_iter_init()
+    }
+
+    /// Record a range iterator or range lookup, if the `metrics` feature is
+    /// enabled
+    #[inline(always)]
+    fn record_range_init(&self) {
+        #[cfg(feature = "metrics")]
+        self.read_counters.record_range_init()
+    }
+
     /// Check if the provided key exists in the db.
     /// The stored value is not decoded, if it exists.
     fn contains_key<'a, 'env, 'txn, Tx>(
@@ -94,6 +477,7 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         KC: BytesEncode<'a>,
         LazyDecode<DC>: BytesDecode<'txn>,
     {
+        let () = self.record_get();
         match self.heed_db.lazily_decode_data().get(txn.read_txn(), key) {
             Ok(lazy_value) => Ok(lazy_value.is_some()),
             Err(err) => {
@@ -109,6 +493,25 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         }
     }
 
+    fn clear(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), error::Clear> {
+        let () = rwtxn.record_op(&self.name);
+        let () = self.heed_db.clear(rwtxn.write_txn()).map_err(|err| {
+            error::Clear {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            }
+        })?;
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(())
+    }
+
     fn delete<'a, 'env, 'txn>(
         &self,
         rwtxn: &'txn mut RwTxn<'env, 'env_id>,
@@ -117,6 +520,7 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
     where
         KC: BytesEncode<'a>,
     {
+        let () = rwtxn.record_op(&self.name);
         let res =
             self.heed_db.delete(rwtxn.write_txn(), key).map_err(|err| {
                 let key_bytes = <KC as BytesEncode>::bytes_encode(key)
@@ -135,6 +539,39 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         Ok(res)
     }
 
+    fn delete_one_duplicate<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<bool, error::Delete>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let res = self
+            .heed_db
+            .delete_one_duplicate(rwtxn.write_txn(), key, data)
+            .map_err(|err| {
+                let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                    .map(|key_bytes| key_bytes.to_vec());
+                error::Delete {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    key_bytes,
+                    source: err,
+                }
+            })?;
+        #[cfg(feature = "observe")]
+        if res {
+            let _watch_tx: Option<watch::Sender<_>> = rwtxn
+                .pending_writes
+                .insert(self.name.clone(), self.watch.0.clone());
+        }
+        Ok(res)
+    }
+
     #[allow(clippy::type_complexity)]
     fn first<'env, 'txn, Tx>(
         &self,
@@ -146,6 +583,7 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         KC: BytesDecode<'txn>,
         DC: BytesDecode<'txn>,
     {
+        let () = self.record_get();
         self.heed_db
             .first(txn.read_txn())
             .map_err(|err| error::First {
@@ -155,6 +593,166 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
             })
     }
 
+    #[allow(clippy::type_complexity)]
+    fn nth<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::Iter>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.record_iter_init();
+        let item_err = |err| error::IterItem {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        };
+        let items = self
+            .heed_db
+            .remap_types::<Bytes, Bytes>()
+            .iter(txn.read_txn())
+            .map_err(|err| error::IterInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })?;
+        let mut remaining = n;
+        for item in items {
+            let (key_bytes, value_bytes) = item.map_err(item_err)?;
+            if remaining > 0 {
+                remaining -= 1;
+                continue;
+            }
+            let key = KC::bytes_decode(key_bytes)
+                .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+            let value = DC::bytes_decode(value_bytes)
+                .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+            return Ok(Some((key, value)));
+        }
+        Ok(None)
+    }
+
+    fn nth_key<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<Option<KC::DItem>, error::Iter>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+    {
+        let () = self.record_iter_init();
+        let item_err = |err| error::IterItem {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        };
+        let items = self
+            .heed_db
+            .remap_types::<Bytes, DecodeIgnore>()
+            .iter(txn.read_txn())
+            .map_err(|err| error::IterInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })?;
+        let mut remaining = n;
+        for item in items {
+            let (key_bytes, ()) = item.map_err(item_err)?;
+            if remaining > 0 {
+                remaining -= 1;
+                continue;
+            }
+            let key = KC::bytes_decode(key_bytes)
+                .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+            return Ok(Some(key));
+        }
+        Ok(None)
+    }
+
+    /// Decode items from an iterator over item bytes
+    fn decode_items<'a, 'txn, It>(
+        &'a self,
+        items: It,
+    ) -> impl FallibleIterator<
+        Item = (KC::DItem, DC::DItem),
+        Error = error::IterItem,
+    > + 'txn
+    where
+        'a: 'txn,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        It: FallibleIterator<
+                Item = (&'txn [u8], &'txn [u8]),
+                Error = error::IterItem,
+            > + 'txn,
+    {
+        let db_name = &*self.name;
+        let db_path = &*self.path;
+        items.map(move |(key_bytes, value_bytes)| {
+            let decode = || {
+                let key = KC::bytes_decode(key_bytes)?;
+                let value = DC::bytes_decode(value_bytes)?;
+                Ok((key, value))
+            };
+            decode().map_err(|err| error::IterItem {
+                db_name: db_name.to_owned(),
+                db_path: db_path.to_owned(),
+                source: heed::Error::Decoding(err),
+            })
+        })
+    }
+
+    /// Iterate over all items, skipping the first `n` items without
+    /// decoding them
+    fn iter_skip<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let items = self.iter_remapped::<_, Bytes, Bytes>(txn)?;
+        Ok(self.decode_items(skip_while_n(items, n)))
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn last<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::Last>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.record_get();
+        self.heed_db
+            .last(txn.read_txn())
+            .map_err(|err| error::Last {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })
+    }
+
     fn get_duplicates<'a, 'env, 'txn, Tx>(
         &'a self,
         txn: &'txn Tx,
@@ -170,6 +768,7 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         KC: BytesDecode<'txn> + BytesEncode<'a>,
         DC: BytesDecode<'txn>,
     {
+        let () = self.record_get();
         match self.heed_db.get_duplicates(txn.read_txn(), key) {
             Ok(it) => Ok(it
                 .into_iter()
@@ -200,41 +799,236 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         }
     }
 
-    fn iter<'a, 'env, 'txn, Tx>(
+    /// Collect the values for a key, without decoding the key for each
+    /// value.
+    /// Values are read one at a time; see
+    /// [`RoDatabaseDup::get_duplicates_bulk`].
+    fn get_duplicates_bulk<'a, 'env, 'txn, Tx>(
         &'a self,
         txn: &'txn Tx,
-    ) -> Result<
-        impl FallibleIterator<
-                Item = (KC::DItem, DC::DItem),
-                Error = error::IterItem,
-            > + 'txn,
-        error::IterInit,
-    >
+        key: &'a KC::EItem,
+    ) -> Result<Vec<DC::DItem>, error::IterDuplicates>
     where
         'a: 'txn,
         'env: 'txn,
         Tx: Txn<'env, 'env_id>,
-        KC: BytesDecode<'txn>,
+        KC: BytesEncode<'a>,
         DC: BytesDecode<'txn>,
     {
-        match self.heed_db.iter(txn.read_txn()) {
-            Ok(it) => Ok(it.transpose_into_fallible().map_err({
-                let db_path = &*self.path;
-                let name = self.name();
-                |err| error::IterItem {
-                    db_name: name.to_owned(),
-                    db_path: db_path.to_owned(),
+        let () = self.record_get();
+        let it = match self.heed_db.get_duplicates(txn.read_txn(), key) {
+            Ok(it) => it,
+            Err(err) => {
+                let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                    .map(|key_bytes| key_bytes.to_vec());
+                return Err(error::IterDuplicatesInit {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    key_bytes,
                     source: err,
                 }
-            })),
-            Err(err) => Err(error::IterInit {
-                db_name: (*self.name).to_owned(),
-                db_path: (*self.path).to_owned(),
+                .into());
+            }
+        };
+        let Some(it) = it else {
+            return Ok(Vec::new());
+        };
+        it.remap_key_type::<DecodeIgnore>()
+            .map(|item| {
+                item.map(|((), value)| value).map_err(|err| {
+                    error::IterItem {
+                        db_name: (*self.name).to_owned(),
+                        db_path: (*self.path).to_owned(),
+                        source: err,
+                    }
+                    .into()
+                })
+            })
+            .collect()
+    }
+
+    fn iter<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.iter_remapped::<_, KC, DC>(txn)
+    }
+
+    /// Iterate over all items, continuing past items that fail to decode.
+    /// Iteration stops after the first error that is not a decoding error,
+    /// since the cursor cannot advance past it.
+    #[allow(clippy::type_complexity)]
+    fn iter_lossy<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl Iterator<Item = Result<(KC::DItem, DC::DItem), error::IterItem>> + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.record_iter_init();
+        let items = self
+            .heed_db
+            .iter(txn.read_txn())
+            .map_err(|err| error::IterInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })?
+            .remap_types::<Bytes, Bytes>();
+        let db_name = &*self.name;
+        let db_path = &*self.path;
+        let mut cursor_failed = false;
+        Ok(items.map_while(move |item| {
+            if cursor_failed {
+                return None;
+            }
+            let decode = |(key_bytes, value_bytes)| {
+                let key = KC::bytes_decode(key_bytes)
+                    .map_err(heed::Error::Decoding)?;
+                let value = DC::bytes_decode(value_bytes)
+                    .map_err(heed::Error::Decoding)?;
+                Ok((key, value))
+            };
+            let res = match item {
+                Ok(item_bytes) => decode(item_bytes),
+                Err(err) => {
+                    cursor_failed = true;
+                    Err(err)
+                }
+            };
+            Some(res.map_err(|err| error::IterItem {
+                db_name: db_name.to_owned(),
+                db_path: db_path.to_owned(),
+                source: err,
+            }))
+        }))
+    }
+
+    /// Iterate over all items, decoding items with `KD` and `DD`.
+    fn iter_remapped<'a, 'env, 'txn, Tx, KD, DD>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KD::DItem, DD::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id> + ?Sized,
+        KD: BytesDecode<'txn> + 'txn,
+        DD: BytesDecode<'txn> + 'txn,
+    {
+        let () = self.record_iter_init();
+        match self.heed_db.iter(txn.read_txn()) {
+            Ok(it) => Ok(it
+                .remap_types::<KD, DD>()
+                .transpose_into_fallible()
+                .map_err({
+                    let db_path = &*self.path;
+                    let name = self.name();
+                    |err| error::IterItem {
+                        db_name: name.to_owned(),
+                        db_path: db_path.to_owned(),
+                        source: err,
+                    }
+                })),
+            Err(err) => Err(error::IterInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
                 source: err,
             }),
         }
     }
 
+    /// Check if the provided encoded key exists in the db
+    fn contains_key_raw<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &[u8],
+    ) -> Result<bool, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id> + ?Sized,
+    {
+        let () = self.record_get();
+        self.heed_db
+            .remap_types::<Raw, DecodeIgnore>()
+            .get(txn.read_txn(), key)
+            .map(|value| value.is_some())
+            .map_err(|err| error::TryGet {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Ok(key.to_vec()),
+                source: err,
+            })
+    }
+
+    /// First item in the db, without decoding
+    #[allow(clippy::type_complexity)]
+    fn first_raw<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::First>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id> + ?Sized,
+    {
+        let () = self.record_get();
+        self.heed_db
+            .remap_types::<Raw, Raw>()
+            .first(txn.read_txn())
+            .map_err(|err| error::First {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })
+    }
+
+    /// Last item in the db, without decoding
+    #[allow(clippy::type_complexity)]
+    fn last_raw<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::Last>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id> + ?Sized,
+    {
+        let () = self.record_get();
+        self.heed_db
+            .remap_types::<Raw, Raw>()
+            .last(txn.read_txn())
+            .map_err(|err| error::Last {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            })
+    }
+
     fn iter_keys<'a, 'env, 'txn, Tx>(
         &'a self,
         txn: &'txn Tx,
@@ -249,6 +1043,7 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         KC: BytesDecode<'txn>,
         LazyDecode<DC>: BytesDecode<'txn>,
     {
+        let () = self.record_iter_init();
         match self.heed_db.lazily_decode_data().iter(txn.read_txn()) {
             Ok(it) => Ok(it
                 .transpose_into_fallible()
@@ -270,6 +1065,61 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         }
     }
 
+    /// Iterate over each distinct key, with the number of items with that
+    /// key.
+    /// Distinct keys are visited with `MDB_NEXT_NODUP`. heed does not expose
+    /// `mdb_cursor_count`, so the items for each key are counted by
+    /// iterating over them with `MDB_NEXT_DUP`.
+    /// Values are not decoded.
+    fn key_counts<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<Item = (KC::DItem, u64), Error = error::IterItem>
+            + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+    {
+        let () = self.record_iter_init();
+        let rotxn = txn.read_txn();
+        let db = self.heed_db.remap_types::<Bytes, DecodeIgnore>();
+        let db_name = &*self.name;
+        let db_path = &*self.path;
+        let item_err = move |err| error::IterItem {
+            db_name: db_name.to_owned(),
+            db_path: db_path.to_owned(),
+            source: err,
+        };
+        let keys = db.iter(rotxn).map_err(|err| error::IterInit {
+            db_name: db_name.to_owned(),
+            db_path: db_path.to_owned(),
+            source: err,
+        })?;
+        Ok(keys
+            .move_between_keys()
+            .transpose_into_fallible()
+            .map_err(item_err)
+            .map(move |(key_bytes, ())| {
+                let mut count = 0;
+                if let Some(items) =
+                    db.get_duplicates(rotxn, key_bytes).map_err(item_err)?
+                {
+                    for item in items {
+                        let (_key_bytes, ()) = item.map_err(item_err)?;
+                        count += 1;
+                    }
+                }
+                let key = KC::bytes_decode(key_bytes)
+                    .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+                Ok((key, count))
+            }))
+    }
+
     fn lazy_decode(&self) -> DbWrapper<'env_id, KC, LazyDecode<DC>, C> {
         let heed_db = self.heed_db.lazily_decode_data();
         DbWrapper {
@@ -277,14 +1127,41 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
             heed_db,
             name: self.name.clone(),
             path: self.path.clone(),
+            dup_fixed: self.dup_fixed,
+            check_value_size: self.check_value_size,
             #[cfg(feature = "observe")]
             watch: self.watch.clone(),
+            #[cfg(feature = "metrics")]
+            read_counters: self.read_counters.clone(),
         }
     }
 
-    fn len<'env, 'txn, Tx>(&self, txn: &'txn Tx) -> Result<u64, error::Len>
+    fn describe<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<String, error::Len>
     where
         Tx: Txn<'env, 'env_id>,
+    {
+        let len = self.len(txn)?;
+        Ok(format!("{} ({len} entries)", self.name))
+    }
+
+    /// Format a db handle, showing the name and path
+    fn fmt_debug(
+        &self,
+        type_name: &str,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.debug_struct(type_name)
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .finish()
+    }
+
+    fn len<'env, 'txn, Tx>(&self, txn: &'txn Tx) -> Result<u64, error::Len>
+    where
+        Tx: Txn<'env, 'env_id> + ?Sized,
     {
         self.heed_db.len(txn.read_txn()).map_err(|err| error::Len {
             db_name: (*self.name).to_owned(),
@@ -293,10 +1170,145 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         })
     }
 
+    /// Number of items in the db, as a `usize`.
+    /// Returns an [`error::LenOverflow`] error if the number of items
+    /// exceeds [`usize::MAX`], which is only possible on targets with
+    /// pointers narrower than 64 bits.
+    fn len_usize<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<usize, error::LenUsize>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        let len = self.len(txn)?;
+        usize::try_from(len).map_err(|_| {
+            let err = error::LenOverflow {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                len,
+            };
+            err.into()
+        })
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 
+    fn qualified_name(&self) -> String {
+        format!("{}::{}", self.path.display(), self.name)
+    }
+
+    /// LMDB does not reject values of the wrong size in dbs created with
+    /// [`DatabaseFlags::DUP_FIXED`], and may corrupt them instead, so check
+    /// that the value is the same size as the first value in the db.
+    /// Encoding errors are left for the write to report.
+    fn check_dup_fixed_size<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let Ok(value_bytes) = <DC as BytesEncode>::bytes_encode(data) else {
+            return Ok(());
+        };
+        let err = |source: heed::Error| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec());
+            error::Put {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Box::new(key_bytes),
+                value_bytes: Box::new(Ok(value_bytes.to_vec())),
+                source: source.into(),
+            }
+        };
+        match self
+            .heed_db
+            .remap_types::<DecodeIgnore, Bytes>()
+            .first(rwtxn.write_txn())
+        {
+            Ok(Some(((), first_value)))
+                if first_value.len() != value_bytes.len() =>
+            {
+                Err(err(heed::Error::Mdb(heed::MdbError::BadValSize)))
+            }
+            Ok(_) => Ok(()),
+            Err(source) => Err(err(source)),
+        }
+    }
+
+    /// Check that an encoded value is not larger than the free space in
+    /// the map.
+    /// Free space is the map size less the pages up to the last page in use,
+    /// as of the last commit.
+    /// Pages on the freelist are not counted as free.
+    fn check_free_space<'a>(
+        &self,
+        rwtxn: &RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let Ok(value_bytes) = <DC as BytesEncode>::bytes_encode(data) else {
+            return Ok(());
+        };
+        // If the page size cannot be read, let the write fail instead
+        let Ok(stat) = self.heed_db.stat(&rwtxn.inner) else {
+            return Ok(());
+        };
+        let info = rwtxn.env.info();
+        let map_size = info.map_size as u64;
+        // Pages are numbered from 0
+        let used = (info.last_page_number as u64 + 1) * stat.page_size as u64;
+        let free = map_size.saturating_sub(used);
+        if value_bytes.len() as u64 <= free {
+            return Ok(());
+        }
+        let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+            .map(|key_bytes| key_bytes.to_vec());
+        Err(error::Put {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            key_bytes: Box::new(key_bytes),
+            value_bytes: Box::new(Ok(value_bytes.to_vec())),
+            source: error::ValueTooLarge {
+                size: value_bytes.len(),
+                free,
+                map_size,
+            }
+            .into(),
+        })
+    }
+
+    /// Checks to run before each put, depending on how the db was opened
+    fn check_put<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        if self.dup_fixed {
+            let () = self.check_dup_fixed_size(rwtxn, key, data)?;
+        }
+        if self.check_value_size {
+            let () = self.check_free_space(rwtxn, key, data)?;
+        }
+        Ok(())
+    }
+
     fn put_with_flags<'a, 'env, 'txn>(
         &self,
         rwtxn: &'txn mut RwTxn<'env, 'env_id>,
@@ -308,6 +1320,8 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         KC: BytesEncode<'a>,
         DC: BytesEncode<'a>,
     {
+        let () = rwtxn.record_op(&self.name);
+        let () = self.check_put(rwtxn, key, data)?;
         let () = self
             .heed_db
             .put_with_flags(rwtxn.write_txn(), flags, key, data)
@@ -319,9 +1333,9 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
                 error::Put {
                     db_name: (*self.name).to_owned(),
                     db_path: (*self.path).to_owned(),
-                    key_bytes,
-                    value_bytes,
-                    source: err,
+                    key_bytes: Box::new(key_bytes),
+                    value_bytes: Box::new(value_bytes),
+                    source: err.into(),
                 }
             })?;
         #[cfg(feature = "observe")]
@@ -331,197 +1345,2080 @@ impl<'env_id, KC, DC, C> DbWrapper<'env_id, KC, DC, C> {
         Ok(())
     }
 
-    pub fn try_get<'a, 'env, 'txn, Tx>(
+    fn put_reserved<'a, F>(
         &self,
-        txn: &'txn Tx,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
         key: &'a KC::EItem,
-    ) -> Result<Option<DC::DItem>, error::TryGet>
+        size: usize,
+        write: F,
+    ) -> Result<(), error::PutReserved>
     where
-        'env: 'txn,
-        Tx: Txn<'env, 'env_id>,
         KC: BytesEncode<'a>,
-        DC: BytesDecode<'txn>,
+        F: FnOnce(&mut ReservedSpace<'_>) -> std::io::Result<()>,
     {
-        self.heed_db.get(txn.read_txn(), key).map_err(|err| {
-            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
-                .map(|key_bytes| key_bytes.to_vec());
-            error::TryGet {
+        let () = rwtxn.record_op(&self.name);
+        let mut written = size;
+        let res = self.heed_db.put_reserved(
+            rwtxn.write_txn(),
+            key,
+            size,
+            |reserved| {
+                let () = write(reserved)?;
+                written = reserved.written_mut().len();
+                // heed checks the write position rather than the number of
+                // bytes written, which differ if `write` seeks backwards
+                if written == size {
+                    let _: u64 = std::io::Seek::seek(
+                        reserved,
+                        std::io::SeekFrom::End(0),
+                    )?;
+                }
+                Ok(())
+            },
+        );
+        let key_bytes = || {
+            <KC as BytesEncode>::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec())
+        };
+        match res {
+            Ok(()) => {
+                #[cfg(feature = "observe")]
+                let _watch_tx: Option<watch::Sender<_>> = rwtxn
+                    .pending_writes
+                    .insert(self.name.clone(), self.watch.0.clone());
+                Ok(())
+            }
+            // `written` is only updated once the key has been encoded and
+            // the space reserved
+            Err(_) if written < size => Err(error::ReservedUnderrun {
                 db_name: (*self.name).to_owned(),
                 db_path: (*self.path).to_owned(),
-                key_bytes,
+                key_bytes: key_bytes().unwrap_or_default(),
+                reserved: size,
+                written,
+            }
+            .into()),
+            Err(err) => Err(error::PutReservedWrite {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: key_bytes(),
                 source: err,
             }
-        })
+            .into()),
+        }
     }
 
-    pub fn get<'a, 'env, 'txn, Tx>(
+    /// Write with the specified flags, which must be a subset of
+    /// `valid_flags`
+    fn put_with_valid_flags<'a>(
         &self,
-        txn: &'txn Tx,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        valid_flags: PutFlags,
+        flags: PutFlags,
         key: &'a KC::EItem,
-    ) -> Result<DC::DItem, error::Get>
+        data: &'a DC::EItem,
+    ) -> Result<(), error::PutWithFlags>
     where
-        'env: 'txn,
-        Tx: Txn<'env, 'env_id>,
         KC: BytesEncode<'a>,
-        DC: BytesDecode<'txn>,
+        DC: BytesEncode<'a>,
     {
-        self.try_get(txn, key)?.ok_or_else(|| {
-            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
-                // Safety: key must encode successfully, as try_get succeeded
-                .unwrap()
-                .to_vec();
-            error::Get::MissingValue {
+        if !valid_flags.contains(flags) {
+            return Err(error::InvalidPutFlags {
                 db_name: (*self.name).to_owned(),
                 db_path: (*self.path).to_owned(),
+                bits: flags.bits(),
+            }
+            .into());
+        }
+        match self.put_with_flags(rwtxn, flags, key, data) {
+            Ok(()) => Ok(()),
+            Err(error::Put {
+                db_name,
+                db_path,
                 key_bytes,
+                value_bytes: _,
+                source:
+                    error::PutSource::Heed(heed::Error::Mdb(
+                        heed::MdbError::KeyExist,
+                    )),
+            }) => Err(error::KeyExists {
+                db_name,
+                db_path,
+                key_bytes: *key_bytes,
             }
-        })
+            .into()),
+            Err(err) => Err(err.into()),
+        }
     }
 
-    /// Attempt to insert a key-value pair in this database,
-    /// or if a value already exists for the key, returns the previous value.
-    /// The entry is always written with the NO_OVERWRITE flag.
-    /// See [`heed::Database::get_or_put`]
-    pub fn try_put<'a, 'env, 'txn>(
-        &'txn self,
+    /// Write a key-value pair, failing if the key already exists
+    fn insert<'a>(
+        &self,
         rwtxn: &mut RwTxn<'_, 'env_id>,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<Option<DC::DItem>, error::Put>
+    ) -> Result<(), error::Insert>
     where
         KC: BytesEncode<'a>,
-        DC: BytesEncode<'a> + BytesDecode<'a>,
+        DC: BytesEncode<'a>,
     {
-        let res = self
-            .heed_db
-            .get_or_put(rwtxn.write_txn(), key, data)
-            .map_err(|err| {
-                let key_bytes = <KC as BytesEncode>::bytes_encode(key)
-                    .map(|key_bytes| key_bytes.to_vec());
-                let value_bytes = <DC as BytesEncode>::bytes_encode(data)
-                    .map(|value_bytes| value_bytes.to_vec());
-                error::Put {
-                    db_name: (*self.name).to_owned(),
-                    db_path: (*self.path).to_owned(),
+        match self.put_with_flags(rwtxn, PutFlags::NO_OVERWRITE, key, data) {
+            Ok(()) => Ok(()),
+            Err(error::Put {
+                db_name,
+                db_path,
+                key_bytes,
+                value_bytes,
+                source:
+                    error::PutSource::Heed(heed::Error::Mdb(
+                        heed::MdbError::KeyExist,
+                    )),
+            }) => match *key_bytes {
+                Ok(key_bytes) => Err(error::Insert::AlreadyExists {
+                    db_name,
+                    db_path,
                     key_bytes,
+                }),
+                key_bytes => Err(error::Put {
+                    db_name,
+                    db_path,
+                    key_bytes: Box::new(key_bytes),
                     value_bytes,
-                    source: err,
+                    source: heed::Error::Mdb(heed::MdbError::KeyExist).into(),
                 }
-            })?;
-        #[cfg(feature = "observe")]
-        let _watch_tx: Option<watch::Sender<_>> = rwtxn
-            .pending_writes
-            .insert(self.name.clone(), self.watch.0.clone());
-        Ok(res)
+                .into()),
+            },
+            Err(err) => Err(err.into()),
+        }
     }
 
-    #[cfg(feature = "observe")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
-    /// Receive notifications when the DB is updated
-    pub fn watch(&self) -> &watch::Receiver<()> {
-        let (_, rx) = &self.watch;
-        rx
+    /// Get the item with the least key in the range, if any
+    #[allow(clippy::type_complexity)]
+    fn first_in_range<'a, 'env, 'txn, Tx, R>(
+        &self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let () = self.record_range_init();
+        // The cursor is positioned when reading the first item, so errors
+        // reading the first item are range initialization errors
+        let first = || {
+            let start = encode_bound::<KC>(range.start_bound())
+                .map_err(heed::Error::Encoding)?;
+            let end = encode_bound::<KC>(range.end_bound())
+                .map_err(heed::Error::Encoding)?;
+            // heed skips an excluded start bound only if it is equal
+            // bytewise
+            let Some((key_bytes, value_bytes)) = self
+                .heed_db
+                .remap_types::<Bytes, Bytes>()
+                .range(
+                    txn.read_txn(),
+                    &(as_slice_bound(&start), Bound::Unbounded),
+                )?
+                .find(|item| {
+                    item.as_ref().map_or(true, |(key_bytes, _)| {
+                        is_after_start::<C>(key_bytes, &start)
+                    })
+                })
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            if !is_before_end::<C>(key_bytes, &end) {
+                return Ok(None);
+            }
+            let key =
+                KC::bytes_decode(key_bytes).map_err(heed::Error::Decoding)?;
+            let value =
+                DC::bytes_decode(value_bytes).map_err(heed::Error::Decoding)?;
+            Ok(Some((key, value)))
+        };
+        first().map_err(|err| error::RangeInit {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        })
     }
-}
 
-/// Read-only wrapper for [`heed::Database`]
-#[derive(Educe)]
-#[educe(Clone, Debug)]
-pub struct RoDatabaseUnique<'env_id, KC, DC, C = DefaultComparator> {
-    inner: DbWrapper<'env_id, KC, DC, C>,
-}
+    /// Get the item with the greatest key in the range, if any
+    #[allow(clippy::type_complexity)]
+    fn last_in_range<'a, 'env, 'txn, Tx, R>(
+        &self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let () = self.record_range_init();
+        // The cursor is positioned when reading the first item, so errors
+        // reading the first item are range initialization errors
+        let last = || {
+            let start = encode_bound::<KC>(range.start_bound())
+                .map_err(heed::Error::Encoding)?;
+            let end = encode_bound::<KC>(range.end_bound())
+                .map_err(heed::Error::Encoding)?;
+            let end = self.resolve_included_end(txn.read_txn(), end)?;
+            let Some((key_bytes, value_bytes)) = self
+                .heed_db
+                .remap_types::<Bytes, Bytes>()
+                .rev_range(
+                    txn.read_txn(),
+                    &(Bound::Unbounded, as_slice_bound(&end)),
+                )?
+                .next()
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            if !is_after_start::<C>(key_bytes, &start) {
+                return Ok(None);
+            }
+            let key =
+                KC::bytes_decode(key_bytes).map_err(heed::Error::Decoding)?;
+            let value =
+                DC::bytes_decode(value_bytes).map_err(heed::Error::Decoding)?;
+            Ok(Some((key, value)))
+        };
+        last().map_err(|err| error::RangeInit {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        })
+    }
+
+    /// Get the item with the greatest key that is less than or equal to
+    /// `upper`, if it starts with `prefix`
+    #[allow(clippy::type_complexity)]
+    fn last_in_prefix_at_or_before<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        prefix: &[u8],
+        upper: &'a KC::EItem,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let () = self.record_range_init();
+        let last = || {
+            let upper = <KC as BytesEncode>::bytes_encode(upper)
+                .map_err(heed::Error::Encoding)?;
+            let end = self
+                .resolve_included_end(txn.read_txn(), Bound::Included(upper))?;
+            // The cursor is positioned at `upper`, or the previous key if
+            // `upper` does not exist, which may belong to another prefix
+            let Some((key_bytes, value_bytes)) = self
+                .heed_db
+                .remap_types::<Bytes, Bytes>()
+                .rev_range(
+                    txn.read_txn(),
+                    &(Bound::Unbounded, as_slice_bound(&end)),
+                )?
+                .next()
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            if !key_bytes.starts_with(prefix) {
+                return Ok(None);
+            }
+            let key =
+                KC::bytes_decode(key_bytes).map_err(heed::Error::Decoding)?;
+            let value =
+                DC::bytes_decode(value_bytes).map_err(heed::Error::Decoding)?;
+            Ok(Some((key, value)))
+        };
+        last().map_err(|err| error::RangeInit {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        })
+    }
+
+    /// Iterate over a range of keys, decoding items with `KD` and `DD`.
+    fn range_remapped<'a, 'env, 'txn, Tx, R, KD, DD>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KD::DItem, DD::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        KD: BytesDecode<'txn> + 'txn,
+        DD: BytesDecode<'txn> + 'txn,
+        C: Comparator,
+    {
+        self.range_bounds_remapped::<_, KD, DD>(
+            txn,
+            range.start_bound(),
+            range.end_bound(),
+        )
+    }
+
+    /// Iterate over the keys between the start and end bounds, decoding
+    /// items with `KD` and `DD`.
+    fn range_bounds_remapped<'a, 'env, 'txn, Tx, KD, DD>(
+        &'a self,
+        txn: &'txn Tx,
+        start: Bound<&'a KC::EItem>,
+        end: Bound<&'a KC::EItem>,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KD::DItem, DD::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        KD: BytesDecode<'txn> + 'txn,
+        DD: BytesDecode<'txn> + 'txn,
+        C: Comparator,
+    {
+        let () = self.record_range_init();
+        let init = || {
+            let start =
+                encode_bound::<KC>(start).map_err(heed::Error::Encoding)?;
+            let end = encode_bound::<KC>(end).map_err(heed::Error::Encoding)?;
+            // The end bound is checked with the comparator, since heed
+            // compares end bounds lexicographically
+            let it = self.heed_db.remap_types::<Bytes, Bytes>().range(
+                txn.read_txn(),
+                &(as_slice_bound(&start), Bound::Unbounded),
+            )?;
+            Ok((it, start, end))
+        };
+        match init() {
+            Ok((it, start, end)) => Ok(it
+                .transpose_into_fallible()
+                // heed skips an excluded start bound only if it is equal
+                // bytewise
+                .skip_while(move |(key_bytes, _)| {
+                    Ok(!is_after_start::<C>(key_bytes, &start))
+                })
+                .take_while(move |(key_bytes, _)| {
+                    Ok(is_before_end::<C>(key_bytes, &end))
+                })
+                .map(|(key_bytes, value_bytes)| {
+                    let key = KD::bytes_decode(key_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    let value = DD::bytes_decode(value_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    Ok((key, value))
+                })
+                .map_err({
+                    let db_path = &*self.path;
+                    let name = self.name();
+                    |err| error::IterItem {
+                        db_name: name.to_owned(),
+                        db_path: db_path.to_owned(),
+                        source: err,
+                    }
+                })),
+            Err(err) => Err(error::RangeInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            }),
+        }
+    }
+
+    fn range<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.range_remapped::<_, _, KC, DC>(txn, range)
+    }
+
+    /// Check if there are any items in a range of keys.
+    /// Stops at the first item in the range, without decoding it.
+    fn range_non_empty<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<bool, error::RangeInit>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        let mut items = self
+            .range_remapped::<_, _, DecodeIgnore, DecodeIgnore>(txn, range)?;
+        // The cursor is positioned at the start of the range when the
+        // first item is read, so errors here are initialization errors
+        items.next().map(|item| item.is_some()).map_err(|err| {
+            error::RangeInit {
+                db_name: err.db_name,
+                db_path: err.db_path,
+                source: err.source,
+            }
+        })
+    }
+
+    /// Iterate over a range of keys, skipping the first `n` items without
+    /// decoding them
+    fn range_skip<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+        n: u64,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let items = self.range_remapped::<_, _, Bytes, Bytes>(txn, range)?;
+        Ok(self.decode_items(skip_while_n(items, n)))
+    }
+
+    /// Iterate over the values with keys in the specified range.
+    /// Keys are not decoded.
+    fn range_values<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = DC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let it = self.range_remapped::<_, _, DecodeIgnore, DC>(txn, range)?;
+        Ok(it.map(|((), value)| Ok(value)))
+    }
+
+    /// heed positions a reverse range at an included end bound with the
+    /// comparator, but only includes a key equal to the bound if it is
+    /// equal bytewise.
+    /// Replaces an included end bound with the stored key that is equal to
+    /// it according to the comparator, if any.
+    fn resolve_included_end<'b>(
+        &self,
+        rotxn: &heed::RoTxn<'_>,
+        end: Bound<Cow<'b, [u8]>>,
+    ) -> Result<Bound<Cow<'b, [u8]>>, heed::Error>
+    where
+        C: Comparator,
+    {
+        let Bound::Included(end_bytes) = end else {
+            return Ok(end);
+        };
+        match self
+            .heed_db
+            .remap_types::<Bytes, DecodeIgnore>()
+            .get_greater_than_or_equal_to(rotxn, &end_bytes)?
+        {
+            Some((key_bytes, ()))
+                if key_bytes != &*end_bytes
+                    && C::compare(key_bytes, &end_bytes) == Ordering::Equal =>
+            {
+                Ok(Bound::Included(Cow::Owned(key_bytes.to_vec())))
+            }
+            _ => Ok(Bound::Included(end_bytes)),
+        }
+    }
+
+    /// Iterate over the items with keys in the specified range, in reverse
+    /// order, using the specified decoders
+    fn rev_range_remapped<'a, 'env, 'txn, Tx, R, KD, DD>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KD::DItem, DD::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        KD: BytesDecode<'txn> + 'txn,
+        DD: BytesDecode<'txn> + 'txn,
+        C: Comparator,
+    {
+        let () = self.record_range_init();
+        let init = || {
+            let start = encode_bound::<KC>(range.start_bound())
+                .map_err(heed::Error::Encoding)?;
+            let end = encode_bound::<KC>(range.end_bound())
+                .map_err(heed::Error::Encoding)?;
+            let end = self.resolve_included_end(txn.read_txn(), end)?;
+            // The start bound is checked with the comparator, since heed
+            // compares start bounds lexicographically
+            let it = self.heed_db.remap_types::<Bytes, Bytes>().rev_range(
+                txn.read_txn(),
+                &(Bound::Unbounded, as_slice_bound(&end)),
+            )?;
+            Ok((it, start))
+        };
+        match init() {
+            Ok((it, start)) => Ok(it
+                .transpose_into_fallible()
+                .take_while(move |(key_bytes, _)| {
+                    Ok(is_after_start::<C>(key_bytes, &start))
+                })
+                .map(|(key_bytes, value_bytes)| {
+                    let key = KD::bytes_decode(key_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    let value = DD::bytes_decode(value_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    Ok((key, value))
+                })
+                .map_err({
+                    let db_path = &*self.path;
+                    let name = self.name();
+                    |err| error::IterItem {
+                        db_name: name.to_owned(),
+                        db_path: db_path.to_owned(),
+                        source: err,
+                    }
+                })),
+            Err(err) => Err(error::RangeInit {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                source: err,
+            }),
+        }
+    }
+
+    /// Iterate over the keys in the specified range.
+    /// Values are not decoded.
+    fn range_keys<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        let it = self.range_remapped::<_, _, KC, DecodeIgnore>(txn, range)?;
+        Ok(it.map(|(key, ())| Ok(key)))
+    }
+
+    /// Iterate over the keys in the specified range, in reverse order.
+    /// Values are not decoded.
+    fn rev_range_keys<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        let it =
+            self.rev_range_remapped::<_, _, KC, DecodeIgnore>(txn, range)?;
+        Ok(it.map(|(key, ())| Ok(key)))
+    }
+
+    /// Iterate over each distinct key in the specified range.
+    /// Values are not decoded.
+    fn range_keys_unique<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        let mut items = self
+            .range_remapped::<_, _, Bytes, DecodeIgnore>(txn, range)?
+            .peekable();
+        let db_name = &*self.name;
+        let db_path = &*self.path;
+        Ok(fallible_iterator::from_fn(move || {
+            let Some((key_bytes, ())) = items.next()? else {
+                return Ok(None);
+            };
+            while items
+                .next_if(|(next_key_bytes, ())| *next_key_bytes == key_bytes)?
+                .is_some()
+            {}
+            let key =
+                KC::bytes_decode(key_bytes).map_err(|err| error::IterItem {
+                    db_name: db_name.to_owned(),
+                    db_path: db_path.to_owned(),
+                    source: heed::Error::Decoding(err),
+                })?;
+            Ok(Some(key))
+        }))
+    }
+
+    fn update_range<'a, R, F, K, V, E>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        range: &'a R,
+        mut f: F,
+    ) -> Result<usize, error::UpdateRange>
+    where
+        KC: BytesEncode<'a> + for<'k> BytesDecode<'k, DItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>
+            + for<'v> BytesEncode<'v, EItem = E>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+        F: FnMut(&K, V) -> Option<E>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let item_err = |err| error::RangeMutItem {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        };
+        let init_err = |err| error::RangeMutInit {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        };
+        let start = encode_bound::<KC>(range.start_bound())
+            .map_err(|err| init_err(heed::Error::Encoding(err)))?;
+        let end = encode_bound::<KC>(range.end_bound())
+            .map_err(|err| init_err(heed::Error::Encoding(err)))?;
+        // The end bound is checked with the comparator, since heed
+        // compares end bounds lexicographically
+        let mut it = self
+            .heed_db
+            .remap_types::<Bytes, Bytes>()
+            .range_mut(
+                rwtxn.write_txn(),
+                &(as_slice_bound(&start), Bound::Unbounded),
+            )
+            .map_err(init_err)?;
+        let mut modified = 0;
+        while let Some(item) = it.next() {
+            let (key_bytes, value_bytes) = item.map_err(item_err)?;
+            // heed skips an excluded start bound only if it is equal
+            // bytewise
+            if !is_after_start::<C>(key_bytes, &start) {
+                continue;
+            }
+            if !is_before_end::<C>(key_bytes, &end) {
+                break;
+            }
+            let key = KC::bytes_decode(key_bytes)
+                .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+            let value = DC::bytes_decode(value_bytes)
+                .map_err(|err| item_err(heed::Error::Decoding(err)))?;
+            // Copy the key, as references into the db must not be held
+            // while writing to the current item
+            let key_bytes = key_bytes.to_vec();
+            let Some(new_value) = f(&key, value) else {
+                continue;
+            };
+            let put_err = |err: heed::Error| error::Put {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Box::new(Ok(key_bytes.clone())),
+                value_bytes: Box::new(
+                    DC::bytes_encode(&new_value)
+                        .map(|value_bytes| value_bytes.to_vec()),
+                ),
+                source: err.into(),
+            };
+            let new_value_bytes = DC::bytes_encode(&new_value)
+                .map_err(|err| put_err(heed::Error::Encoding(err)))?;
+            // Safety: no references into the db are held
+            let _: bool =
+                unsafe { it.put_current(&key_bytes, &new_value_bytes) }
+                    .map_err(put_err)?;
+            modified += 1;
+        }
+        drop(it);
+        #[cfg(feature = "observe")]
+        if modified > 0 {
+            let _watch_tx: Option<watch::Sender<_>> = rwtxn
+                .pending_writes
+                .insert(self.name.clone(), self.watch.0.clone());
+        }
+        Ok(modified)
+    }
+
+    /// Remove and return the first item, using a single cursor
+    fn pop_first<K, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<Option<(K, V)>, error::PopFirst>
+    where
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let err = |err| error::PopFirst {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            source: err,
+        };
+        let mut it = self.heed_db.iter_mut(rwtxn.write_txn()).map_err(err)?;
+        let Some(item) = it.next().transpose().map_err(err)? else {
+            return Ok(None);
+        };
+        // Safety: the item is decoded into owned values, so no references
+        // into the db are held
+        let _deleted: bool = unsafe { it.del_current() }.map_err(err)?;
+        drop(it);
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(Some(item))
+    }
+
+    /// Remove and return the value for the specified key, if it exists
+    fn take<'a, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+    ) -> Result<Option<V>, error::Take>
+    where
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let err = |err| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec());
+            error::Take {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes,
+                source: err,
+            }
+        };
+        let heed_rwtxn = rwtxn.write_txn();
+        let Some(value) = self.heed_db.get(heed_rwtxn, key).map_err(err)?
+        else {
+            return Ok(None);
+        };
+        let _deleted: bool =
+            self.heed_db.delete(heed_rwtxn, key).map_err(err)?;
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(Some(value))
+    }
+
+    /// Encode a key, for a write that operates on key bytes
+    fn encode_key<'a>(
+        &self,
+        key: &'a KC::EItem,
+    ) -> Result<Cow<'a, [u8]>, error::TryGet>
+    where
+        KC: BytesEncode<'a>,
+    {
+        KC::bytes_encode(key).map_err(|err| error::TryGet {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+            key_bytes: KC::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec()),
+            source: heed::Error::Encoding(err),
+        })
+    }
+
+    /// Read the value bytes for a key, copying them so that no references
+    /// into the db are held
+    fn get_value_bytes(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>, error::TryGet> {
+        self.heed_db
+            .remap_types::<Bytes, Bytes>()
+            .get(rwtxn.write_txn(), key_bytes)
+            .map(|value_bytes| value_bytes.map(<[u8]>::to_vec))
+            .map_err(|err| error::TryGet {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Ok(key_bytes.to_vec()),
+                source: err,
+            })
+    }
+
+    /// Write value bytes for a key, without notifying watchers
+    fn put_value_bytes(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_bytes: &[u8],
+        value_bytes: &[u8],
+    ) -> Result<(), error::Put> {
+        self.heed_db
+            .remap_types::<Bytes, Bytes>()
+            .put(rwtxn.write_txn(), key_bytes, value_bytes)
+            .map_err(|err| error::Put {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Box::new(Ok(key_bytes.to_vec())),
+                value_bytes: Box::new(Ok(value_bytes.to_vec())),
+                source: err.into(),
+            })
+    }
+
+    /// Move the value for `old_key` to `new_key`, without decoding it.
+    /// Returns `false` if `old_key` does not exist, or if the keys are
+    /// equal.
+    fn rename_key<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        old_key: &'a KC::EItem,
+        new_key: &'a KC::EItem,
+        overwrite: bool,
+    ) -> Result<bool, error::RenameKey>
+    where
+        KC: BytesEncode<'a>,
+        C: Comparator,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let old_key_bytes = self.encode_key(old_key)?;
+        let new_key_bytes = self.encode_key(new_key)?;
+        if C::compare(&old_key_bytes, &new_key_bytes).is_eq() {
+            return Ok(false);
+        }
+        let Some(value_bytes) = self.get_value_bytes(rwtxn, &old_key_bytes)?
+        else {
+            return Ok(false);
+        };
+        if !overwrite && self.get_value_bytes(rwtxn, &new_key_bytes)?.is_some()
+        {
+            return Err(error::DuplicateKey {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: new_key_bytes.into_owned(),
+            }
+            .into());
+        }
+        let () = self.put_value_bytes(rwtxn, &new_key_bytes, &value_bytes)?;
+        let _deleted: bool = self
+            .heed_db
+            .remap_types::<Bytes, Bytes>()
+            .delete(rwtxn.write_txn(), &old_key_bytes)
+            .map_err(|err| error::Delete {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Ok(old_key_bytes.to_vec()),
+                source: err,
+            })?;
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(true)
+    }
+
+    /// Exchange the values for two keys, without decoding them.
+    /// Returns `false` without writing if either key does not exist.
+    fn swap_keys<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_a: &'a KC::EItem,
+        key_b: &'a KC::EItem,
+    ) -> Result<bool, error::SwapKeys>
+    where
+        KC: BytesEncode<'a>,
+        C: Comparator,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let key_a_bytes = self.encode_key(key_a)?;
+        let key_b_bytes = self.encode_key(key_b)?;
+        let Some(value_a_bytes) = self.get_value_bytes(rwtxn, &key_a_bytes)?
+        else {
+            return Ok(false);
+        };
+        if C::compare(&key_a_bytes, &key_b_bytes).is_eq() {
+            return Ok(true);
+        }
+        let Some(value_b_bytes) = self.get_value_bytes(rwtxn, &key_b_bytes)?
+        else {
+            return Ok(false);
+        };
+        let () = self.put_value_bytes(rwtxn, &key_a_bytes, &value_b_bytes)?;
+        let () = self.put_value_bytes(rwtxn, &key_b_bytes, &value_a_bytes)?;
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(true)
+    }
+
+    /// Add `by` to the value for a key, treating a missing value as `0`.
+    /// Returns the new value.
+    fn increment<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        by: i64,
+    ) -> Result<i64, error::Increment>
+    where
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = i64>
+            + for<'v> BytesEncode<'v, EItem = i64>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let key_bytes = self.encode_key(key)?;
+        let value = match self.get_value_bytes(rwtxn, &key_bytes)? {
+            Some(value_bytes) => {
+                DC::bytes_decode(&value_bytes).map_err(|err| error::TryGet {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    key_bytes: Ok(key_bytes.to_vec()),
+                    source: heed::Error::Decoding(err),
+                })?
+            }
+            None => 0,
+        };
+        let Some(new_value) = value.checked_add(by) else {
+            return Err(error::Overflow {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: key_bytes.into_owned(),
+                value,
+                by,
+            }
+            .into());
+        };
+        let new_value_bytes =
+            DC::bytes_encode(&new_value).map_err(|err| error::Put {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Box::new(Ok(key_bytes.to_vec())),
+                value_bytes: Box::new(
+                    DC::bytes_encode(&new_value)
+                        .map(|value_bytes| value_bytes.to_vec()),
+                ),
+                source: heed::Error::Encoding(err).into(),
+            })?;
+        let () = self.put_value_bytes(rwtxn, &key_bytes, &new_value_bytes)?;
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(new_value)
+    }
+
+    fn remap_types<KC2, DC2>(&self) -> DbWrapper<'env_id, KC2, DC2, C> {
+        let heed_db = self.heed_db.remap_types();
+        DbWrapper {
+            unique_guard: self.unique_guard.clone(),
+            heed_db,
+            name: self.name.clone(),
+            path: self.path.clone(),
+            dup_fixed: self.dup_fixed,
+            check_value_size: self.check_value_size,
+            #[cfg(feature = "observe")]
+            watch: self.watch.clone(),
+            #[cfg(feature = "metrics")]
+            read_counters: self.read_counters.clone(),
+        }
+    }
+
+    /// # Safety
+    /// See [`Env::clone_with_tag`]
+    unsafe fn retag<'new>(self, env: &Env<'new>) -> DbWrapper<'new, KC, DC, C> {
+        DbWrapper {
+            unique_guard: env.unique_guard().clone(),
+            heed_db: self.heed_db,
+            name: self.name,
+            path: self.path,
+            dup_fixed: self.dup_fixed,
+            check_value_size: self.check_value_size,
+            #[cfg(feature = "observe")]
+            watch: self.watch,
+            #[cfg(feature = "metrics")]
+            read_counters: self.read_counters,
+        }
+    }
+
+    fn search<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        target: &'a KC::EItem,
+    ) -> Result<SearchResult<KC::DItem>, error::Search>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        C: Comparator,
+    {
+        let () = self.record_get();
+        let search = || {
+            let target_bytes = <KC as BytesEncode>::bytes_encode(target)
+                .map_err(heed::Error::Encoding)?;
+            let db = self.heed_db.remap_types::<Bytes, DecodeIgnore>();
+            let decode_key = |key_bytes| {
+                <KC as BytesDecode>::bytes_decode(key_bytes)
+                    .map_err(heed::Error::Decoding)
+            };
+            let next =
+                db.get_greater_than_or_equal_to(txn.read_txn(), &target_bytes)?;
+            let next = match next {
+                Some((key_bytes, ()))
+                    if C::compare(key_bytes, &target_bytes)
+                        == Ordering::Equal =>
+                {
+                    return Ok(SearchResult::Found(decode_key(key_bytes)?))
+                }
+                Some((key_bytes, ())) => Some(decode_key(key_bytes)?),
+                None => None,
+            };
+            let prev = match db.get_lower_than(txn.read_txn(), &target_bytes)? {
+                Some((key_bytes, ())) => Some(decode_key(key_bytes)?),
+                None => None,
+            };
+            Ok(SearchResult::NotFound { prev, next })
+        };
+        search().map_err(|err| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(target)
+                .map(|key_bytes| key_bytes.to_vec());
+            error::Search {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes,
+                source: err,
+            }
+        })
+    }
+
+    pub fn try_get<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Option<DC::DItem>, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.record_get();
+        self.heed_db.get(txn.read_txn(), key).map_err(|err| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec());
+            error::TryGet {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes,
+                source: err,
+            }
+        })
+    }
+
+    /// Get the value for a key, both decoded and as raw bytes
+    #[allow(clippy::type_complexity)]
+    fn try_get_with_bytes<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Option<(DC::DItem, &'txn [u8])>, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.record_get();
+        self.heed_db
+            .remap_data_type::<Bytes>()
+            .get(txn.read_txn(), key)
+            .and_then(|value_bytes| {
+                let Some(value_bytes) = value_bytes else {
+                    return Ok(None);
+                };
+                let value = DC::bytes_decode(value_bytes)
+                    .map_err(heed::Error::Decoding)?;
+                Ok(Some((value, value_bytes)))
+            })
+            .map_err(|err| {
+                let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                    .map(|key_bytes| key_bytes.to_vec());
+                error::TryGet {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    key_bytes,
+                    source: err,
+                }
+            })
+    }
+
+    pub fn get<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<DC::DItem, error::Get>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        self.try_get(txn, key)?.ok_or_else(|| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                // Safety: key must encode successfully, as try_get succeeded
+                .unwrap()
+                .to_vec();
+            error::Get::MissingValue {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes,
+            }
+        })
+    }
+
+    /// Attempt to insert a key-value pair in this database,
+    /// or if a value already exists for the key, returns the previous value.
+    /// The entry is always written with the NO_OVERWRITE flag.
+    /// Unlike [`heed::Database::get_or_put`], the previous value is decoded
+    /// against the write txn, rather than the key and value.
+    pub fn try_put<'a, 'txn>(
+        &self,
+        rwtxn: &'txn mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<Option<DC::DItem>, error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a> + BytesDecode<'txn>,
+    {
+        if self.put_if_absent(rwtxn, key, data)? {
+            return Ok(None);
+        }
+        self.heed_db.get(rwtxn.write_txn(), key).map_err(|err| {
+            let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                .map(|key_bytes| key_bytes.to_vec());
+            let value_bytes = <DC as BytesEncode>::bytes_encode(data)
+                .map(|value_bytes| value_bytes.to_vec());
+            error::Put {
+                db_name: (*self.name).to_owned(),
+                db_path: (*self.path).to_owned(),
+                key_bytes: Box::new(key_bytes),
+                value_bytes: Box::new(value_bytes),
+                source: err.into(),
+            }
+        })
+    }
+
+    fn put_if_absent<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<bool, error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let () = rwtxn.record_op(&self.name);
+        let () = self.check_put(rwtxn, key, data)?;
+        match self.heed_db.put_with_flags(
+            rwtxn.write_txn(),
+            PutFlags::NO_OVERWRITE,
+            key,
+            data,
+        ) {
+            Ok(()) => (),
+            Err(heed::Error::Mdb(heed::MdbError::KeyExist)) => {
+                return Ok(false)
+            }
+            Err(err) => {
+                let key_bytes = <KC as BytesEncode>::bytes_encode(key)
+                    .map(|key_bytes| key_bytes.to_vec());
+                let value_bytes = <DC as BytesEncode>::bytes_encode(data)
+                    .map(|value_bytes| value_bytes.to_vec());
+                return Err(error::Put {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    key_bytes: Box::new(key_bytes),
+                    value_bytes: Box::new(value_bytes),
+                    source: err.into(),
+                });
+            }
+        }
+        #[cfg(feature = "observe")]
+        let _watch_tx: Option<watch::Sender<_>> = rwtxn
+            .pending_writes
+            .insert(self.name.clone(), self.watch.0.clone());
+        Ok(true)
+    }
+
+    #[cfg(feature = "observe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+    /// Receive notifications when the DB is updated
+    pub fn watch(&self) -> &watch::Receiver<()> {
+        let (_, rx) = &self.watch;
+        rx
+    }
+
+    #[cfg(feature = "observe")]
+    async fn changed(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<bool, error::WatchClosed> {
+        let mut rx = self.watch().clone();
+        // Only wait for notifications sent after this point
+        let () = *rx.borrow_and_update();
+        let res = match timeout {
+            Some(timeout) => {
+                match tokio::time::timeout(timeout, rx.changed()).await {
+                    Ok(res) => res,
+                    Err(_elapsed) => return Ok(false),
+                }
+            }
+            None => rx.changed().await,
+        };
+        res.map(|()| true).map_err(|_| error::WatchClosed {
+            db_name: (*self.name).to_owned(),
+            db_path: (*self.path).to_owned(),
+        })
+    }
+}
+
+/// Read-only wrapper for [`heed::Database`]
+#[derive(Educe)]
+#[educe(Clone)]
+pub struct RoDatabaseUnique<'env_id, KC, DC, C = DefaultComparator> {
+    inner: DbWrapper<'env_id, KC, DC, C>,
+}
+
+impl<KC, DC, C> std::fmt::Debug for RoDatabaseUnique<'_, KC, DC, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt_debug("RoDatabaseUnique", f)
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Open a DB, if it exists
+    pub(crate) fn open<'env, Tx>(
+        env: &Env<'env_id>,
+        txn: &Tx,
+        name: &str,
+    ) -> Result<Option<Self>, env::error::OpenDb>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        let inner = DbWrapper::open(env, txn, name)?;
+        Ok(inner.map(|inner| Self { inner }))
+    }
+
+    /// Check if the provided key exists in the db.
+    /// The stored value is not decoded, if it exists.
+    #[inline(always)]
+    pub fn contains_key<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<bool, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        LazyDecode<DC>: BytesDecode<'txn>,
+    {
+        self.inner.contains_key(txn, key)
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn first<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::First>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.first(txn)
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn last<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::Last>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.last(txn)
+    }
+
+    /// Get the item at position `n` in key order, if any.
+    /// `nth(txn, 0)` is equivalent to [`Self::first`].
+    /// This advances a cursor `n` items from the first item, so it is
+    /// O(n), but does not decode or allocate skipped items.
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn nth<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::Iter>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.nth(txn, n)
+    }
+
+    /// Get the key at position `n` in key order, if any.
+    /// Like [`Self::nth`], this is O(n), and no keys or values are decoded
+    /// other than the `n`th key.
+    #[inline(always)]
+    pub fn nth_key<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<Option<KC::DItem>, error::Iter>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+    {
+        self.inner.nth_key(txn, n)
+    }
+
+    /// Get the item with the least key in the range, if any
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn first_in_range<'a, 'env, 'txn, Tx, R>(
+        &self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.first_in_range(txn, range)
+    }
+
+    /// Get the item with the greatest key in the range, if any
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn last_in_range<'a, 'env, 'txn, Tx, R>(
+        &self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.last_in_range(txn, range)
+    }
+
+    /// Get the item with the greatest key that is less than or equal to
+    /// `upper`, if its encoded key starts with `prefix`.
+    /// Returns `None` if there is no such item, or if the greatest key less
+    /// than or equal to `upper` does not start with `prefix`.
+    ///
+    /// This is useful for composite keys, eg. to look up the latest sample
+    /// at or before a timestamp for a series in a db keyed by
+    /// `(series_id, timestamp)`, without reading a sample from a
+    /// different series.
+    /// Keys with a common prefix are only contiguous if the comparator
+    /// orders keys lexicographically, as the default comparator does.
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn last_in_prefix_at_or_before<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        prefix: &[u8],
+        upper: &'a KC::EItem,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::RangeInit>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.last_in_prefix_at_or_before(txn, prefix, upper)
+    }
+
+    #[inline(always)]
+    pub fn iter<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.iter(txn)
+    }
+
+    /// Iterate over all items, yielding an error for each item that fails
+    /// to decode instead of stopping at the first one.
+    /// Unlike [`Self::iter`], this is a plain [`Iterator`], so that
+    /// iteration can continue after an error, eg. to report every
+    /// corrupt item in a db in a single pass.
+    /// Iteration stops after an error reading from the db, since the
+    /// cursor cannot advance past it.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions};
+    /// use sneed::types::{BigEndian, Bytes, U32};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let raw_db: DatabaseUnique<U32<BigEndian>, Bytes> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "values")?;
+    /// let () = raw_db.put(&mut rwtxn, &0, &0u32.to_be_bytes())?;
+    /// let () = raw_db.put(&mut rwtxn, &1, b"bad")?;
+    /// let () = raw_db.put(&mut rwtxn, &2, &2u32.to_be_bytes())?;
+    /// let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "values")?;
+    /// let (good, bad): (Vec<_>, Vec<_>) =
+    ///     db.iter_lossy(&rwtxn)?.partition(Result::is_ok);
+    /// assert_eq!(good.len(), 2);
+    /// assert_eq!(bad.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn iter_lossy<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl Iterator<Item = Result<(KC::DItem, DC::DItem), error::IterItem>> + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.iter_lossy(txn)
+    }
+
+    /// Iterate over all items, skipping the first `n` items, eg. for
+    /// offset-based pagination.
+    /// Skipping is still O(n) cursor movement, but skipped items are not
+    /// decoded.
+    #[inline(always)]
+    pub fn iter_skip<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+        n: u64,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.iter_skip(txn, n)
+    }
+
+    /// Iterate over all items, in chunks of up to `chunk_size` items.
+    /// Every chunk is full, except for the last chunk, which may be
+    /// smaller. No empty chunks are yielded.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use fallible_iterator::FallibleIterator as _;
+    /// use sneed::types::{BigEndian, U32};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<U32<BigEndian>, UnitKey> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// for n in 0..250 {
+    ///     let () = db.put(&mut rwtxn, &n, &())?;
+    /// }
+    /// let chunk_size = NonZeroUsize::new(100).unwrap();
+    /// let chunk_lens: Vec<usize> = db
+    ///     .iter_chunks(&rwtxn, chunk_size)?
+    ///     .map(|chunk| Ok(chunk.len()))
+    ///     .collect()?;
+    /// assert_eq!(chunk_lens, [100, 100, 50]);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn iter_chunks<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+        chunk_size: NonZeroUsize,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = Vec<(KC::DItem, DC::DItem)>,
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let mut items = self.inner.iter(txn)?.fuse();
+        Ok(fallible_iterator::from_fn(move || {
+            let mut chunk = Vec::with_capacity(chunk_size.get());
+            while chunk.len() < chunk_size.get() {
+                let Some(item) = items.next()? else {
+                    break;
+                };
+                chunk.push(item);
+            }
+            Ok((!chunk.is_empty()).then_some(chunk))
+        }))
+    }
+
+    /// Iterate over all items, without decoding keys or values.
+    #[inline(always)]
+    pub(crate) fn iter_bytes<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (&'txn [u8], &'txn [u8]),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.iter_remapped::<_, Bytes, Bytes>(txn)
+    }
+
+    pub fn iter_keys<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        LazyDecode<DC>: BytesDecode<'txn>,
+    {
+        self.inner.iter_keys(txn)
+    }
+
+    #[inline(always)]
+    pub fn lazy_decode(
+        &self,
+    ) -> RoDatabaseUnique<'env_id, KC, LazyDecode<DC>, C> {
+        RoDatabaseUnique {
+            inner: self.inner.lazy_decode(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn len<'env, 'txn, Tx>(&self, txn: &'txn Tx) -> Result<u64, error::Len>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.len(txn)
+    }
+
+    /// Describe the db, as `<name> (<len> entries)`, eg. for logging
+    #[inline(always)]
+    pub fn describe<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<String, error::Len>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.describe(txn)
+    }
+
+    /// Number of items in the db, as a `usize`.
+    /// Returns an [`error::LenOverflow`] error if the number of items
+    /// exceeds [`usize::MAX`], which is only possible on targets with
+    /// pointers narrower than 64 bits.
+    #[inline(always)]
+    pub fn len_usize<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<usize, error::LenUsize>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.len_usize(txn)
+    }
+
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Read counts for the db, and the time of the last read.
+    /// Counts are shared by every handle to the db in the same env,
+    /// and include reads through [`DatabaseUnique`] handles.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<UnitKey, UnitKey> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// let () = rwtxn.commit()?;
+    /// let rotxn = env.read_txn()?;
+    /// let _: Option<()> = db.try_get(&rotxn, &())?;
+    /// let _ = db.iter(&rotxn)?;
+    /// let stats = db.read_stats();
+    /// assert_eq!((stats.gets, stats.iter_inits), (1, 1));
+    /// assert!(stats.last_read.is_some());
+    /// assert_eq!(env.read_stats()["db"], stats);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    #[inline(always)]
+    pub fn read_stats(&self) -> ReadStats {
+        self.inner.read_counters.stats()
+    }
+
+    /// Identifier for the db, in the form `{env_path}::{db_name}`, where
+    /// `env_path` is the path that the env was opened with.
+    /// Useful for referring to dbs consistently in logs and metrics.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<UnitKey, UnitKey> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// assert_eq!(db.qualified_name(), format!("{}::db", path.display()));
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn qualified_name(&self) -> String {
+        self.inner.qualified_name()
+    }
+
+    /// The underlying heed db.
+    /// See [raw handles](crate#raw-handles).
+    #[cfg(feature = "raw")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+    #[inline(always)]
+    pub fn as_heed(&self) -> &heed::Database<KC, DC, C> {
+        &self.inner.heed_db
+    }
+
+    #[inline(always)]
+    pub(crate) fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Iterate over the items with keys in the specified range
+    #[inline(always)]
+    pub fn range<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.range(txn, range)
+    }
 
-impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
-    /// Check if the provided key exists in the db.
-    /// The stored value is not decoded, if it exists.
+    /// Check if there are any items in the specified range.
+    /// Stops at the first item in the range, without decoding it, so this
+    /// is cheaper than counting the items in the range.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions};
+    /// use sneed::types::{BigEndian, U32, Unit};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<U32<BigEndian>, Unit> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// for key in [1, 2, 3, 10, 11] {
+    ///     let () = db.put(&mut rwtxn, &key, &())?;
+    /// }
+    /// assert!(db.range_non_empty(&rwtxn, &(2..=3))?);
+    /// assert!(!db.range_non_empty(&rwtxn, &(4..10))?);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
     #[inline(always)]
-    pub fn contains_key<'a, 'env, 'txn, Tx>(
-        &self,
+    pub fn range_non_empty<'a, 'env, 'txn, Tx, R>(
+        &'a self,
         txn: &'txn Tx,
-        key: &'a KC::EItem,
-    ) -> Result<bool, error::TryGet>
+        range: &'a R,
+    ) -> Result<bool, error::RangeInit>
     where
+        'a: 'txn,
         'env: 'txn,
         Tx: Txn<'env, 'env_id>,
         KC: BytesEncode<'a>,
-        LazyDecode<DC>: BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
     {
-        self.inner.contains_key(txn, key)
+        self.inner.range_non_empty(txn, range)
     }
 
-    #[allow(clippy::type_complexity)]
+    /// Iterate over a range of keys, skipping the first `n` items in the
+    /// range, eg. for offset-based pagination.
+    /// Skipping is still O(n) cursor movement, but skipped items are not
+    /// decoded.
     #[inline(always)]
-    pub fn first<'env, 'txn, Tx>(
-        &self,
+    pub fn range_skip<'a, 'env, 'txn, Tx, R>(
+        &'a self,
         txn: &'txn Tx,
-    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::First>
+        range: &'a R,
+        n: u64,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
     where
+        'a: 'txn,
         'env: 'txn,
         Tx: Txn<'env, 'env_id>,
-        KC: BytesDecode<'txn>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
         DC: BytesDecode<'txn>,
+        C: Comparator,
     {
-        self.inner.first(txn)
+        self.inner.range_skip(txn, range, n)
     }
 
+    /// Iterate over the keys from `start` (inclusive) to `end`, which is
+    /// inclusive if `end_inclusive` is `true`, and exclusive otherwise.
+    /// This is equivalent to [`Self::range`] with `start..=end` or
+    /// `start..end`, without needing to construct a range that outlives
+    /// the iterator.
     #[inline(always)]
-    pub fn iter<'a, 'env, 'txn, Tx>(
+    pub fn range_from_to<'a, 'env, 'txn, Tx>(
         &'a self,
         txn: &'txn Tx,
+        start: &'a KC::EItem,
+        end: &'a KC::EItem,
+        end_inclusive: bool,
     ) -> Result<
         impl FallibleIterator<
                 Item = (KC::DItem, DC::DItem),
                 Error = error::IterItem,
             > + 'txn,
-        error::IterInit,
+        error::RangeInit,
     >
     where
         'a: 'txn,
         'env: 'txn,
         Tx: Txn<'env, 'env_id>,
-        KC: BytesDecode<'txn>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
         DC: BytesDecode<'txn>,
+        C: Comparator,
     {
-        self.inner.iter(txn)
+        let end = if end_inclusive {
+            Bound::Included(end)
+        } else {
+            Bound::Excluded(end)
+        };
+        self.inner.range_bounds_remapped::<_, KC, DC>(
+            txn,
+            Bound::Included(start),
+            end,
+        )
     }
 
-    pub fn iter_keys<'a, 'env, 'txn, Tx>(
+    /// Iterate over the keys in the specified range.
+    /// Values are not decoded.
+    #[inline(always)]
+    pub fn range_keys<'a, 'env, 'txn, Tx, R>(
         &'a self,
         txn: &'txn Tx,
+        range: &'a R,
     ) -> Result<
         impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
-        error::IterInit,
+        error::RangeInit,
     >
     where
         'a: 'txn,
         'env: 'txn,
         Tx: Txn<'env, 'env_id>,
-        KC: BytesDecode<'txn>,
-        LazyDecode<DC>: BytesDecode<'txn>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
     {
-        self.inner.iter_keys(txn)
+        self.inner.range_keys(txn, range)
     }
 
+    /// Iterate over the keys in the specified range, in reverse order.
+    /// Values are not decoded.
     #[inline(always)]
-    pub fn lazy_decode(
-        &self,
-    ) -> RoDatabaseUnique<'env_id, KC, LazyDecode<DC>, C> {
-        RoDatabaseUnique {
-            inner: self.inner.lazy_decode(),
-        }
+    pub fn rev_range_keys<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        self.inner.rev_range_keys(txn, range)
     }
 
+    /// Iterate over the items with keys in the specified range,
+    /// without decoding keys or values.
     #[inline(always)]
-    pub fn len<'env, 'txn, Tx>(&self, txn: &'txn Tx) -> Result<u64, error::Len>
+    pub(crate) fn range_bytes<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (&'txn [u8], &'txn [u8]),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
     where
+        'a: 'txn,
+        'env: 'txn,
         Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
     {
-        self.inner.len(txn)
+        self.inner.range_remapped::<_, _, Bytes, Bytes>(txn, range)
     }
 
     #[inline(always)]
-    pub fn name(&self) -> &str {
-        &self.inner.name
+    pub(crate) fn remap_types<KC2, DC2>(
+        &self,
+    ) -> RoDatabaseUnique<'env_id, KC2, DC2, C> {
+        RoDatabaseUnique {
+            inner: self.inner.remap_types(),
+        }
+    }
+
+    /// Re-tag the db for use with a re-tagged env.
+    ///
+    /// # Safety
+    /// `env` must be a clone of the env that this db was created in.
+    /// See [`Env::clone_with_tag`].
+    #[inline(always)]
+    pub unsafe fn retag<'new>(
+        self,
+        env: &Env<'new>,
+    ) -> RoDatabaseUnique<'new, KC, DC, C> {
+        RoDatabaseUnique {
+            inner: self.inner.retag(env),
+        }
+    }
+
+    /// Search for the target key.
+    /// If the key does not exist, returns the neighboring keys.
+    ///
+    /// # Cost
+    /// An exact hit takes a single seek.
+    /// Otherwise, a second seek and one step back are needed to find the
+    /// previous key, since heed does not expose cursors that could step
+    /// back from the first seek.
+    #[inline(always)]
+    pub fn search<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        target: &'a KC::EItem,
+    ) -> Result<SearchResult<KC::DItem>, error::Search>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.search(txn, target)
     }
 
     #[inline(always)]
@@ -539,6 +3436,24 @@ impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
         self.inner.try_get(txn, key)
     }
 
+    /// Get the value for a key, both decoded and as the raw bytes stored in
+    /// the db, eg. for hashing the stored value
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn try_get_with_bytes<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Option<(DC::DItem, &'txn [u8])>, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.try_get_with_bytes(txn, key)
+    }
+
     #[inline(always)]
     pub fn get<'a, 'env, 'txn, Tx>(
         &self,
@@ -554,6 +3469,26 @@ impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
         self.inner.get(txn, key)
     }
 
+    /// Get the value for the specified key, or the default value if the key
+    /// does not exist.
+    /// Unlike [`Self::get`], a missing value is not an error.
+    #[inline(always)]
+    pub fn get_or_default<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<DC::DItem, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+        DC::DItem: Default,
+    {
+        let value = self.inner.try_get(txn, key)?;
+        Ok(value.unwrap_or_default())
+    }
+
     #[cfg(feature = "observe")]
     #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
     /// Receive notifications when the DB is updated
@@ -561,6 +3496,19 @@ impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
     pub fn watch(&self) -> &watch::Receiver<()> {
         self.inner.watch()
     }
+
+    #[cfg(feature = "observe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+    /// Wait for the next notification that the DB is updated.
+    /// Returns `false` if the timeout elapses first.
+    /// The timeout requires a tokio runtime with the time driver enabled.
+    #[inline(always)]
+    pub async fn changed(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<bool, error::WatchClosed> {
+        self.inner.changed(timeout).await
+    }
 }
 
 impl<KC, DC, C> Database for RoDatabaseUnique<'_, KC, DC, C> {
@@ -571,14 +3519,68 @@ impl<KC, DC, C> Database for RoDatabaseUnique<'_, KC, DC, C> {
     }
 }
 
+impl<'env_id, KC, DC, C> DatabaseRead<'env_id>
+    for RoDatabaseUnique<'env_id, KC, DC, C>
+{
+    fn len(&self, txn: &dyn Txn<'_, 'env_id>) -> Result<u64, error::Len> {
+        self.inner.len(txn)
+    }
+
+    fn iter_raw<'a, 'env, 'txn>(
+        &'a self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<RawIter<'txn>, error::IterInit>
+    where
+        'a: 'txn,
+        'env: 'txn,
+    {
+        let iter = self.inner.iter_remapped::<_, Raw, Raw>(txn)?;
+        Ok(Box::new(iter))
+    }
+
+    fn contains_key_raw(
+        &self,
+        txn: &dyn Txn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, error::TryGet> {
+        self.inner.contains_key_raw(txn, key)
+    }
+
+    fn first_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::First>
+    where
+        'env: 'txn,
+    {
+        self.inner.first_raw(txn)
+    }
+
+    fn last_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::Last>
+    where
+        'env: 'txn,
+    {
+        self.inner.last_raw(txn)
+    }
+}
+
 /// Wrapper for [`heed::Database`]
 #[derive(Educe)]
-#[educe(Clone, Debug)]
+#[educe(Clone)]
 #[repr(transparent)]
 pub struct DatabaseUnique<'env_id, KC, DC, C = DefaultComparator> {
     inner: RoDatabaseUnique<'env_id, KC, DC, C>,
 }
 
+impl<KC, DC, C> std::fmt::Debug for DatabaseUnique<'_, KC, DC, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.inner.fmt_debug("DatabaseUnique", f)
+    }
+}
+
 impl<'env_id, KC, DC, C> DatabaseUnique<'env_id, KC, DC, C> {
     pub fn create(
         env: &Env<'env_id>,
@@ -596,6 +3598,88 @@ impl<'env_id, KC, DC, C> DatabaseUnique<'env_id, KC, DC, C> {
         })
     }
 
+    /// Open a DB for writing, if it exists
+    pub(crate) fn open(
+        env: &Env<'env_id>,
+        rwtxn: &RwTxn<'_, 'env_id>,
+        name: &str,
+    ) -> Result<Option<Self>, env::error::OpenDb>
+    where
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        let inner = RoDatabaseUnique::open(env, rwtxn, name)?;
+        Ok(inner.map(|inner| Self { inner }))
+    }
+
+    /// Re-tag the db for use with a re-tagged env.
+    ///
+    /// # Safety
+    /// `env` must be a clone of the env that this db was created in.
+    /// See [`Env::clone_with_tag`].
+    #[inline(always)]
+    pub unsafe fn retag<'new>(
+        self,
+        env: &Env<'new>,
+    ) -> DatabaseUnique<'new, KC, DC, C> {
+        DatabaseUnique {
+            inner: self.inner.retag(env),
+        }
+    }
+
+    /// Check each value against the free space in the map before writing
+    /// it with [`Self::put`], [`Self::put_with_flags`], or
+    /// [`Self::insert`].
+    /// Values that are larger than the free space fail with an
+    /// [`error::Put`] caused by [`error::ValueTooLarge`], rather than
+    /// [`heed::MdbError::MapFull`].
+    ///
+    /// Free space is the space after the last page used as of the last
+    /// commit. Pages used by the current txn are not counted, and pages on
+    /// the freelist are not counted as free, so writes that pass the
+    /// check can still fail with `MapFull`, and writes that fail the check
+    /// might have fit in reused pages.
+    /// The check encodes each value an extra time, so it is disabled by
+    /// default.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+    /// use sneed::{db::error::PutSource, types::Bytes};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// make_guard!(guard);
+    /// let mut opts = EnvOpenOptions::new();
+    /// let _ = opts.map_size(1 << 20).max_dbs(1);
+    /// let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<UnitKey, Bytes> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// let db = db.check_value_size(true);
+    /// let err = db.put(&mut rwtxn, &(), &vec![0; 2 << 20]).unwrap_err();
+    /// let source = std::error::Error::source(&err)
+    ///     .and_then(|source| source.downcast_ref::<PutSource>());
+    /// assert!(matches!(source, Some(PutSource::ValueTooLarge(_))));
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn check_value_size(mut self, check: bool) -> Self {
+        self.inner.inner.check_value_size = check;
+        self
+    }
+
+    /// Delete all entries in the db
+    #[inline(always)]
+    pub fn clear(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), error::Clear> {
+        self.inner.inner.clear(rwtxn)
+    }
+
     #[inline(always)]
     pub fn delete<'a, 'env>(
         &self,
@@ -608,6 +3692,193 @@ impl<'env_id, KC, DC, C> DatabaseUnique<'env_id, KC, DC, C> {
         self.inner.inner.delete(rwtxn, key)
     }
 
+    /// Delete the item with the specified key, returning the value if it
+    /// existed.
+    /// This is the single-key analog of [`Self::pop_first`].
+    #[inline(always)]
+    pub fn take<'a, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+    ) -> Result<Option<V>, error::Take>
+    where
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.inner.take(rwtxn, key)
+    }
+
+    /// Move the value for `old_key` to `new_key`, without decoding or
+    /// re-encoding it.
+    /// If `new_key` already exists, it is overwritten if `overwrite` is
+    /// `true`, and otherwise an error is returned.
+    /// Returns `true` if the value was moved, and `false` if `old_key` does
+    /// not exist, or if the keys are equal.
+    #[inline(always)]
+    pub fn rename_key<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        old_key: &'a KC::EItem,
+        new_key: &'a KC::EItem,
+        overwrite: bool,
+    ) -> Result<bool, error::RenameKey>
+    where
+        KC: BytesEncode<'a>,
+        C: Comparator,
+    {
+        self.inner
+            .inner
+            .rename_key(rwtxn, old_key, new_key, overwrite)
+    }
+
+    /// Exchange the values for two keys, without decoding or re-encoding
+    /// them.
+    /// Returns `true` if both keys exist, and `false` without writing
+    /// otherwise.
+    #[inline(always)]
+    pub fn swap_keys<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_a: &'a KC::EItem,
+        key_b: &'a KC::EItem,
+    ) -> Result<bool, error::SwapKeys>
+    where
+        KC: BytesEncode<'a>,
+        C: Comparator,
+    {
+        self.inner.inner.swap_keys(rwtxn, key_a, key_b)
+    }
+
+    /// Add `by` to the value for the specified key, and return the new
+    /// value. A missing value is treated as `0`.
+    /// Only available for `i64` value codecs, such as
+    /// `sneed::types::I64<BigEndian>`.
+    /// Returns [`error::Overflow`] without writing if the new value would
+    /// overflow.
+    ///
+    /// ```
+    /// # use sneed::{db, make_guard, DatabaseUnique, Env, EnvOpenOptions};
+    /// use sneed::types::{BigEndian, I64, Str};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<Str, I64<BigEndian>> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "counters")?;
+    /// assert_eq!(db.increment(&mut rwtxn, "hits", 1)?, 1);
+    /// assert_eq!(db.increment(&mut rwtxn, "hits", 2)?, 3);
+    /// assert_eq!(db.increment(&mut rwtxn, "misses", -1)?, -1);
+    /// let () = db.put(&mut rwtxn, "hits", &i64::MAX)?;
+    /// assert!(matches!(
+    ///     db.increment(&mut rwtxn, "hits", 1),
+    ///     Err(db::error::Increment::Overflow(_))
+    /// ));
+    /// assert_eq!(db.try_get(&rwtxn, "hits")?, Some(i64::MAX));
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn increment<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        by: i64,
+    ) -> Result<i64, error::Increment>
+    where
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = i64>
+            + for<'v> BytesEncode<'v, EItem = i64>,
+    {
+        self.inner.inner.increment(rwtxn, key, by)
+    }
+
+    /// Reserve `size` bytes for the value for the specified key, and write
+    /// the value in place with `write`, avoiding a copy of the encoded
+    /// value.
+    /// `write` must write exactly `size` bytes. If it writes fewer,
+    /// [`error::ReservedUnderrun`] is returned, since LMDB would otherwise
+    /// store the unwritten bytes as part of the value.
+    ///
+    /// If an error is returned after the space was reserved, the value for
+    /// the key is incomplete, and the txn should be aborted.
+    ///
+    /// ```
+    /// # use sneed::{db, make_guard, DatabaseUnique, Env, EnvOpenOptions};
+    /// use std::io::Write as _;
+    ///
+    /// use sneed::types::{Bytes, Str};
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<Str, Bytes> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// let () = db.put_reserved(&mut rwtxn, "full", 4, |reserved| {
+    ///     reserved.write_all(b"abcd")
+    /// })?;
+    /// assert_eq!(db.try_get(&rwtxn, "full")?, Some(&b"abcd"[..]));
+    /// assert!(matches!(
+    ///     db.put_reserved(&mut rwtxn, "short", 4, |reserved| {
+    ///         reserved.write_all(b"ab")
+    ///     }),
+    ///     Err(db::error::PutReserved::ReservedUnderrun(_))
+    /// ));
+    /// rwtxn.abort();
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn put_reserved<'a, F>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        size: usize,
+        write: F,
+    ) -> Result<(), error::PutReserved>
+    where
+        KC: BytesEncode<'a>,
+        F: FnOnce(&mut ReservedSpace<'_>) -> std::io::Result<()>,
+    {
+        self.inner.inner.put_reserved(rwtxn, key, size, write)
+    }
+
+    /// Get the entry for the specified key, for in-place manipulation.
+    pub fn entry<'a, 'env, K, V>(
+        &'a self,
+        rwtxn: &'a mut RwTxn<'env, 'env_id>,
+        key: &'a K,
+    ) -> Result<Entry<'a, 'env, 'env_id, KC, DC, C, K, V>, error::TryGet>
+    where
+        KC: for<'k> BytesEncode<'k, EItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+        K: ?Sized,
+    {
+        let entry = match self.try_get(rwtxn, key)? {
+            Some(value) => Entry::Occupied(OccupiedEntry {
+                db: self,
+                rwtxn,
+                key,
+                value,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                db: self,
+                rwtxn,
+                key,
+            }),
+        };
+        Ok(entry)
+    }
+
     #[inline(always)]
     pub fn lazy_decode(
         &self,
@@ -617,39 +3888,186 @@ impl<'env_id, KC, DC, C> DatabaseUnique<'env_id, KC, DC, C> {
         }
     }
 
+    /// Remove and return the first item in the db, if any
+    #[inline(always)]
+    pub fn pop_first<K, V>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<Option<(K, V)>, error::PopFirst>
+    where
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        self.inner.inner.pop_first(rwtxn)
+    }
+
     #[inline(always)]
     pub fn put<'a, 'env>(
         &self,
-        rwtxn: &mut RwTxn<'env, 'env_id>,
+        rwtxn: &mut RwTxn<'env, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        self.inner
+            .inner
+            .put_with_flags(rwtxn, PutFlags::empty(), key, data)
+    }
+
+    /// Write a key-value pair, failing with
+    /// [`error::Insert::AlreadyExists`] if the key already exists.
+    /// Unlike [`Self::try_put`], the existing value is not read, so the
+    /// value codec does not need to implement [`BytesDecode`].
+    #[inline(always)]
+    pub fn insert<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::Insert>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        self.inner.inner.insert(rwtxn, key, data)
+    }
+
+    /// Write with the specified flags.
+    ///
+    /// Valid flags for dbs with unique keys are:
+    /// * [`PutFlags::NO_OVERWRITE`]: fail with [`error::KeyExists`] if the
+    ///   key already exists
+    /// * [`PutFlags::APPEND`]: append the item to the end of the db, for
+    ///   fast bulk loading of sorted keys. Fails with
+    ///   [`error::KeyExists`] if the key is not greater than the last key
+    ///   in the db.
+    ///
+    /// Other flags apply only to dbs with duplicate keys, and fail with
+    /// [`error::InvalidPutFlags`].
+    #[inline(always)]
+    pub fn put_with_flags<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<(), error::Put>
+    ) -> Result<(), error::PutWithFlags>
     where
         KC: BytesEncode<'a>,
         DC: BytesEncode<'a>,
     {
+        self.inner.inner.put_with_valid_flags(
+            rwtxn,
+            PutFlags::NO_OVERWRITE | PutFlags::APPEND,
+            flags,
+            key,
+            data,
+        )
+    }
+
+    /// Write an encoded key-value pair, bypassing the key and value codecs.
+    pub(crate) fn put_bytes(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key_bytes: &[u8],
+        value_bytes: &[u8],
+    ) -> Result<(), error::Put> {
         self.inner
             .inner
-            .put_with_flags(rwtxn, PutFlags::empty(), key, data)
+            .remap_types::<Bytes, Bytes>()
+            .put_with_flags(rwtxn, PutFlags::empty(), key_bytes, value_bytes)
     }
 
     /// Attempt to insert a key-value pair in this database,
     /// or if a value already exists for the key, returns the previous value.
     /// The entry is always written with the NO_OVERWRITE flag.
-    /// See [`heed::Database::get_or_put`]
+    /// The previous value borrows from the write txn, so values that borrow
+    /// from the db, such as [`heed::types::Str`], can outlive the key and
+    /// value that were written.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions};
+    /// use sneed::types::Str;
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<Str, Str> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "names")?;
+    /// let previous: Option<&str> = {
+    ///     let key = String::from("alice");
+    ///     assert_eq!(db.try_put(&mut rwtxn, &key, "first")?, None);
+    ///     let value = String::from("second");
+    ///     db.try_put(&mut rwtxn, &key, &value)?
+    /// };
+    /// assert_eq!(previous, Some("first"));
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
     #[inline(always)]
-    pub fn try_put<'a, 'env, 'txn>(
-        &'txn self,
-        rwtxn: &mut RwTxn<'env, 'env_id>,
+    pub fn try_put<'a, 'txn>(
+        &self,
+        rwtxn: &'txn mut RwTxn<'_, 'env_id>,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
     ) -> Result<Option<DC::DItem>, error::Put>
     where
         KC: BytesEncode<'a>,
-        DC: BytesEncode<'a> + BytesDecode<'a>,
+        DC: BytesEncode<'a> + BytesDecode<'txn>,
     {
         self.inner.inner.try_put(rwtxn, key, data)
     }
+
+    /// Insert a key-value pair if the key does not exist.
+    /// Returns `true` if the pair was inserted, or `false` if a value
+    /// already exists for the key. Unlike [`Self::try_put`], the existing
+    /// value is not decoded.
+    /// The entry is always written with the NO_OVERWRITE flag.
+    /// The value is checked as with [`Self::put`], eg. if
+    /// [`Self::check_value_size`] is enabled, even if the key exists.
+    #[inline(always)]
+    pub fn put_if_absent<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<bool, error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        self.inner.inner.put_if_absent(rwtxn, key, data)
+    }
+
+    /// Update values in a range in-place.
+    /// For each item in the range, `f` is called with the key and value.
+    /// If `f` returns a new value, the value is replaced.
+    /// Returns the number of values that were replaced.
+    #[inline(always)]
+    pub fn update_range<'a, R, F, K, V, E>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        range: &'a R,
+        f: F,
+    ) -> Result<usize, error::UpdateRange>
+    where
+        KC: BytesEncode<'a> + for<'k> BytesDecode<'k, DItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>
+            + for<'v> BytesEncode<'v, EItem = E>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+        F: FnMut(&K, V) -> Option<E>,
+    {
+        self.inner.inner.update_range(rwtxn, range, f)
+    }
 }
 
 impl<'env_id, KC, DC, C> std::ops::Deref
@@ -664,11 +4082,17 @@ impl<'env_id, KC, DC, C> std::ops::Deref
 
 /// Read-only wrapper for [`heed::Database`] with duplicate keys
 #[derive(Educe)]
-#[educe(Clone, Debug)]
+#[educe(Clone)]
 pub struct RoDatabaseDup<'id, KC, DC, C = DefaultComparator> {
     inner: DbWrapper<'id, KC, DC, C>,
 }
 
+impl<KC, DC, C> std::fmt::Debug for RoDatabaseDup<'_, KC, DC, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt_debug("RoDatabaseDup", f)
+    }
+}
+
 impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
     /// Check if the provided key exists in the db.
     /// The stored value is not decoded, if it exists.
@@ -687,6 +4111,70 @@ impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
         self.inner.contains_key(txn, key)
     }
 
+    /// Re-tag the db for use with a re-tagged env.
+    ///
+    /// # Safety
+    /// `env` must be a clone of the env that this db was created in.
+    /// See [`Env::clone_with_tag`].
+    #[inline(always)]
+    pub unsafe fn retag<'new>(
+        self,
+        env: &Env<'new>,
+    ) -> RoDatabaseDup<'new, KC, DC, C> {
+        RoDatabaseDup {
+            inner: self.inner.retag(env),
+        }
+    }
+
+    /// Iterate over all items, without decoding keys or values.
+    /// Each duplicate value is yielded with its key,
+    /// in key-then-value order.
+    #[inline(always)]
+    pub(crate) fn iter_bytes<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (&'txn [u8], &'txn [u8]),
+                Error = error::IterItem,
+            > + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.iter_remapped::<_, Bytes, Bytes>(txn)
+    }
+
+    /// Iterate over each distinct key, with the number of duplicate values
+    /// for that key.
+    /// Values are not decoded.
+    ///
+    /// # Cost
+    /// Distinct keys are visited without visiting their duplicates, but
+    /// heed does not expose `mdb_cursor_count`, so the duplicates of each
+    /// key are counted by iterating over them, without decoding.
+    /// Iterating to the end is O(total entries).
+    #[inline(always)]
+    pub fn key_counts<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<Item = (KC::DItem, u64), Error = error::IterItem>
+            + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+    {
+        self.inner.key_counts(txn)
+    }
+
     #[inline(always)]
     pub fn lazy_decode(&self) -> RoDatabaseDup<'env_id, KC, LazyDecode<DC>, C> {
         RoDatabaseDup {
@@ -702,11 +4190,63 @@ impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
         self.inner.len(txn)
     }
 
+    /// Describe the db, as `<name> (<len> entries)`, eg. for logging
+    #[inline(always)]
+    pub fn describe<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<String, error::Len>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.describe(txn)
+    }
+
+    /// Number of items in the db, as a `usize`.
+    /// Returns an [`error::LenOverflow`] error if the number of items
+    /// exceeds [`usize::MAX`], which is only possible on targets with
+    /// pointers narrower than 64 bits.
+    #[inline(always)]
+    pub fn len_usize<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<usize, error::LenUsize>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.len_usize(txn)
+    }
+
     #[inline(always)]
     pub fn name(&self) -> &str {
         &self.inner.name
     }
 
+    /// Read counts for the db, and the time of the last read.
+    /// See [`RoDatabaseUnique::read_stats`].
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    #[inline(always)]
+    pub fn read_stats(&self) -> ReadStats {
+        self.inner.read_counters.stats()
+    }
+
+    /// Identifier for the db, in the form `{env_path}::{db_name}`.
+    /// See [`RoDatabaseUnique::qualified_name`].
+    #[inline(always)]
+    pub fn qualified_name(&self) -> String {
+        self.inner.qualified_name()
+    }
+
+    /// The underlying heed db.
+    /// See [raw handles](crate#raw-handles).
+    #[cfg(feature = "raw")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+    #[inline(always)]
+    pub fn as_heed(&self) -> &heed::Database<KC, DC, C> {
+        &self.inner.heed_db
+    }
+
     #[inline(always)]
     pub fn get<'a, 'env, 'txn, Tx>(
         &'a self,
@@ -726,6 +4266,126 @@ impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
         self.inner.get_duplicates(txn, key)
     }
 
+    /// Collect the values for a key, in value order.
+    /// Unlike [`Self::get`], the key is not decoded for each value.
+    ///
+    /// This does **not** use LMDB's `MDB_GET_MULTIPLE` or
+    /// `MDB_NEXT_MULTIPLE`, which heed does not expose.
+    /// Values are read from the cursor one at a time, even in dbs created
+    /// with [`DatabaseDup::create_fixed`], so the only saving over
+    /// [`Self::get_duplicates`] is not decoding the key.
+    #[inline(always)]
+    pub fn get_duplicates_bulk<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Vec<DC::DItem>, error::IterDuplicates>
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        self.inner.get_duplicates_bulk(txn, key)
+    }
+
+    /// Iterate over the items with keys in the specified range.
+    /// Each duplicate value is yielded with its key,
+    /// in key-then-value order.
+    #[inline(always)]
+    pub fn range<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.range(txn, range)
+    }
+
+    /// Iterate over the values with keys in the specified range,
+    /// in key-then-value order.
+    /// Keys are not decoded.
+    #[inline(always)]
+    pub fn range_values<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = DC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+        C: Comparator,
+    {
+        self.inner.range_values(txn, range)
+    }
+
+    /// Iterate over each distinct key in the specified range.
+    /// Values are not decoded.
+    #[inline(always)]
+    pub fn range_keys_unique<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        self.inner.range_keys_unique(txn, range)
+    }
+
+    /// Iterate over the keys in the specified range, with each key repeated
+    /// once for each of its duplicate values.
+    /// Values are not decoded.
+    #[inline(always)]
+    pub fn range_keys_duplicate<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        C: Comparator,
+    {
+        self.inner.range_keys(txn, range)
+    }
+
     #[cfg(feature = "observe")]
     #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
     /// Receive notifications when the DB is updated
@@ -733,6 +4393,19 @@ impl<'env_id, KC, DC, C> RoDatabaseDup<'env_id, KC, DC, C> {
     pub fn watch(&self) -> &watch::Receiver<()> {
         self.inner.watch()
     }
+
+    #[cfg(feature = "observe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+    /// Wait for the next notification that the DB is updated.
+    /// Returns `false` if the timeout elapses first.
+    /// The timeout requires a tokio runtime with the time driver enabled.
+    #[inline(always)]
+    pub async fn changed(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<bool, error::WatchClosed> {
+        self.inner.changed(timeout).await
+    }
 }
 
 impl<KC, DC, C> Database for RoDatabaseDup<'_, KC, DC, C> {
@@ -743,14 +4416,68 @@ impl<KC, DC, C> Database for RoDatabaseDup<'_, KC, DC, C> {
     }
 }
 
+impl<'env_id, KC, DC, C> DatabaseRead<'env_id>
+    for RoDatabaseDup<'env_id, KC, DC, C>
+{
+    fn len(&self, txn: &dyn Txn<'_, 'env_id>) -> Result<u64, error::Len> {
+        self.inner.len(txn)
+    }
+
+    fn iter_raw<'a, 'env, 'txn>(
+        &'a self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<RawIter<'txn>, error::IterInit>
+    where
+        'a: 'txn,
+        'env: 'txn,
+    {
+        let iter = self.inner.iter_remapped::<_, Raw, Raw>(txn)?;
+        Ok(Box::new(iter))
+    }
+
+    fn contains_key_raw(
+        &self,
+        txn: &dyn Txn<'_, 'env_id>,
+        key: &[u8],
+    ) -> Result<bool, error::TryGet> {
+        self.inner.contains_key_raw(txn, key)
+    }
+
+    fn first_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::First>
+    where
+        'env: 'txn,
+    {
+        self.inner.first_raw(txn)
+    }
+
+    fn last_raw<'env, 'txn>(
+        &self,
+        txn: &'txn dyn Txn<'env, 'env_id>,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, error::Last>
+    where
+        'env: 'txn,
+    {
+        self.inner.last_raw(txn)
+    }
+}
+
 /// Wrapper for [`heed::Database`] with duplicate keys
 #[derive(Educe)]
-#[educe(Clone, Debug)]
+#[educe(Clone)]
 #[repr(transparent)]
 pub struct DatabaseDup<'env_id, KC, DC, C = DefaultComparator> {
     inner: RoDatabaseDup<'env_id, KC, DC, C>,
 }
 
+impl<KC, DC, C> std::fmt::Debug for DatabaseDup<'_, KC, DC, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.inner.fmt_debug("DatabaseDup", f)
+    }
+}
+
 impl<'env_id, KC, DC, C> DatabaseDup<'env_id, KC, DC, C> {
     pub fn create(
         env: &Env<'env_id>,
@@ -769,6 +4496,69 @@ impl<'env_id, KC, DC, C> DatabaseDup<'env_id, KC, DC, C> {
         })
     }
 
+    /// Create a db with fixed-size values, if it does not already exist, and
+    /// open it if it does.
+    ///
+    /// Every value in the db must encode to the same number of bytes.
+    /// LMDB stores the values for each key densely, which makes them cheaper
+    /// to store.
+    /// Writing a value of a different size to the first value in the db
+    /// fails with an [`error::Put`] caused by
+    /// [`heed::MdbError::BadValSize`].
+    /// An existing db keeps the flags it was created with, so opening a
+    /// fixed-size db with [`Self::create`] still checks value sizes, and
+    /// opening a db that was not created with this constructor does not
+    /// make it fixed-size.
+    pub fn create_fixed(
+        env: &Env<'env_id>,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        name: &str,
+    ) -> Result<Self, env::error::CreateDb>
+    where
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        let flags = DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED;
+        let db_wrapper = DbWrapper::create(env, rwtxn, name, Some(flags))?;
+        Ok(Self {
+            inner: RoDatabaseDup { inner: db_wrapper },
+        })
+    }
+
+    /// Re-tag the db for use with a re-tagged env.
+    ///
+    /// # Safety
+    /// `env` must be a clone of the env that this db was created in.
+    /// See [`Env::clone_with_tag`].
+    #[inline(always)]
+    pub unsafe fn retag<'new>(
+        self,
+        env: &Env<'new>,
+    ) -> DatabaseDup<'new, KC, DC, C> {
+        DatabaseDup {
+            inner: self.inner.retag(env),
+        }
+    }
+
+    /// Check each value against the free space in the map before writing
+    /// it.
+    /// See [`DatabaseUnique::check_value_size`].
+    #[inline(always)]
+    pub fn check_value_size(mut self, check: bool) -> Self {
+        self.inner.inner.check_value_size = check;
+        self
+    }
+
+    /// Delete all entries in the db
+    #[inline(always)]
+    pub fn clear(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<(), error::Clear> {
+        self.inner.inner.clear(rwtxn)
+    }
+
     /// Delete each item with the specified key
     #[inline(always)]
     pub fn delete_each<'a, 'env, 'txn>(
@@ -782,6 +4572,22 @@ impl<'env_id, KC, DC, C> DatabaseDup<'env_id, KC, DC, C> {
         self.inner.inner.delete(rwtxn, key)
     }
 
+    /// Delete a single item with the specified key and value.
+    /// Returns `false` if the item did not exist.
+    #[inline(always)]
+    pub fn delete_one<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<bool, error::Delete>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        self.inner.inner.delete_one_duplicate(rwtxn, key, data)
+    }
+
     #[inline(always)]
     pub fn lazy_decode(&self) -> DatabaseDup<'env_id, KC, LazyDecode<DC>, C> {
         DatabaseDup {
@@ -804,6 +4610,40 @@ impl<'env_id, KC, DC, C> DatabaseDup<'env_id, KC, DC, C> {
             .inner
             .put_with_flags(rwtxn, PutFlags::empty(), key, data)
     }
+
+    /// Write with the specified flags.
+    ///
+    /// Valid flags for dbs with duplicate keys are:
+    /// * [`PutFlags::NO_DUP_DATA`]: fail with [`error::KeyExists`] if the
+    ///   key-value pair already exists
+    /// * [`PutFlags::NO_OVERWRITE`]: fail with [`error::KeyExists`] if the
+    ///   key already exists, with any value
+    /// * [`PutFlags::APPEND`]: append the item to the end of the db, for
+    ///   fast bulk loading of sorted keys. Fails with
+    ///   [`error::KeyExists`] if the key is less than the last key in the
+    ///   db.
+    /// * [`PutFlags::APPEND_DUP`]: as with [`PutFlags::APPEND`], but for
+    ///   sorted values of the same key
+    #[inline(always)]
+    pub fn put_with_flags<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        flags: PutFlags,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), error::PutWithFlags>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        self.inner.inner.put_with_valid_flags(
+            rwtxn,
+            PutFlags::all(),
+            flags,
+            key,
+            data,
+        )
+    }
 }
 
 impl<'env_id, KC, DC, C> std::ops::Deref for DatabaseDup<'env_id, KC, DC, C> {
@@ -813,3 +4653,9 @@ impl<'env_id, KC, DC, C> std::ops::Deref for DatabaseDup<'env_id, KC, DC, C> {
         &self.inner
     }
 }
+
+/// Db with keys and values as raw bytes
+pub type RawDatabaseUnique<'env_id> = RoDatabaseUnique<'env_id, Raw, Raw>;
+
+/// Db with duplicate keys, with keys and values as raw bytes
+pub type RawDatabaseDup<'env_id> = RoDatabaseDup<'env_id, Raw, Raw>;
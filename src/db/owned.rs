@@ -0,0 +1,120 @@
+//! Read methods that decode owned values.
+//!
+//! These methods require codecs that decode values that do not borrow from
+//! the bytes in the db, such as serde-based codecs, so that the results can
+//! outlive the txn that they were read with.
+
+use std::ops::RangeBounds;
+
+use fallible_iterator::FallibleIterator;
+use heed::{BytesDecode, BytesEncode, Comparator};
+
+use crate::{
+    db::{error, RoDatabaseUnique},
+    Txn,
+};
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Like [`Self::try_get`], but the value does not borrow from the txn
+    #[inline(always)]
+    pub fn try_get_owned<'a, 'env, Tx, V>(
+        &self,
+        txn: &Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Option<V>, error::TryGet>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.try_get(txn, key)
+    }
+
+    /// Like [`Self::get`], but the value does not borrow from the txn
+    #[inline(always)]
+    pub fn get_owned<'a, 'env, Tx, V>(
+        &self,
+        txn: &Tx,
+        key: &'a KC::EItem,
+    ) -> Result<V, error::Get>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.get(txn, key)
+    }
+
+    /// Like [`Self::first`], but the item does not borrow from the txn
+    #[inline(always)]
+    pub fn first_owned<'env, Tx, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<Option<(K, V)>, error::First>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'k> BytesDecode<'k, DItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.first(txn)
+    }
+
+    /// Like [`Self::last`], but the item does not borrow from the txn
+    #[inline(always)]
+    pub fn last_owned<'env, Tx, K, V>(
+        &self,
+        txn: &Tx,
+    ) -> Result<Option<(K, V)>, error::Last>
+    where
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'k> BytesDecode<'k, DItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.last(txn)
+    }
+
+    /// Like [`Self::iter`], but items do not borrow from the txn.
+    /// The iterator borrows the txn, but collected items can be used after
+    /// the txn is dropped.
+    #[inline(always)]
+    pub fn iter_owned<'a, 'env, 'txn, Tx, K, V>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<Item = (K, V), Error = error::IterItem> + 'txn,
+        error::IterInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: for<'k> BytesDecode<'k, DItem = K>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+    {
+        self.inner.iter(txn)
+    }
+
+    /// Like [`Self::range`], but items do not borrow from the txn.
+    /// The iterator borrows the txn, but collected items can be used after
+    /// the txn is dropped.
+    #[inline(always)]
+    pub fn range_owned<'a, 'env, 'txn, Tx, R, K, V>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<Item = (K, V), Error = error::IterItem> + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + for<'k> BytesDecode<'k, DItem = K>,
+        R: RangeBounds<KC::EItem>,
+        DC: for<'v> BytesDecode<'v, DItem = V>,
+        C: Comparator,
+    {
+        self.inner.range(txn, range)
+    }
+}
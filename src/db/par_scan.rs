@@ -0,0 +1,134 @@
+//! Parallel scans, using a read txn on each worker thread
+
+use std::{cmp::Ordering, num::NonZeroUsize, ops::Bound};
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{
+    types::{Bytes, DecodeIgnore},
+    BytesDecode, Comparator,
+};
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+
+use crate::{
+    db::{error, RoDatabaseUnique},
+    Env,
+};
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Keys that split the db into up to `partitions` ranges of
+    /// approximately equal numbers of items.
+    /// The first range starts from the least key, and each split key is the
+    /// least key in the following range.
+    ///
+    /// LMDB does not provide access to the number of items in each page,
+    /// so this walks the keys in the db without decoding values.
+    fn par_scan_split_keys(
+        &self,
+        env: &Env<'env_id>,
+        partitions: NonZeroUsize,
+    ) -> Result<Vec<Vec<u8>>, error::ParScan> {
+        let rotxn = env.read_txn()?;
+        let len = self.len(&rotxn)?;
+        let stride = len.div_ceil(partitions.get() as u64).max(1) as usize;
+        let split_keys = self
+            .inner
+            .iter_remapped::<_, Bytes, DecodeIgnore>(&rotxn)?
+            .skip(stride)
+            .step_by(stride)
+            .take(partitions.get() - 1)
+            .map(|(key_bytes, ())| Ok(key_bytes.to_vec()))
+            .collect()?;
+        Ok(split_keys)
+    }
+
+    /// Apply `f` to every item, in parallel, and collect the results in key
+    /// order.
+    ///
+    /// The db is split into up to `partitions` key ranges with approximately
+    /// equal numbers of items, and each range is scanned on a thread in the
+    /// rayon thread pool, using a read txn for that range.
+    /// Ranges are read in different txns, so the scan is not a consistent
+    /// snapshot of the db if there are concurrent writes.
+    ///
+    /// Without [`heed::EnvFlags::NO_TLS`], LMDB allows only one read txn on
+    /// each thread, and a rayon thread may start a range while it already
+    /// has a read txn, eg. if `f` uses rayon, or if the caller is on a rayon
+    /// thread.
+    /// The env must therefore be opened with `NO_TLS`, or this fails with
+    /// [`error::ParScanRequiresNoTls`].
+    /// Envs are always opened with `NO_TLS` if the `stream` feature is
+    /// enabled.
+    pub fn par_scan<K, V, R, F>(
+        &self,
+        env: &Env<'env_id>,
+        partitions: NonZeroUsize,
+        f: F,
+    ) -> Result<Vec<R>, error::ParScan>
+    where
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+        C: Comparator + Sync,
+        R: Send,
+        F: Fn(K, V) -> R + Sync,
+    {
+        if !env.no_tls() {
+            return Err(error::ParScanRequiresNoTls {
+                db_name: (*self.inner.name).to_owned(),
+                db_path: (*self.inner.path).to_owned(),
+            }
+            .into());
+        }
+        let split_keys = self.par_scan_split_keys(env, partitions)?;
+        let () = self.inner.record_iter_init();
+        let heed_db = self.inner.heed_db.remap_types::<Bytes, Bytes>();
+        let db_name = &*self.inner.name;
+        let db_path = &*self.inner.path;
+        let scan_partition = |index: usize| -> Result<Vec<R>, error::ParScan> {
+            let start = match index.checked_sub(1) {
+                Some(prev_index) => Bound::Included(&*split_keys[prev_index]),
+                None => Bound::Unbounded,
+            };
+            let end = split_keys.get(index);
+            let rotxn = env.read_txn()?;
+            let items = heed_db
+                .range(&rotxn.inner, &(start, Bound::Unbounded))
+                .map_err(|err| error::RangeInit {
+                    db_name: db_name.to_owned(),
+                    db_path: db_path.to_owned(),
+                    source: err,
+                })?;
+            let mut res = Vec::new();
+            for item in items {
+                let item = item.and_then(|(key_bytes, value_bytes)| {
+                    // The end bound is checked with the db's comparator,
+                    // since it may not be lexicographic
+                    if let Some(end) = end {
+                        if C::compare(key_bytes, end) != Ordering::Less {
+                            return Ok(None);
+                        }
+                    }
+                    let key = KC::bytes_decode(key_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    let value = DC::bytes_decode(value_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    Ok(Some((key, value)))
+                });
+                let item = item.map_err(|err| error::IterItem {
+                    db_name: db_name.to_owned(),
+                    db_path: db_path.to_owned(),
+                    source: err,
+                })?;
+                let Some((key, value)) = item else {
+                    break;
+                };
+                res.push(f(key, value));
+            }
+            Ok(res)
+        };
+        let partition_results: Vec<Vec<R>> = (0..=split_keys.len())
+            .into_par_iter()
+            .map(scan_partition)
+            .collect::<Result<_, _>>()?;
+        Ok(partition_results.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,121 @@
+//! Long scans, using a short-lived read txn for each chunk of items
+
+use std::{
+    cmp::Ordering,
+    num::NonZeroUsize,
+    ops::{Bound, ControlFlow},
+};
+
+use heed::{types::Bytes, BytesDecode, Comparator};
+
+use crate::{
+    db::{error, RoDatabaseUnique},
+    Env,
+};
+
+/// Statistics for a scan.
+/// See [`RoDatabaseUnique::scan_chunked`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ScanStats {
+    /// Number of chunks passed to the callback
+    pub chunks: u64,
+    /// Number of items passed to the callback
+    pub rows: u64,
+    /// Number of keys that were not strictly greater than the preceding
+    /// key, according to the db's comparator.
+    /// Items with these keys may have been scanned more than once.
+    pub non_increasing_keys: u64,
+    /// `true` if the env was written to between chunks.
+    /// Items inserted or deleted during the scan may have been skipped.
+    pub concurrent_writes: bool,
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Scan all items in key order, in chunks of up to `chunk_size` items.
+    /// Each chunk is read with a fresh read txn, which is released before
+    /// `f` is called, so that long scans do not prevent the reuse of pages
+    /// freed by concurrent writes.
+    /// Each chunk resumes from the item after the last key in the previous
+    /// chunk.
+    ///
+    /// The scan is not a consistent snapshot of the db. Items that are
+    /// written during the scan may or may not be scanned, and the values of
+    /// items in different chunks may reflect different txns.
+    ///
+    /// The scan stops early if `f` returns [`ControlFlow::Break`].
+    pub fn scan_chunked<K, V, F>(
+        &self,
+        env: &Env<'env_id>,
+        chunk_size: NonZeroUsize,
+        mut f: F,
+    ) -> Result<ScanStats, error::ScanChunked>
+    where
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+        C: Comparator,
+        F: FnMut(&[(K, V)]) -> ControlFlow<()>,
+    {
+        let () = self.inner.record_iter_init();
+        let heed_db = self.inner.heed_db.remap_types::<Bytes, Bytes>();
+        let mut stats = ScanStats::default();
+        let mut chunk = Vec::with_capacity(chunk_size.get());
+        let mut last_key_bytes: Option<Vec<u8>> = None;
+        let mut first_txn_id = None;
+        loop {
+            let txn_id = env.last_txn_id();
+            if *first_txn_id.get_or_insert(txn_id) != txn_id {
+                stats.concurrent_writes = true;
+            }
+            let rotxn = env.read_txn()?;
+            let start = match &last_key_bytes {
+                Some(last_key_bytes) => {
+                    Bound::Excluded(last_key_bytes.as_slice())
+                }
+                None => Bound::Unbounded,
+            };
+            let items = heed_db
+                .range(&rotxn.inner, &(start, Bound::Unbounded))
+                .map_err(|err| error::RangeInit {
+                    db_name: self.name().to_owned(),
+                    db_path: self.path().to_owned(),
+                    source: err,
+                })?;
+            for item in items.take(chunk_size.get()) {
+                let item = item.and_then(|(key_bytes, value_bytes)| {
+                    let key = KC::bytes_decode(key_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    let value = DC::bytes_decode(value_bytes)
+                        .map_err(heed::Error::Decoding)?;
+                    Ok((key_bytes, (key, value)))
+                });
+                let (key_bytes, item) =
+                    item.map_err(|err| error::IterItem {
+                        db_name: self.name().to_owned(),
+                        db_path: self.path().to_owned(),
+                        source: err,
+                    })?;
+                if let Some(last_key_bytes) = &last_key_bytes {
+                    if C::compare(key_bytes, last_key_bytes)
+                        != Ordering::Greater
+                    {
+                        stats.non_increasing_keys += 1;
+                    }
+                }
+                last_key_bytes = Some(key_bytes.to_vec());
+                chunk.push(item);
+            }
+            drop(rotxn);
+            if chunk.is_empty() {
+                break;
+            }
+            stats.chunks += 1;
+            stats.rows += chunk.len() as u64;
+            let is_last_chunk = chunk.len() < chunk_size.get();
+            if f(&chunk).is_break() || is_last_chunk {
+                break;
+            }
+            chunk.clear();
+        }
+        Ok(stats)
+    }
+}
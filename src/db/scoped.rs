@@ -0,0 +1,438 @@
+//! Views of a db, restricted to keys with a common prefix
+
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+use educe::Educe;
+use fallible_iterator::{FallibleIterator, IteratorExt as _};
+use heed::{
+    types::{Bytes, DecodeIgnore},
+    BytesDecode, BytesEncode, PutFlags,
+};
+#[cfg(feature = "observe")]
+use tokio::sync::watch;
+
+use crate::{
+    db::{error, DatabaseUnique, DbWrapper},
+    RwTxn, Txn,
+};
+
+/// The least byte string that is greater than every byte string with the
+/// specified prefix, if any
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut res = prefix.to_vec();
+    while let Some(last) = res.pop() {
+        if last < u8::MAX {
+            res.push(last + 1);
+            return Some(res);
+        }
+    }
+    None
+}
+
+fn as_slice_bound(bound: &Bound<Vec<u8>>) -> Bound<&[u8]> {
+    match bound {
+        Bound::Included(bytes) => Bound::Included(bytes),
+        Bound::Excluded(bytes) => Bound::Excluded(bytes),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Strip the prefix from a key, and decode the key and value
+fn decode_item<'txn, KC, DC>(
+    prefix_len: usize,
+    key_bytes: &'txn [u8],
+    value_bytes: &'txn [u8],
+) -> Result<(KC::DItem, DC::DItem), heed::Error>
+where
+    KC: BytesDecode<'txn>,
+    DC: BytesDecode<'txn>,
+{
+    let key = KC::bytes_decode(&key_bytes[prefix_len..])
+        .map_err(heed::Error::Decoding)?;
+    let value = DC::bytes_decode(value_bytes).map_err(heed::Error::Decoding)?;
+    Ok((key, value))
+}
+
+type Bounds = (Bound<Vec<u8>>, Bound<Vec<u8>>);
+
+/// View of a db, restricted to keys with a common prefix.
+/// The prefix is prepended to encoded keys when writing, and stripped from
+/// encoded keys when reading, so keys in the view do not include the prefix.
+/// Views share the underlying db, including its watch channel.
+#[derive(Educe)]
+#[educe(Clone, Debug)]
+pub struct ScopedDatabase<'env_id, KC, DC> {
+    db: DbWrapper<'env_id, Bytes, Bytes>,
+    prefix: Arc<[u8]>,
+    #[educe(Debug(ignore))]
+    _types: PhantomData<fn() -> (KC, DC)>,
+}
+
+impl<'env_id, KC, DC> ScopedDatabase<'env_id, KC, DC> {
+    /// Restrict the view to keys with an additional prefix.
+    /// The new prefix is appended to the existing prefix.
+    pub fn scoped(&self, prefix: &[u8]) -> Self {
+        Self {
+            db: self.db.clone(),
+            prefix: [&*self.prefix, prefix].concat().into(),
+            _types: PhantomData,
+        }
+    }
+
+    /// The full prefix for keys in the view
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.db.name
+    }
+
+    /// Encode a key, and prepend the prefix
+    fn encode_key<'a>(
+        &self,
+        key: &'a KC::EItem,
+    ) -> Result<Vec<u8>, heed::BoxedError>
+    where
+        KC: BytesEncode<'a>,
+    {
+        let key_bytes = KC::bytes_encode(key)?;
+        Ok([&*self.prefix, &key_bytes].concat())
+    }
+
+    /// Bounds for every key in the view
+    fn full_bounds(&self) -> Bounds {
+        let end = match prefix_successor(&self.prefix) {
+            Some(successor) => Bound::Excluded(successor),
+            None => Bound::Unbounded,
+        };
+        (Bound::Included(self.prefix.to_vec()), end)
+    }
+
+    /// Translate a range of keys to a range of prefixed encoded keys
+    fn encode_bounds<'a, R>(
+        &self,
+        range: &'a R,
+    ) -> Result<Bounds, heed::BoxedError>
+    where
+        KC: BytesEncode<'a>,
+        R: RangeBounds<KC::EItem>,
+    {
+        let (full_start, full_end) = self.full_bounds();
+        let start = match range.start_bound() {
+            Bound::Included(key) => Bound::Included(self.encode_key(key)?),
+            Bound::Excluded(key) => Bound::Excluded(self.encode_key(key)?),
+            Bound::Unbounded => full_start,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => Bound::Included(self.encode_key(key)?),
+            Bound::Excluded(key) => Bound::Excluded(self.encode_key(key)?),
+            Bound::Unbounded => full_end,
+        };
+        Ok((start, end))
+    }
+
+    /// Iterate over items with prefixed encoded keys within the bounds
+    fn items<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+        (start, end): Bounds,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.db.record_range_init();
+        let db_name = &*self.db.name;
+        let db_path = &*self.db.path;
+        let prefix_len = self.prefix.len();
+        let bounds = (as_slice_bound(&start), as_slice_bound(&end));
+        let it =
+            self.db
+                .heed_db
+                .range(txn.read_txn(), &bounds)
+                .map_err(|err| error::RangeInit {
+                    db_name: db_name.to_owned(),
+                    db_path: db_path.to_owned(),
+                    source: err,
+                })?;
+        Ok(it
+            .into_iter()
+            .transpose_into_fallible()
+            .map(move |(key_bytes, value_bytes)| {
+                decode_item::<KC, DC>(prefix_len, key_bytes, value_bytes)
+            })
+            .map_err(|err| error::IterItem {
+                db_name: db_name.to_owned(),
+                db_path: db_path.to_owned(),
+                source: err,
+            }))
+    }
+
+    /// Iterate over all items in the view
+    pub fn iter<'a, 'env, 'txn, Tx>(
+        &'a self,
+        txn: &'txn Tx,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        self.items(txn, self.full_bounds())
+    }
+
+    /// Iterate over the items in the view with keys in the specified range
+    pub fn range<'a, 'env, 'txn, Tx, R>(
+        &'a self,
+        txn: &'txn Tx,
+        range: &'a R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = error::IterItem,
+            > + 'txn,
+        error::RangeInit,
+    >
+    where
+        'a: 'txn,
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a> + BytesDecode<'txn>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'txn>,
+    {
+        let bounds =
+            self.encode_bounds(range).map_err(|err| error::RangeInit {
+                db_name: (*self.db.name).to_owned(),
+                db_path: (*self.db.path).to_owned(),
+                source: heed::Error::Encoding(err),
+            })?;
+        self.items(txn, bounds)
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn first<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::First>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.db.record_range_init();
+        let (start, end) = self.full_bounds();
+        let bounds = (as_slice_bound(&start), as_slice_bound(&end));
+        let first = || {
+            let Some((key_bytes, value_bytes)) = self
+                .db
+                .heed_db
+                .range(txn.read_txn(), &bounds)?
+                .next()
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            decode_item::<KC, DC>(self.prefix.len(), key_bytes, value_bytes)
+                .map(Some)
+        };
+        first().map_err(|err| error::First {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            source: err,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn last<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, error::Last>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        let () = self.db.record_range_init();
+        let (start, end) = self.full_bounds();
+        let bounds = (as_slice_bound(&start), as_slice_bound(&end));
+        let last = || {
+            let Some((key_bytes, value_bytes)) = self
+                .db
+                .heed_db
+                .rev_range(txn.read_txn(), &bounds)?
+                .next()
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+            decode_item::<KC, DC>(self.prefix.len(), key_bytes, value_bytes)
+                .map(Some)
+        };
+        last().map_err(|err| error::Last {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            source: err,
+        })
+    }
+
+    /// Number of items in the view.
+    /// Unlike [`crate::RoDatabaseUnique::len`], this requires iterating over
+    /// each item in the view.
+    pub fn len<'env, Tx>(&self, txn: &Tx) -> Result<u64, error::Len>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        let () = self.db.record_range_init();
+        let (start, end) = self.full_bounds();
+        let bounds = (as_slice_bound(&start), as_slice_bound(&end));
+        let len = || {
+            let mut len = 0;
+            for item in self
+                .db
+                .heed_db
+                .remap_data_type::<DecodeIgnore>()
+                .range(txn.read_txn(), &bounds)?
+            {
+                let (_key_bytes, ()) = item?;
+                len += 1;
+            }
+            Ok(len)
+        };
+        len().map_err(|err| error::Len {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            source: err,
+        })
+    }
+
+    pub fn try_get<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<Option<DC::DItem>, error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'txn>,
+    {
+        let key_bytes = self.encode_key(key).map_err(|err| error::TryGet {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            key_bytes: self.encode_key(key),
+            source: heed::Error::Encoding(err),
+        })?;
+        let () = self.db.record_get();
+        let try_get = || {
+            let Some(value_bytes) =
+                self.db.heed_db.get(txn.read_txn(), &key_bytes)?
+            else {
+                return Ok(None);
+            };
+            DC::bytes_decode(value_bytes)
+                .map(Some)
+                .map_err(heed::Error::Decoding)
+        };
+        try_get().map_err(|err| error::TryGet {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            key_bytes: Ok(key_bytes.clone()),
+            source: err,
+        })
+    }
+
+    pub fn put<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+        value: &'a DC::EItem,
+    ) -> Result<(), error::Put>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesEncode<'a>,
+    {
+        let encode_err = |err| error::Put {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            key_bytes: Box::new(self.encode_key(key)),
+            value_bytes: Box::new(
+                DC::bytes_encode(value)
+                    .map(|value_bytes| value_bytes.into_owned()),
+            ),
+            source: heed::Error::Encoding(err).into(),
+        };
+        let key_bytes = self.encode_key(key).map_err(encode_err)?;
+        let value_bytes = DC::bytes_encode(value).map_err(encode_err)?;
+        self.db.put_with_flags(
+            rwtxn,
+            PutFlags::empty(),
+            &key_bytes,
+            &value_bytes,
+        )
+    }
+
+    /// Returns `false` if the key did not exist
+    pub fn delete<'a>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &'a KC::EItem,
+    ) -> Result<bool, error::Delete>
+    where
+        KC: BytesEncode<'a>,
+    {
+        let key_bytes = self.encode_key(key).map_err(|err| error::Delete {
+            db_name: (*self.db.name).to_owned(),
+            db_path: (*self.db.path).to_owned(),
+            key_bytes: self.encode_key(key),
+            source: heed::Error::Encoding(err),
+        })?;
+        self.db.delete(rwtxn, &key_bytes)
+    }
+
+    #[cfg(feature = "observe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+    /// Receive notifications when the underlying DB is updated
+    #[inline(always)]
+    pub fn watch(&self) -> &watch::Receiver<()> {
+        self.db.watch()
+    }
+}
+
+impl<'env_id, KC, DC> DatabaseUnique<'env_id, KC, DC> {
+    /// View of the db, restricted to keys with the specified prefix.
+    /// Keys are ordered by their encoded bytes, so that keys with a common
+    /// prefix are contiguous.
+    pub fn scoped(&self, prefix: &[u8]) -> ScopedDatabase<'env_id, KC, DC> {
+        ScopedDatabase {
+            db: self.inner.inner.remap_types(),
+            prefix: prefix.into(),
+            _types: PhantomData,
+        }
+    }
+}
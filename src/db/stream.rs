@@ -0,0 +1,170 @@
+//! Streams of db items, read in batches with an owned read txn
+
+use std::{
+    collections::VecDeque,
+    num::NonZeroUsize,
+    ops::Bound,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use heed::{types::Bytes, BytesDecode};
+
+use crate::{
+    db::{error, RoDatabaseUnique},
+    StaticRoTxn,
+};
+
+/// Stream of all items in a db, in key order.
+/// See [`RoDatabaseUnique::iter_stream`].
+///
+/// Items are read in batches, and the stream yields to the executor
+/// between batches.
+/// The read txn is released when the stream ends or is dropped.
+pub struct IterStream<'env_id, KC, DC, C, K, V> {
+    heed_db: heed::Database<KC, DC, C>,
+    name: Arc<str>,
+    path: Arc<Path>,
+    /// `None` once the stream has ended
+    txn: Option<StaticRoTxn<'env_id>>,
+    batch_size: NonZeroUsize,
+    batch: VecDeque<Result<(K, V), error::Iter>>,
+    /// Key of the last item read, if any
+    last_key_bytes: Option<Vec<u8>>,
+    /// Set after yielding to the executor, and cleared after reading a batch
+    yielded: bool,
+}
+
+// No fields are structurally pinned
+impl<KC, DC, C, K, V> Unpin for IterStream<'_, KC, DC, C, K, V> {}
+
+impl<'env_id, KC, DC, C, K, V> IterStream<'env_id, KC, DC, C, K, V>
+where
+    KC: for<'a> BytesDecode<'a, DItem = K>,
+    DC: for<'a> BytesDecode<'a, DItem = V>,
+{
+    /// Read the next batch of items.
+    /// Returns `true` if there are no more items to read, or if an error
+    /// occurred.
+    fn read_batch(&mut self) -> bool {
+        let Some(txn) = &self.txn else {
+            return true;
+        };
+        let start = match &self.last_key_bytes {
+            Some(last_key_bytes) => Bound::Excluded(last_key_bytes.as_slice()),
+            None => Bound::Unbounded,
+        };
+        let items = self
+            .heed_db
+            .remap_types::<Bytes, Bytes>()
+            .range(&txn.inner, &(start, Bound::Unbounded));
+        let mut items = match items {
+            Ok(items) => items,
+            Err(err) => {
+                let err = error::IterInit {
+                    db_name: (*self.name).to_owned(),
+                    db_path: (*self.path).to_owned(),
+                    source: err,
+                };
+                self.batch.push_back(Err(err.into()));
+                return true;
+            }
+        };
+        let mut last_key_bytes = None;
+        for _ in 0..self.batch_size.get() {
+            let Some(item) = items.next() else {
+                break;
+            };
+            let item = item.and_then(|(key_bytes, value_bytes)| {
+                let key = KC::bytes_decode(key_bytes)
+                    .map_err(heed::Error::Decoding)?;
+                let value = DC::bytes_decode(value_bytes)
+                    .map_err(heed::Error::Decoding)?;
+                last_key_bytes = Some(key_bytes);
+                Ok((key, value))
+            });
+            match item {
+                Ok(item) => self.batch.push_back(Ok(item)),
+                Err(err) => {
+                    let err = error::IterItem {
+                        db_name: (*self.name).to_owned(),
+                        db_path: (*self.path).to_owned(),
+                        source: err,
+                    };
+                    self.batch.push_back(Err(err.into()));
+                    return true;
+                }
+            }
+        }
+        if self.batch.len() < self.batch_size.get() {
+            return true;
+        }
+        self.last_key_bytes = last_key_bytes.map(<[u8]>::to_vec);
+        false
+    }
+}
+
+impl<'env_id, KC, DC, C, K, V> Stream for IterStream<'env_id, KC, DC, C, K, V>
+where
+    KC: for<'a> BytesDecode<'a, DItem = K>,
+    DC: for<'a> BytesDecode<'a, DItem = V>,
+{
+    type Item = Result<(K, V), error::Iter>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = Pin::into_inner(self);
+        if let Some(item) = this.batch.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+        if this.txn.is_none() {
+            return Poll::Ready(None);
+        }
+        // Yield to the executor before reading each batch, other than
+        // the first
+        if this.last_key_bytes.is_some() && !this.yielded {
+            this.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.yielded = false;
+        if this.read_batch() {
+            // Release the read txn promptly
+            this.txn = None;
+        }
+        Poll::Ready(this.batch.pop_front())
+    }
+}
+
+impl<'env_id, KC, DC, C> RoDatabaseUnique<'env_id, KC, DC, C> {
+    /// Stream all items, in key order, reading up to `batch_size` items at
+    /// a time.
+    /// The stream owns the read txn, so it is `'static` if the env tag is
+    /// `'static`, and can be sent between threads if the items can be.
+    pub fn iter_stream<K, V>(
+        &self,
+        txn: StaticRoTxn<'env_id>,
+        batch_size: NonZeroUsize,
+    ) -> IterStream<'env_id, KC, DC, C, K, V>
+    where
+        KC: for<'a> BytesDecode<'a, DItem = K>,
+        DC: for<'a> BytesDecode<'a, DItem = V>,
+    {
+        let () = self.inner.record_iter_init();
+        IterStream {
+            heed_db: self.inner.heed_db,
+            name: self.inner.name.clone(),
+            path: self.inner.path.clone(),
+            txn: Some(txn),
+            batch_size,
+            batch: VecDeque::with_capacity(batch_size.get()),
+            last_key_bytes: None,
+            yielded: false,
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Compare databases, which may be in different envs
+
+use std::{cmp::Ordering, marker::PhantomData};
+
+use fallible_iterator::{FallibleIterator, Peekable};
+use heed::Comparator;
+
+use crate::{
+    db::error::{IterInit, IterItem},
+    RoDatabaseDup, RoDatabaseUnique, Txn,
+};
+
+/// A key for which two databases differ.
+/// Keys are encoded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DiffEntry {
+    OnlyInA(Vec<u8>),
+    OnlyInB(Vec<u8>),
+    /// The values for the key differ. For databases with duplicate keys,
+    /// the set of values for the key differs.
+    ValueDiffers(Vec<u8>),
+}
+
+/// Merge-join over two sorted iterators of encoded items
+struct Diff<A, B, C>
+where
+    A: FallibleIterator,
+    B: FallibleIterator,
+{
+    a: Peekable<A>,
+    b: Peekable<B>,
+    a_values: Vec<Vec<u8>>,
+    b_values: Vec<Vec<u8>>,
+    _comparator: PhantomData<C>,
+}
+
+/// Collect each value for the next key into `values`.
+/// Keys are grouped if they compare equal with `C`, which may be true of
+/// keys that are not bytewise equal.
+fn next_group<'txn, It, C>(
+    it: &mut Peekable<It>,
+    values: &mut Vec<Vec<u8>>,
+) -> Result<Option<&'txn [u8]>, IterItem>
+where
+    It: FallibleIterator<Item = (&'txn [u8], &'txn [u8]), Error = IterItem>,
+    C: Comparator,
+{
+    values.clear();
+    let Some((key, value)) = it.next()? else {
+        return Ok(None);
+    };
+    values.push(value.to_vec());
+    while let Some((_, value)) =
+        it.next_if(|(next_key, _)| C::compare(next_key, key).is_eq())?
+    {
+        values.push(value.to_vec());
+    }
+    Ok(Some(key))
+}
+
+impl<'txn, A, B, C> FallibleIterator for Diff<A, B, C>
+where
+    A: FallibleIterator<Item = (&'txn [u8], &'txn [u8]), Error = IterItem>,
+    B: FallibleIterator<Item = (&'txn [u8], &'txn [u8]), Error = IterItem>,
+    C: Comparator,
+{
+    type Item = DiffEntry;
+    type Error = IterItem;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            let a_key = self.a.peek()?.map(|(key, _)| *key);
+            let b_key = self.b.peek()?.map(|(key, _)| *key);
+            let ordering = match (a_key, b_key) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a_key), Some(b_key)) => C::compare(a_key, b_key),
+            };
+            match ordering {
+                Ordering::Less => {
+                    let key =
+                        next_group::<_, C>(&mut self.a, &mut self.a_values)?
+                            .expect("A should have a next item");
+                    return Ok(Some(DiffEntry::OnlyInA(key.to_vec())));
+                }
+                Ordering::Greater => {
+                    let key =
+                        next_group::<_, C>(&mut self.b, &mut self.b_values)?
+                            .expect("B should have a next item");
+                    return Ok(Some(DiffEntry::OnlyInB(key.to_vec())));
+                }
+                Ordering::Equal => {
+                    let key =
+                        next_group::<_, C>(&mut self.a, &mut self.a_values)?
+                            .expect("A should have a next item");
+                    let _: Option<&[u8]> =
+                        next_group::<_, C>(&mut self.b, &mut self.b_values)?;
+                    if self.a_values != self.b_values {
+                        return Ok(Some(DiffEntry::ValueDiffers(key.to_vec())));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compare the encoded keys and values of two databases,
+/// yielding each key for which they differ, in key order.
+pub fn diff_unique<
+    'a,
+    'env_a,
+    'env_b,
+    'txn,
+    'id_a,
+    'id_b,
+    TxA,
+    TxB,
+    KC,
+    DC,
+    C,
+>(
+    txn_a: &'txn TxA,
+    db_a: &'a RoDatabaseUnique<'id_a, KC, DC, C>,
+    txn_b: &'txn TxB,
+    db_b: &'a RoDatabaseUnique<'id_b, KC, DC, C>,
+) -> Result<
+    impl FallibleIterator<Item = DiffEntry, Error = IterItem> + 'txn,
+    IterInit,
+>
+where
+    'a: 'txn,
+    'env_a: 'txn,
+    'env_b: 'txn,
+    TxA: Txn<'env_a, 'id_a>,
+    TxB: Txn<'env_b, 'id_b>,
+    C: Comparator,
+{
+    Ok(Diff::<_, _, C> {
+        a: db_a.iter_bytes(txn_a)?.peekable(),
+        b: db_b.iter_bytes(txn_b)?.peekable(),
+        a_values: Vec::new(),
+        b_values: Vec::new(),
+        _comparator: PhantomData,
+    })
+}
+
+/// Compare the encoded keys and sets of values of two databases with
+/// duplicate keys, yielding each key for which they differ, in key order.
+/// Values for each key are compared in the order that they are stored.
+pub fn diff_dup<'a, 'env_a, 'env_b, 'txn, 'id_a, 'id_b, TxA, TxB, KC, DC, C>(
+    txn_a: &'txn TxA,
+    db_a: &'a RoDatabaseDup<'id_a, KC, DC, C>,
+    txn_b: &'txn TxB,
+    db_b: &'a RoDatabaseDup<'id_b, KC, DC, C>,
+) -> Result<
+    impl FallibleIterator<Item = DiffEntry, Error = IterItem> + 'txn,
+    IterInit,
+>
+where
+    'a: 'txn,
+    'env_a: 'txn,
+    'env_b: 'txn,
+    TxA: Txn<'env_a, 'id_a>,
+    TxB: Txn<'env_b, 'id_b>,
+    C: Comparator,
+{
+    Ok(Diff::<_, _, C> {
+        a: db_a.iter_bytes(txn_a)?.peekable(),
+        b: db_b.iter_bytes(txn_b)?.peekable(),
+        a_values: Vec::new(),
+        b_values: Vec::new(),
+        _comparator: PhantomData,
+    })
+}
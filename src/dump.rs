@@ -0,0 +1,389 @@
+//! Portable dumps of entire environments.
+//!
+//! A dump contains every named database in an env, with its flags and raw
+//! key-value pairs. Dumps do not depend on the LMDB file format or page
+//! size.
+//!
+//! # Format
+//! All integers are little-endian.
+//! * Magic bytes `SNEEDDMP`, followed by a `u8` format version
+//! * For each database:
+//!   * Tag byte `0x01`
+//!   * Name length (`u32`), followed by the UTF-8 name
+//!   * Database flags (`u32`)
+//!   * Number of entries (`u64`)
+//!   * For each entry, key length (`u32`), key, value length (`u32`),
+//!     value
+//! * Tag byte `0x00`
+
+use std::{
+    io::{Read, Write},
+    sync::Arc,
+};
+
+use heed::{types::Bytes, DatabaseFlags, PutFlags};
+
+use crate::{db, env, Env, RoTxn, RwTxn};
+
+const MAGIC: &[u8; 8] = b"SNEEDDMP";
+
+const FORMAT_VERSION: u8 = 1;
+
+const TAG_END: u8 = 0x00;
+
+const TAG_DB: u8 = 0x01;
+
+/// Offset of the `md_flags` field in LMDB's `MDB_db` struct,
+/// which is stored as the value for each named database in the unnamed
+/// database
+const MDB_DB_FLAGS_OFFSET: usize = 4;
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::{db, env};
+
+    #[derive(Debug, Error)]
+    #[error("Invalid record for database `{name}` in env at `{path}`")]
+    pub struct InvalidDbRecord {
+        pub(crate) name: String,
+        pub(crate) path: PathBuf,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error writing dump of env at `{path}`")]
+    pub struct Write {
+        pub(crate) path: PathBuf,
+        pub(crate) source: std::io::Error,
+    }
+
+    /// Error type for [`super::dump_env`]
+    #[derive(Debug, Error)]
+    pub enum Dump {
+        #[error(transparent)]
+        InvalidDbRecord(#[from] InvalidDbRecord),
+        #[error(transparent)]
+        IterInit(#[from] db::error::IterInit),
+        #[error(transparent)]
+        IterItem(#[from] db::error::IterItem),
+        #[error(transparent)]
+        Len(#[from] db::error::Len),
+        #[error(transparent)]
+        ListDbs(#[from] env::error::ListDbs),
+        #[error(transparent)]
+        OpenDb(#[from] env::error::OpenDb),
+        #[error(transparent)]
+        Write(#[from] Write),
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Invalid dump format version {version} at byte offset {offset}")]
+    pub struct InvalidFormatVersion {
+        pub(crate) offset: u64,
+        pub(crate) version: u8,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Invalid dump magic bytes `{}`", hex::encode(.magic))]
+    pub struct InvalidMagic {
+        pub(crate) magic: Vec<u8>,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Invalid database flags `0x{bits:x}` at byte offset {offset}")]
+    pub struct InvalidFlags {
+        pub(crate) offset: u64,
+        pub(crate) bits: u32,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Invalid database name at byte offset {offset}")]
+    pub struct InvalidName {
+        pub(crate) offset: u64,
+        pub(crate) source: std::string::FromUtf8Error,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Invalid tag byte `0x{tag:x}` at byte offset {offset}")]
+    pub struct InvalidTag {
+        pub(crate) offset: u64,
+        pub(crate) tag: u8,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error reading dump at byte offset {offset}")]
+    pub struct Read {
+        pub(crate) offset: u64,
+        pub(crate) source: std::io::Error,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Dump is truncated at byte offset {offset}")]
+    pub struct Truncated {
+        pub(crate) offset: u64,
+    }
+
+    /// Error type for [`super::restore_env`]
+    #[derive(Debug, Error)]
+    pub enum Restore {
+        #[error(transparent)]
+        CreateDb(#[from] env::error::CreateDb),
+        #[error(transparent)]
+        InvalidFlags(#[from] InvalidFlags),
+        #[error(transparent)]
+        InvalidFormatVersion(#[from] InvalidFormatVersion),
+        #[error(transparent)]
+        InvalidMagic(#[from] InvalidMagic),
+        #[error(transparent)]
+        InvalidName(#[from] InvalidName),
+        #[error(transparent)]
+        InvalidTag(#[from] InvalidTag),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+        #[error(transparent)]
+        Read(#[from] Read),
+        #[error(transparent)]
+        Truncated(#[from] Truncated),
+    }
+}
+
+/// Read the database flags from an `MDB_db` record
+fn db_record_flags(db_record: &[u8]) -> Option<DatabaseFlags> {
+    match db_record.get(MDB_DB_FLAGS_OFFSET..MDB_DB_FLAGS_OFFSET + 2)? {
+        &[b0, b1] => {
+            let bits = u16::from_ne_bytes([b0, b1]) as u32;
+            Some(DatabaseFlags::from_bits_truncate(bits))
+        }
+        _ => None,
+    }
+}
+
+/// Write a dump of every named database in the env
+pub fn dump_env<'id, W>(
+    env: &Env<'id>,
+    rotxn: &RoTxn<'_, 'id>,
+    mut writer: W,
+) -> Result<(), error::Dump>
+where
+    W: Write,
+{
+    let path = &**env.path();
+    let mut write = |bytes: &[u8]| {
+        writer.write_all(bytes).map_err(|err| error::Write {
+            path: path.to_owned(),
+            source: err,
+        })
+    };
+    let () = write(MAGIC)?;
+    let () = write(&[FORMAT_VERSION])?;
+    for (name, db_record) in env.db_records(rotxn)? {
+        let flags = db_record_flags(&db_record).ok_or_else(|| {
+            error::InvalidDbRecord {
+                name: name.clone(),
+                path: path.to_owned(),
+            }
+        })?;
+        let db = env.open_db_bytes(rotxn, Some(&name))?;
+        let len = db.len(&rotxn.inner).map_err(|err| db::error::Len {
+            db_name: name.to_owned(),
+            db_path: path.to_owned(),
+            source: err,
+        })?;
+        let () = write(&[TAG_DB])?;
+        let () = write(&(name.len() as u32).to_le_bytes())?;
+        let () = write(name.as_bytes())?;
+        let () = write(&flags.bits().to_le_bytes())?;
+        let () = write(&len.to_le_bytes())?;
+        let entries =
+            db.iter(&rotxn.inner).map_err(|err| db::error::IterInit {
+                db_name: name.to_owned(),
+                db_path: path.to_owned(),
+                source: err,
+            })?;
+        for entry in entries {
+            let (key, value) = entry.map_err(|err| db::error::IterItem {
+                db_name: name.to_owned(),
+                db_path: path.to_owned(),
+                source: err,
+            })?;
+            let () = write(&(key.len() as u32).to_le_bytes())?;
+            let () = write(key)?;
+            let () = write(&(value.len() as u32).to_le_bytes())?;
+            let () = write(value)?;
+        }
+    }
+    let () = write(&[TAG_END])?;
+    Ok(())
+}
+
+/// Wrapper that tracks the byte offset of a reader
+struct OffsetReader<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R> OffsetReader<R>
+where
+    R: Read,
+{
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), error::Restore> {
+        match self.inner.read_exact(buf) {
+            Ok(()) => {
+                self.offset += buf.len() as u64;
+                Ok(())
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Err(error::Truncated {
+                    offset: self.offset,
+                }
+                .into())
+            }
+            Err(err) => Err(error::Read {
+                offset: self.offset,
+                source: err,
+            }
+            .into()),
+        }
+    }
+
+    fn read_array<const N: usize>(
+        &mut self,
+    ) -> Result<[u8; N], error::Restore> {
+        let mut buf = [0; N];
+        let () = self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, error::Restore> {
+        let [byte] = self.read_array()?;
+        Ok(byte)
+    }
+
+    /// Read a `u32` length prefix, followed by that many bytes.
+    /// The buffer grows as bytes are read, so that a corrupt length prefix
+    /// cannot cause a large allocation.
+    fn read_bytes(&mut self, buf: &mut Vec<u8>) -> Result<(), error::Restore> {
+        let len = u32::from_le_bytes(self.read_array()?);
+        buf.clear();
+        let read = (&mut self.inner)
+            .take(len as u64)
+            .read_to_end(buf)
+            .map_err(|err| error::Read {
+                offset: self.offset,
+                source: err,
+            })?;
+        self.offset += read as u64;
+        if read as u64 != len as u64 {
+            return Err(error::Truncated {
+                offset: self.offset,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Restore a dump written by [`dump_env`].
+/// Databases are created if they do not already exist, with the flags
+/// recorded in the dump. Entries are inserted in append mode, so databases
+/// that already exist must be empty.
+pub fn restore_env<'id, R>(
+    env: &Env<'id>,
+    rwtxn: &mut RwTxn<'_, 'id>,
+    reader: R,
+) -> Result<(), error::Restore>
+where
+    R: Read,
+{
+    let mut reader = OffsetReader {
+        inner: reader,
+        offset: 0,
+    };
+    let magic: [u8; MAGIC.len()] = reader.read_array()?;
+    if magic != *MAGIC {
+        return Err(error::InvalidMagic {
+            magic: magic.to_vec(),
+        }
+        .into());
+    }
+    let version_offset = reader.offset;
+    let version = reader.read_u8()?;
+    if version != FORMAT_VERSION {
+        return Err(error::InvalidFormatVersion {
+            offset: version_offset,
+            version,
+        }
+        .into());
+    }
+    let path = &**env.path();
+    let mut prev_key = Vec::new();
+    let mut key = Vec::new();
+    let mut value = Vec::new();
+    loop {
+        let tag_offset = reader.offset;
+        match reader.read_u8()? {
+            TAG_END => return Ok(()),
+            TAG_DB => (),
+            tag => {
+                return Err(error::InvalidTag {
+                    offset: tag_offset,
+                    tag,
+                }
+                .into())
+            }
+        }
+        let name_offset = reader.offset;
+        let mut name_bytes = Vec::new();
+        let () = reader.read_bytes(&mut name_bytes)?;
+        let name = String::from_utf8(name_bytes).map_err(|err| {
+            error::InvalidName {
+                offset: name_offset,
+                source: err,
+            }
+        })?;
+        let flags_offset = reader.offset;
+        let flags_bits = u32::from_le_bytes(reader.read_array()?);
+        let flags = DatabaseFlags::from_bits(flags_bits).ok_or(
+            error::InvalidFlags {
+                offset: flags_offset,
+                bits: flags_bits,
+            },
+        )?;
+        let mut db_opts = env.database_options().types::<Bytes, Bytes>();
+        db_opts.name(&name).flags(flags);
+        let db = db_opts.create(rwtxn.write_txn()).map_err(|err| {
+            env::error::CreateDb {
+                name: name.clone(),
+                path: path.to_owned(),
+                source: err,
+            }
+        })?;
+        // Recorded so that caches of the db are invalidated on commit
+        let () = rwtxn.record_write(&Arc::from(name.as_str()));
+        let dup_sort = flags.contains(DatabaseFlags::DUP_SORT);
+        let len = u64::from_le_bytes(reader.read_array()?);
+        for idx in 0..len {
+            std::mem::swap(&mut prev_key, &mut key);
+            let () = reader.read_bytes(&mut key)?;
+            let () = reader.read_bytes(&mut value)?;
+            // Duplicate values for the previous key are appended to the
+            // previous key's values
+            let put_flags = if dup_sort && idx != 0 && key == prev_key {
+                PutFlags::APPEND_DUP
+            } else {
+                PutFlags::APPEND
+            };
+            let () = db
+                .put_with_flags(rwtxn.write_txn(), put_flags, &key, &value)
+                .map_err(|err| db::error::Put {
+                    db_name: name.clone(),
+                    db_path: path.to_owned(),
+                    key_bytes: Box::new(Ok(key.clone())),
+                    value_bytes: Box::new(Ok(value.clone())),
+                    source: err.into(),
+                })?;
+        }
+    }
+}
@@ -1,12 +1,109 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc, Mutex, PoisonError, Weak,
+    },
+    time::{Duration, Instant},
+};
 
-use crate::{EnvOpenOptions, RoTxn, RwTxn};
+use heed::types::Bytes;
+
+use crate::{
+    db,
+    usage::{DbUsage, UsageReport},
+    EnvOpenOptions, RoTxn, RwTxn,
+};
 
 pub mod error {
     use std::path::PathBuf;
 
     use thiserror::Error;
 
+    use crate::db;
+
+    /// Returned when opening an env that has a live `Env` in this process,
+    /// that was opened with a different tag.
+    /// See [`crate::Env::open_tagged`].
+    /// The path is the one passed to the open fn, which may differ from the
+    /// path of the live env if either is not canonical.
+    #[derive(Debug, Error)]
+    #[error(
+        "Database env at (`{path}`) is already open in this process, with a different tag"
+    )]
+    pub struct AlreadyOpen {
+        pub(crate) path: PathBuf,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error copying database env at (`{path}`) to (`{dst}`)")]
+    pub struct CopyToFile {
+        pub(crate) path: PathBuf,
+        pub(crate) dst: PathBuf,
+        pub(crate) source: heed::Error,
+    }
+
+    #[cfg(feature = "backup")]
+    #[derive(Debug, Error)]
+    #[error("Error hashing backup at (`{dst}`)")]
+    pub struct HashBackup {
+        pub(crate) dst: PathBuf,
+        pub(crate) source: std::io::Error,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error writing snapshot manifest (`{path}`)")]
+    pub struct WriteManifest {
+        pub(crate) path: PathBuf,
+        pub(crate) source: std::io::Error,
+    }
+
+    /// Error type for [`super::Env::snapshot_to_dir`]
+    #[derive(Debug, Error)]
+    pub enum SnapshotToDir {
+        #[error(transparent)]
+        CopyToFile(#[from] CopyToFile),
+        #[error(transparent)]
+        CreateDir(#[from] CreateDir),
+        #[error(transparent)]
+        WriteManifest(#[from] WriteManifest),
+    }
+
+    /// Error type for [`super::Env::backup`]
+    #[cfg(feature = "backup")]
+    #[derive(Debug, Error)]
+    pub enum Backup {
+        #[error(transparent)]
+        CopyToFile(#[from] CopyToFile),
+        #[error(transparent)]
+        HashBackup(#[from] HashBackup),
+    }
+
+    #[derive(Debug, Error)]
+    #[error(
+        "Database env at (`{path}`) is still referenced by {references} other handles"
+    )]
+    pub struct StillReferenced {
+        pub(crate) path: PathBuf,
+        pub(crate) references: usize,
+    }
+
+    /// Error type for [`super::ClosingEvent::wait_timeout`]
+    #[derive(Debug, Error)]
+    pub enum Close {
+        #[error(transparent)]
+        StillReferenced(#[from] StillReferenced),
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error creating database env dir (`{path}`)")]
+    pub struct CreateDir {
+        pub(crate) path: PathBuf,
+        pub(crate) source: std::io::Error,
+    }
+
     #[derive(Debug, Error)]
     #[error("Error creating database `{name}` in `{path}`")]
     pub struct CreateDb {
@@ -15,6 +112,47 @@ pub mod error {
         pub(crate) source: heed::Error,
     }
 
+    #[derive(Debug, Error)]
+    #[error("Invalid database name in env at `{path}` (`{}`)", hex::encode(.name_bytes))]
+    pub struct InvalidDbName {
+        pub(crate) path: PathBuf,
+        pub(crate) name_bytes: Vec<u8>,
+    }
+
+    /// Error type for [`super::Env::database_names`]
+    #[derive(Debug, Error)]
+    pub enum ListDbs {
+        #[error(transparent)]
+        InvalidDbName(#[from] InvalidDbName),
+        #[error(transparent)]
+        IterInit(#[from] db::error::IterInit),
+        #[error(transparent)]
+        IterItem(#[from] db::error::IterItem),
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error opening database `{name}` in env at `{path}`")]
+    pub struct OpenDb {
+        pub(crate) name: String,
+        pub(crate) path: PathBuf,
+        pub(crate) source: heed::Error,
+    }
+
+    #[derive(Debug, Error)]
+    #[error("Error reading flags for database env at (`{path}`)")]
+    pub struct GetFlags {
+        pub(crate) path: PathBuf,
+        pub(crate) source: heed::Error,
+    }
+
+    #[cfg(feature = "writer-lock")]
+    #[derive(Debug, Error)]
+    #[error("Error acquiring writer lock file (`{lock_path}`)")]
+    pub struct LockFile {
+        pub(crate) lock_path: PathBuf,
+        pub(crate) source: std::io::Error,
+    }
+
     #[derive(Debug, Error)]
     #[error("Error opening database env at (`{path}`)")]
     pub struct OpenEnv {
@@ -22,6 +160,36 @@ pub mod error {
         pub(crate) source: heed::Error,
     }
 
+    /// Error type for [`super::Env::open`]
+    #[derive(Debug, Error)]
+    pub enum Open {
+        #[error(transparent)]
+        AlreadyOpen(#[from] AlreadyOpen),
+        #[error(transparent)]
+        OpenEnv(#[from] OpenEnv),
+    }
+
+    /// Error type for [`super::Env::open_create_dir`]
+    #[derive(Debug, Error)]
+    pub enum OpenCreateDir {
+        #[error(transparent)]
+        CreateDir(#[from] CreateDir),
+        #[error(transparent)]
+        Open(#[from] Open),
+    }
+
+    /// Error type for [`super::Env::open_with_writer_lock`]
+    #[cfg(feature = "writer-lock")]
+    #[derive(Debug, Error)]
+    pub enum OpenWithWriterLock {
+        #[error(transparent)]
+        LockFile(#[from] LockFile),
+        #[error(transparent)]
+        Open(#[from] Open),
+        #[error(transparent)]
+        WriteLocked(#[from] WriteLocked),
+    }
+
     #[derive(Debug, Error)]
     #[error("Error creating read txn for database dir `{db_dir}`")]
     pub struct ReadTxn {
@@ -29,6 +197,24 @@ pub mod error {
         pub(crate) source: heed::Error,
     }
 
+    #[derive(Debug, Error)]
+    #[error("Error setting flags for database env at (`{path}`)")]
+    pub struct SetFlags {
+        pub(crate) path: PathBuf,
+        pub(crate) source: heed::Error,
+    }
+
+    /// Error type for [`super::Env::usage_report`]
+    #[derive(Debug, Error)]
+    pub enum UsageReport {
+        #[error(transparent)]
+        ListDbs(#[from] ListDbs),
+        #[error(transparent)]
+        OpenDb(#[from] OpenDb),
+        #[error(transparent)]
+        Stat(#[from] db::error::Stat),
+    }
+
     #[derive(Debug, Error)]
     #[error("Error creating write txn for database dir `{db_dir}`")]
     pub struct WriteTxn {
@@ -36,92 +222,1312 @@ pub mod error {
         pub(crate) source: heed::Error,
     }
 
+    #[derive(Debug, Error)]
+    #[error(
+        "Nested write txns are not supported for database dir `{db_dir}`, since the env was opened with `WRITE_MAP`"
+    )]
+    pub struct UnsupportedWithWriteMap {
+        pub(crate) db_dir: PathBuf,
+    }
+
+    /// Error creating a nested write txn.
+    /// See [`super::Env::supports_nested_txns`].
+    #[derive(Debug, Error)]
+    pub enum NestedWriteTxn {
+        #[error(transparent)]
+        UnsupportedWithWriteMap(#[from] UnsupportedWithWriteMap),
+        #[error(transparent)]
+        WriteTxn(#[from] WriteTxn),
+    }
+
+    /// Error type for [`super::Env::transaction`]
+    #[derive(Debug, Error)]
+    pub enum Transaction {
+        #[error(transparent)]
+        ReadTxn(#[from] ReadTxn),
+        #[error(transparent)]
+        WriteTxn(#[from] WriteTxn),
+    }
+
+    #[cfg(feature = "writer-lock")]
+    #[derive(Debug, Error)]
+    #[error(
+        "Database env at (`{path}`) is locked by another writer (lock file: `{lock_path}`)"
+    )]
+    pub struct WriteLocked {
+        pub(crate) path: PathBuf,
+        pub(crate) lock_path: PathBuf,
+    }
+
     /// General error type for Env operations
     #[derive(Debug, Error)]
     pub enum Error {
+        #[error(transparent)]
+        AlreadyOpen(#[from] AlreadyOpen),
+        #[error(transparent)]
+        Commit(#[from] crate::rwtxn::error::Commit),
         #[error(transparent)]
         CreateDb(#[from] CreateDb),
         #[error(transparent)]
+        CreateDir(#[from] CreateDir),
+        #[error(transparent)]
+        GetFlags(#[from] GetFlags),
+        #[error(transparent)]
         OpenEnv(#[from] OpenEnv),
         #[error(transparent)]
         ReadTxn(#[from] ReadTxn),
         #[error(transparent)]
+        SetFlags(#[from] SetFlags),
+        #[error(transparent)]
+        StillReferenced(#[from] StillReferenced),
+        #[error(transparent)]
+        UnsupportedWithWriteMap(#[from] UnsupportedWithWriteMap),
+        #[error(transparent)]
         WriteTxn(#[from] WriteTxn),
     }
+
+    impl From<Close> for Error {
+        fn from(err: Close) -> Self {
+            match err {
+                Close::StillReferenced(err) => Self::StillReferenced(err),
+            }
+        }
+    }
+
+    impl From<Open> for Error {
+        fn from(err: Open) -> Self {
+            match err {
+                Open::AlreadyOpen(err) => Self::AlreadyOpen(err),
+                Open::OpenEnv(err) => Self::OpenEnv(err),
+            }
+        }
+    }
+
+    impl From<NestedWriteTxn> for Error {
+        fn from(err: NestedWriteTxn) -> Self {
+            match err {
+                NestedWriteTxn::UnsupportedWithWriteMap(err) => {
+                    Self::UnsupportedWithWriteMap(err)
+                }
+                NestedWriteTxn::WriteTxn(err) => Self::WriteTxn(err),
+            }
+        }
+    }
+
+    impl From<Transaction> for Error {
+        fn from(err: Transaction) -> Self {
+            match err {
+                Transaction::ReadTxn(err) => Self::ReadTxn(err),
+                Transaction::WriteTxn(err) => Self::WriteTxn(err),
+            }
+        }
+    }
+
+    impl From<OpenCreateDir> for Error {
+        fn from(err: OpenCreateDir) -> Self {
+            match err {
+                OpenCreateDir::CreateDir(err) => Self::CreateDir(err),
+                OpenCreateDir::Open(err) => err.into(),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{
+            AlreadyOpen, Close, CreateDb, CreateDir, Error, GetFlags,
+            InvalidDbName, ListDbs, NestedWriteTxn, Open, OpenCreateDir,
+            OpenDb, OpenEnv, ReadTxn, SetFlags, StillReferenced, Transaction,
+            UnsupportedWithWriteMap, UsageReport, WriteTxn,
+        };
+        #[cfg(feature = "backup")]
+        use super::{Backup, HashBackup};
+        use super::{CopyToFile, SnapshotToDir, WriteManifest};
+        #[cfg(feature = "writer-lock")]
+        use super::{LockFile, OpenWithWriterLock, WriteLocked};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(AlreadyOpen { path: PathStr });
+
+        serialize_error_struct!(CopyToFile {
+            path: PathStr,
+            dst: PathStr,
+            source: DisplayStr,
+        });
+
+        #[cfg(feature = "backup")]
+        serialize_error_struct!(HashBackup {
+            dst: PathStr,
+            source: DisplayStr,
+        });
+
+        #[cfg(feature = "backup")]
+        serialize_error_enum!(Backup {
+            CopyToFile,
+            HashBackup
+        });
+
+        serialize_error_enum!(Close { StillReferenced });
+
+        serialize_error_struct!(CreateDb {
+            name: Plain,
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(CreateDir {
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(GetFlags {
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(InvalidDbName {
+            path: PathStr,
+            name_bytes: Hex,
+        });
+
+        serialize_error_enum!(ListDbs {
+            InvalidDbName,
+            IterInit,
+            IterItem
+        });
+
+        #[cfg(feature = "writer-lock")]
+        serialize_error_struct!(LockFile {
+            lock_path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(OpenDb {
+            name: Plain,
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(OpenEnv {
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_enum!(Open {
+            AlreadyOpen,
+            OpenEnv
+        });
+
+        serialize_error_enum!(OpenCreateDir { CreateDir, Open });
+
+        serialize_error_enum!(SnapshotToDir {
+            CopyToFile,
+            CreateDir,
+            WriteManifest
+        });
+
+        #[cfg(feature = "writer-lock")]
+        serialize_error_enum!(OpenWithWriterLock {
+            LockFile,
+            Open,
+            WriteLocked
+        });
+
+        serialize_error_struct!(ReadTxn {
+            db_dir: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(SetFlags {
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(StillReferenced {
+            path: PathStr,
+            references: Plain,
+        });
+
+        serialize_error_enum!(NestedWriteTxn {
+            UnsupportedWithWriteMap,
+            WriteTxn
+        });
+
+        serialize_error_enum!(Transaction { ReadTxn, WriteTxn });
+
+        serialize_error_struct!(UnsupportedWithWriteMap { db_dir: PathStr });
+
+        serialize_error_enum!(UsageReport {
+            ListDbs,
+            OpenDb,
+            Stat
+        });
+
+        #[cfg(feature = "writer-lock")]
+        serialize_error_struct!(WriteLocked {
+            path: PathStr,
+            lock_path: PathStr,
+        });
+
+        serialize_error_struct!(WriteManifest {
+            path: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_struct!(WriteTxn {
+            db_dir: PathStr,
+            source: DisplayStr,
+        });
+
+        serialize_error_enum!(Error {
+            AlreadyOpen,
+            Commit,
+            CreateDb,
+            CreateDir,
+            GetFlags,
+            OpenEnv,
+            ReadTxn,
+            SetFlags,
+            StillReferenced,
+            UnsupportedWithWriteMap,
+            WriteTxn
+        });
+    }
 }
 pub use error::Error;
 
+mod durability;
+pub use durability::Durability;
+
+pub mod snapshot;
+pub use snapshot::{DbReader, Snapshot};
+
+pub mod write_guarded;
+pub use write_guarded::{GuardedRwTxn, WriteGuarded, WriterInfo};
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod writer;
+#[cfg(feature = "tokio")]
+pub use writer::{Writer, WriterHandle};
+
+/// Result of a successful [`Env::backup`]
+#[cfg(feature = "backup")]
+#[cfg_attr(docsrs, doc(cfg(feature = "backup")))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BackupReport {
+    /// Size of the backup file
+    pub bytes: u64,
+    /// BLAKE3 hash of the backup file
+    pub blake3: [u8; 32],
+}
+
+/// Result of a successful [`Env::snapshot_to_dir`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SnapshotInfo {
+    /// Commit sequence number when the snapshot was started.
+    /// This is a lower bound: the snapshot contains at least the writes
+    /// committed up to this sequence number, and may contain later writes.
+    /// See [`Env::last_commit_seq`].
+    pub commit_seq: u64,
+    /// Time at which the snapshot was started
+    pub timestamp: std::time::SystemTime,
+    /// Size of the copied data file
+    pub bytes: u64,
+}
+
+/// Configuration of an open env, as reported by LMDB.
+/// See [`Env::open_config`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OpenConfig {
+    /// Size of the memory map
+    pub map_size: usize,
+    /// Maximum number of reader slots
+    pub max_readers: u32,
+    /// Flags that the env was opened with
+    pub flags: heed::EnvFlags,
+}
+
+/// Tag of an env that is open in this process
+#[derive(Debug)]
+struct OpenEnv {
+    /// Tag that the env was opened with, if any.
+    /// See [`Env::open_tagged`].
+    tag: Option<TypeId>,
+    /// Number of live registrations
+    registrations: usize,
+}
+
+/// Envs that are open in this process, by canonical path
+static OPEN_ENVS: Mutex<BTreeMap<PathBuf, OpenEnv>> =
+    Mutex::new(BTreeMap::new());
+
+/// LMDB requires that env flags are not set by more than one thread at a
+/// time
+static SET_FLAGS: Mutex<()> = Mutex::new(());
+
+/// Registers an env path as open, until dropped
+#[derive(Debug)]
+struct Registration {
+    canonical_path: PathBuf,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let mut open_envs =
+            OPEN_ENVS.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(open_env) = open_envs.get_mut(&self.canonical_path) {
+            open_env.registrations -= 1;
+            if open_env.registrations == 0 {
+                open_envs.remove(&self.canonical_path);
+            }
+        }
+    }
+}
+
+/// Commit sequence counter for an env.
+/// Commits of top-level write txns are serialized by `lock`, so that
+/// sequence numbers are assigned in commit order. LMDB already allows only
+/// one write txn at a time, but releases its writer lock during commit,
+/// before the sequence number could otherwise be assigned.
+#[derive(Debug, Default)]
+pub(crate) struct CommitSeq {
+    lock: Mutex<()>,
+    seq: AtomicU64,
+    /// Sequence numbers of the last write txns to write to each db,
+    /// recorded before commit
+    #[cfg(feature = "cache")]
+    pub(crate) last_write_seqs: db::cache::LastWriteSeqs,
+}
+
+impl CommitSeq {
+    /// Sequence number of the last commit
+    pub(crate) fn load(&self) -> u64 {
+        self.seq.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Run `commit` with the next sequence number, and assign the sequence
+    /// number if it succeeds.
+    /// `commit` must commit a top-level write txn.
+    pub(crate) fn commit<F, T, E>(&self, commit: F) -> Result<(T, u64), E>
+    where
+        F: FnOnce(u64) -> Result<T, E>,
+    {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let seq = self.load() + 1;
+        let res = commit(seq)?;
+        let () = self.seq.store(seq, atomic::Ordering::SeqCst);
+        Ok((res, seq))
+    }
+}
+
+#[cfg(feature = "metrics")]
+type ReadCountersRegistry = Arc<
+    Mutex<std::collections::BTreeMap<Arc<str>, Arc<db::metrics::ReadCounters>>>,
+>;
+
+/// Returned by [`Env::close`], to wait for the env to close
+#[derive(Debug)]
+pub struct ClosingEvent {
+    path: Arc<Path>,
+    registration: Weak<Registration>,
+    inner: heed::EnvClosingEvent,
+}
+
+impl ClosingEvent {
+    /// Interval at which to check whether other handles have been dropped
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    /// Number of handles to the env that have not been dropped yet.
+    /// Clones of the underlying heed env, such as those obtained from
+    /// [raw handles](crate#raw-handles), are not counted, but are waited
+    /// for by [`Self::wait`] and [`Self::wait_timeout`].
+    pub fn references(&self) -> usize {
+        self.registration.strong_count()
+    }
+
+    /// Block until every other handle to the env has been dropped, and
+    /// LMDB has closed the env.
+    /// Once this returns, the env can be re-opened immediately.
+    ///
+    /// This blocks forever if a handle to the env is held by the calling
+    /// thread.
+    pub fn wait(&self) {
+        while self.references() > 0 {
+            std::thread::sleep(Self::POLL_INTERVAL);
+        }
+        self.inner.wait()
+    }
+
+    /// As with [`Self::wait`], but gives up after `timeout`.
+    /// If the env has not been closed by then, an
+    /// [`error::StillReferenced`] error reporting the number of remaining
+    /// handles is returned, and the env is closed once they are dropped.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<(), error::Close> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let references = self.references();
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if references == 0 && self.inner.wait_timeout(remaining) {
+                return Ok(());
+            }
+            if remaining.is_zero() {
+                return Err(error::StillReferenced {
+                    path: (*self.path).to_owned(),
+                    references,
+                }
+                .into());
+            }
+            std::thread::sleep(Self::POLL_INTERVAL.min(remaining));
+        }
+    }
+}
+
 /// Wrapper for heed's `Env`
 #[derive(Clone, Debug)]
 pub struct Env<'id> {
     inner: heed::Env,
     path: Arc<Path>,
+    open_options: Arc<EnvOpenOptions>,
     unique_guard: Arc<generativity::Guard<'id>>,
+    _registration: Arc<Registration>,
+    /// Number of write txns committed through this env since it was opened
+    commit_seq: Arc<CommitSeq>,
+    /// `true` if the env was opened with `WRITE_MAP`, in which case LMDB
+    /// does not support nested txns
+    write_map: bool,
+    /// Read counters for each db, by name
+    #[cfg(feature = "metrics")]
+    read_counters: ReadCountersRegistry,
+    /// Held until every clone of the env is dropped
+    #[cfg(feature = "writer-lock")]
+    _writer_lock: Option<Arc<std::fs::File>>,
 }
 
 impl<'id> Env<'id> {
+    /// Name of the manifest file written by [`Self::snapshot_to_dir`]
+    pub const SNAPSHOT_MANIFEST: &'static str = "snapshot.manifest";
+
+    /// Open an env.
+    /// An env can be opened more than once in each process. Each `Env` has
+    /// a distinct brand, so txns and dbs from one `Env` cannot be used with
+    /// another, even if both are opened from the same path.
+    ///
+    /// If the env is already open with a tag (see [`Self::open_tagged`]),
+    /// [`error::Open::AlreadyOpen`] is returned.
+    ///
     /// # Safety
     /// See [`heed::EnvOpenOptions::open`]
     pub unsafe fn open(
         unique_guard: generativity::Guard<'id>,
         opts: &EnvOpenOptions,
         path: &Path,
-    ) -> Result<Self, error::OpenEnv> {
+    ) -> Result<Self, error::Open> {
+        Self::open_with_tag(unique_guard, opts, path, None)
+    }
+
+    /// Open an env, as with [`Self::open`], recording `Tag` as the runtime
+    /// tag of the env.
+    /// If the env is already open in this process with a different tag, or
+    /// without a tag, [`error::Open::AlreadyOpen`] is returned. Opening the
+    /// env again with the same tag succeeds.
+    /// The tag is released once every clone of each `Env` opened with it is
+    /// dropped.
+    ///
+    /// # Safety
+    /// See [`heed::EnvOpenOptions::open`]
+    pub unsafe fn open_tagged<Tag>(
+        unique_guard: generativity::Guard<'id>,
+        opts: &EnvOpenOptions,
+        path: &Path,
+    ) -> Result<Self, error::Open>
+    where
+        Tag: 'static,
+    {
+        Self::open_with_tag(unique_guard, opts, path, Some(TypeId::of::<Tag>()))
+    }
+
+    /// # Safety
+    /// See [`heed::EnvOpenOptions::open`]
+    unsafe fn open_with_tag(
+        unique_guard: generativity::Guard<'id>,
+        opts: &EnvOpenOptions,
+        path: &Path,
+        tag: Option<TypeId>,
+    ) -> Result<Self, error::Open> {
         let inner = match opts.open(path) {
             Ok(env) => env,
             Err(err) => {
                 return Err(error::OpenEnv {
                     path: path.to_owned(),
                     source: err,
-                })
+                }
+                .into())
             }
         };
+        let write_map = inner.get_flags().is_ok_and(|flags| {
+            heed::EnvFlags::from_bits_truncate(flags)
+                .contains(heed::EnvFlags::WRITE_MAP)
+        });
+        let canonical_path = inner.path().to_owned();
+        {
+            let mut open_envs =
+                OPEN_ENVS.lock().unwrap_or_else(PoisonError::into_inner);
+            let open_env =
+                open_envs.entry(canonical_path.clone()).or_insert(OpenEnv {
+                    tag,
+                    registrations: 0,
+                });
+            if open_env.tag != tag {
+                return Err(error::AlreadyOpen {
+                    path: path.to_owned(),
+                }
+                .into());
+            }
+            open_env.registrations += 1;
+        }
         Ok(Self {
             inner,
             path: Arc::from(path),
+            open_options: Arc::new(opts.clone()),
             unique_guard: Arc::new(unique_guard),
+            _registration: Arc::new(Registration { canonical_path }),
+            commit_seq: Arc::default(),
+            write_map,
+            #[cfg(feature = "metrics")]
+            read_counters: Arc::default(),
+            #[cfg(feature = "writer-lock")]
+            _writer_lock: None,
         })
     }
 
+    /// Open an env, as with [`Self::open`], creating the env dir and any
+    /// missing parent dirs first.
+    /// The env must not be opened with the `NO_SUB_DIR` flag, since `path`
+    /// is created as a dir.
+    ///
+    /// # Safety
+    /// See [`heed::EnvOpenOptions::open`]
+    pub unsafe fn open_create_dir(
+        unique_guard: generativity::Guard<'id>,
+        opts: &EnvOpenOptions,
+        path: &Path,
+    ) -> Result<Self, error::OpenCreateDir> {
+        let () =
+            std::fs::create_dir_all(path).map_err(|err| error::CreateDir {
+                path: path.to_owned(),
+                source: err,
+            })?;
+        let env = Self::open(unique_guard, opts, path)?;
+        Ok(env)
+    }
+
+    /// Open an env, as with [`Self::open`].
+    /// Unless the env is opened read-only, an exclusive advisory lock is
+    /// acquired on the file at `lock_path`, which is created if it does not
+    /// exist. If the lock is held by another process, a
+    /// [`error::WriteLocked`] error is returned.
+    /// The lock is released once every clone of the `Env` is dropped.
+    ///
+    /// The lock only excludes other processes that use the same lock file.
+    ///
+    /// # Safety
+    /// See [`heed::EnvOpenOptions::open`]
+    #[cfg(feature = "writer-lock")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "writer-lock")))]
+    pub unsafe fn open_with_writer_lock(
+        unique_guard: generativity::Guard<'id>,
+        opts: &EnvOpenOptions,
+        path: &Path,
+        lock_path: &Path,
+    ) -> Result<Self, error::OpenWithWriterLock> {
+        use fs2::FileExt as _;
+
+        let mut env = Self::open(unique_guard, opts, path)?;
+        let flags = env.inner.get_flags().map_err(|err| {
+            error::Open::from(error::OpenEnv {
+                path: path.to_owned(),
+                source: err,
+            })
+        })?;
+        if flags & heed::EnvFlags::READ_ONLY.bits() != 0 {
+            return Ok(env);
+        }
+        let lock_file_err = |err| error::LockFile {
+            lock_path: lock_path.to_owned(),
+            source: err,
+        };
+        let lock_file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_path)
+            .map_err(lock_file_err)?;
+        match lock_file.try_lock_exclusive() {
+            Ok(()) => (),
+            Err(err)
+                if err.raw_os_error()
+                    == fs2::lock_contended_error().raw_os_error() =>
+            {
+                return Err(error::WriteLocked {
+                    path: path.to_owned(),
+                    lock_path: lock_path.to_owned(),
+                }
+                .into())
+            }
+            Err(err) => return Err(lock_file_err(err).into()),
+        }
+        env._writer_lock = Some(Arc::new(lock_file));
+        Ok(env)
+    }
+
+    /// Close the env.
+    /// The env is closed once every other handle to it has been dropped,
+    /// which can be waited for with the returned [`ClosingEvent`].
+    ///
+    /// Other handles include clones of the `Env`, including clones held by
+    /// [static read txns](crate::StaticRoTxn) and by a
+    /// [`Writer`](writer::Writer), which holds its clone until every
+    /// [`WriterHandle`](writer::WriterHandle) has been dropped and the
+    /// writer has stopped.
+    /// Db handles do not keep the env open, but must not be used with
+    /// txns from the env once it has been re-opened.
+    ///
+    /// ```
+    /// # use sneed::{make_guard, Env, EnvOpenOptions};
+    /// use std::time::Duration;
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// make_guard!(guard);
+    /// let env = unsafe { Env::open(guard, &EnvOpenOptions::new(), path) }?;
+    /// let reader_env = env.clone();
+    /// let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    /// std::thread::scope(|scope| {
+    ///     let reader = scope.spawn(move || {
+    ///         let _rotxn = reader_env.read_txn().unwrap();
+    ///         let _ = done_rx.recv();
+    ///     });
+    ///     let closing = env.close();
+    ///     let err = closing.wait_timeout(Duration::from_millis(10)).unwrap_err();
+    ///     assert!(err.to_string().contains("1 other handles"));
+    ///     drop(done_tx);
+    ///     let () = reader.join().unwrap();
+    ///     closing.wait_timeout(Duration::from_secs(10))
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    pub fn close(self) -> ClosingEvent {
+        let Self {
+            inner,
+            path,
+            _registration,
+            ..
+        } = self;
+        let registration = Arc::downgrade(&_registration);
+        let inner = inner.prepare_for_closing();
+        drop(_registration);
+        ClosingEvent {
+            path,
+            registration,
+            inner,
+        }
+    }
+
+    /// Clone the env handle, re-tagging it with a new guard.
+    /// This is an escape hatch for interop with code that is generic over
+    /// the env tag.
+    ///
+    /// # Safety
+    /// The tag is what prevents handles from different envs being used
+    /// together. Handles derived from this env must be re-tagged (eg. with
+    /// [`crate::RoDatabaseUnique::retag`]) before being used with the new
+    /// env, and must only ever be re-tagged with clones of this env.
+    pub unsafe fn clone_with_tag<'new>(
+        &self,
+        unique_guard: generativity::Guard<'new>,
+    ) -> Env<'new> {
+        Env {
+            inner: self.inner.clone(),
+            path: self.path.clone(),
+            open_options: self.open_options.clone(),
+            unique_guard: Arc::new(unique_guard),
+            _registration: self._registration.clone(),
+            commit_seq: self.commit_seq.clone(),
+            write_map: self.write_map,
+            #[cfg(feature = "metrics")]
+            read_counters: self.read_counters.clone(),
+            #[cfg(feature = "writer-lock")]
+            _writer_lock: self._writer_lock.clone(),
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn unique_guard(&self) -> &Arc<generativity::Guard<'id>> {
         &self.unique_guard
     }
 
+    /// Commit sequence number of the last write txn committed through this
+    /// env, or `0` if no write txn has been committed since the env was
+    /// opened.
+    ///
+    /// Each commit of a top-level write txn increments the sequence number
+    /// by one, including commits of txns that made no changes. Commits of
+    /// nested txns do not. The sequence number is not persisted, and starts
+    /// from `0` each time the env is opened.
+    /// See [`RwTxn::commit_with_seq`] and [`RoTxn::snapshot_seq`].
+    ///
+    /// ```
+    /// # use sneed::{make_guard, Env, EnvOpenOptions};
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let env = unsafe { Env::open(guard, &EnvOpenOptions::new(), path) }?;
+    /// let seq = env.last_commit_seq();
+    /// let mut rwtxn = env.write_txn()?;
+    /// let mut savepoints = rwtxn.savepoints();
+    /// let () = savepoints.push("nested")?;
+    /// let () = savepoints.release("nested")?;
+    /// drop(savepoints);
+    /// assert_eq!(env.last_commit_seq(), seq);
+    /// assert_eq!(rwtxn.commit_with_seq()?, seq + 1);
+    /// assert_eq!(env.read_txn()?.snapshot_seq(), seq + 1);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    pub fn last_commit_seq(&self) -> u64 {
+        self.commit_seq.load()
+    }
+
+    /// Read counters for the db with the specified name, shared by every
+    /// handle to the db
+    #[cfg(feature = "metrics")]
+    pub(crate) fn read_counters(
+        &self,
+        name: &str,
+    ) -> Arc<db::metrics::ReadCounters> {
+        let mut read_counters = self
+            .read_counters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(counters) = read_counters.get(name) {
+            return counters.clone();
+        }
+        read_counters.entry(Arc::from(name)).or_default().clone()
+    }
+
+    /// Read stats for each db that has been created or opened through this
+    /// env, by name.
+    /// See [`crate::RoDatabaseUnique::read_stats`].
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+    pub fn read_stats(
+        &self,
+    ) -> std::collections::BTreeMap<String, db::ReadStats> {
+        let read_counters = self
+            .read_counters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        read_counters
+            .iter()
+            .map(|(name, counters)| (name.to_string(), counters.stats()))
+            .collect()
+    }
+
+    /// `true` if nested write txns, such as [`RwTxn::savepoints`], can be
+    /// created in this env.
+    /// LMDB does not support nested txns in envs opened with `WRITE_MAP`;
+    /// creating one fails with
+    /// [`error::NestedWriteTxn::UnsupportedWithWriteMap`].
+    ///
+    /// ```
+    /// # use sneed::{make_guard, Env, EnvOpenOptions};
+    /// use sneed::flags::EnvFlags;
+    ///
+    /// # fn example(
+    /// #     path0: &std::path::Path,
+    /// #     path1: &std::path::Path,
+    /// # ) -> Result<(), Box<dyn std::error::Error>> {
+    /// make_guard!(guard0);
+    /// let env = unsafe { Env::open(guard0, &EnvOpenOptions::new(), path0) }?;
+    /// assert!(env.supports_nested_txns());
+    /// let mut rwtxn = env.write_txn()?;
+    /// let () = rwtxn.savepoints().push("nested")?;
+    ///
+    /// make_guard!(guard1);
+    /// let mut opts = EnvOpenOptions::new();
+    /// let _ = unsafe { opts.flags(EnvFlags::WRITE_MAP) };
+    /// let env = unsafe { Env::open(guard1, &opts, path1) }?;
+    /// assert!(!env.supports_nested_txns());
+    /// let mut rwtxn = env.write_txn()?;
+    /// let err = rwtxn.savepoints().push("nested").unwrap_err();
+    /// assert!(matches!(
+    ///     err,
+    ///     sneed::env::error::NestedWriteTxn::UnsupportedWithWriteMap(_)
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// # let (dir0, dir1) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    /// # example(dir0.path(), dir1.path()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn supports_nested_txns(&self) -> bool {
+        !self.write_map
+    }
+
+    /// `true` if the env was opened with [`heed::EnvFlags::NO_TLS`], so
+    /// that read txns are not tied to the thread that created them
+    #[cfg(feature = "rayon")]
+    pub(crate) fn no_tls(&self) -> bool {
+        self.inner.get_flags().is_ok_and(|flags| {
+            heed::EnvFlags::from_bits_truncate(flags)
+                .contains(heed::EnvFlags::NO_TLS)
+        })
+    }
+
+    /// Id of the last committed write txn
+    #[inline(always)]
+    pub(crate) fn last_txn_id(&self) -> usize {
+        self.inner.info().last_txn_id
+    }
+
+    #[cfg(feature = "cache")]
+    #[inline(always)]
+    pub(crate) fn last_write_seqs(&self) -> &db::cache::LastWriteSeqs {
+        &self.commit_seq.last_write_seqs
+    }
+
+    /// The underlying heed env.
+    /// See [raw handles](crate#raw-handles).
+    #[cfg(feature = "raw")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+    #[inline(always)]
+    pub fn as_heed(&self) -> &heed::Env {
+        &self.inner
+    }
+
     #[inline(always)]
     pub fn path(&self) -> &Arc<Path> {
         &self.path
     }
 
+    /// The options that the env was opened with.
+    /// These can be used to open another env with the same configuration.
+    #[inline(always)]
+    pub fn open_options(&self) -> &EnvOpenOptions {
+        &self.open_options
+    }
+
+    /// The configuration of the env, as reported by LMDB.
+    /// The map size is the current map size, which may be larger than the
+    /// map size that the env was opened with, if the env was resized.
+    /// LMDB does not report the maximum number of named dbs; see
+    /// [`Self::open_options`].
+    pub fn open_config(&self) -> Result<OpenConfig, error::GetFlags> {
+        let flags = self.flags()?;
+        let info = self.inner.info();
+        Ok(OpenConfig {
+            map_size: info.map_size,
+            max_readers: info.maximum_number_of_readers,
+            flags,
+        })
+    }
+
+    /// The active env flags, as reported by LMDB.
+    /// Flags that are not known to heed are omitted.
+    pub fn flags(&self) -> Result<heed::EnvFlags, error::GetFlags> {
+        let flags = self.inner.get_flags().map_err(|err| error::GetFlags {
+            path: (*self.path).to_owned(),
+            source: err,
+        })?;
+        Ok(heed::EnvFlags::from_bits_truncate(flags))
+    }
+
+    /// The durability level of the env, as reported by LMDB
+    pub fn durability(&self) -> Result<Durability, error::GetFlags> {
+        self.flags().map(Durability::from_env_flags)
+    }
+
+    /// Change the durability level of the env.
+    /// The new level applies to txns committed after this returns, and is
+    /// not persisted; envs are opened with the durability level set in
+    /// the open options.
+    ///
+    /// # Safety
+    /// See [`heed::Env::set_flags`]. Unlike heed's `set_flags`, calls are
+    /// serialized, so this may be called from multiple threads.
+    pub unsafe fn set_durability(
+        &self,
+        durability: Durability,
+    ) -> Result<(), error::SetFlags> {
+        let (disable, enable) = durability.flag_changes();
+        let _lock = SET_FLAGS.lock().unwrap_or_else(PoisonError::into_inner);
+        let err = |err| error::SetFlags {
+            path: (*self.path).to_owned(),
+            source: err,
+        };
+        let () = unsafe {
+            self.inner.set_flags(disable, heed::FlagSetMode::Disable)
+        }
+        .map_err(err)?;
+        unsafe { self.inner.set_flags(enable, heed::FlagSetMode::Enable) }
+            .map_err(err)
+    }
+
     #[inline(always)]
     pub(crate) fn database_options(
         &self,
-    ) -> heed::DatabaseOpenOptions<heed::Unspecified, heed::Unspecified> {
+    ) -> heed::DatabaseOpenOptions<'_, '_, heed::Unspecified, heed::Unspecified>
+    {
         self.inner.database_options()
     }
 
+    /// Copy the env to a new file at `dst`, and hash the copy.
+    /// If `compact` is `true`, free pages are omitted from the copy, and
+    /// pages are renumbered sequentially.
+    /// The copy is a consistent snapshot, and can be made while the env is
+    /// in use.
+    #[cfg(feature = "backup")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "backup")))]
+    pub fn backup(
+        &self,
+        dst: &Path,
+        compact: bool,
+    ) -> Result<BackupReport, error::Backup> {
+        let compaction = if compact {
+            heed::CompactionOption::Enabled
+        } else {
+            heed::CompactionOption::Disabled
+        };
+        let mut file =
+            self.inner.copy_to_file(dst, compaction).map_err(|err| {
+                error::CopyToFile {
+                    path: (*self.path).to_owned(),
+                    dst: dst.to_owned(),
+                    source: err,
+                }
+            })?;
+        let mut hasher = blake3::Hasher::new();
+        let bytes = std::io::copy(&mut file, &mut hasher).map_err(|err| {
+            error::HashBackup {
+                dst: dst.to_owned(),
+                source: err,
+            }
+        })?;
+        Ok(BackupReport {
+            bytes,
+            blake3: *hasher.finalize().as_bytes(),
+        })
+    }
+
+    /// Write a consistent snapshot of the env to the dir `dest`, which is
+    /// created if it does not exist. The snapshot can be opened as an env.
+    ///
+    /// The data file is a compacting copy, made under a single read txn,
+    /// so writes committed while the copy is in progress are not included,
+    /// and the writer is not blocked.
+    /// Once the copy is complete, a manifest file named
+    /// [`Self::SNAPSHOT_MANIFEST`] is written to `dest`, recording the
+    /// commit sequence number and the time at which the snapshot was
+    /// started, as `key=value` lines. A snapshot dir without a manifest is
+    /// incomplete.
+    ///
+    /// The recorded commit sequence number is a lower bound: the snapshot
+    /// contains every write committed up to it, and may also contain
+    /// writes committed after it, before the copy began.
+    ///
+    /// Returns an error if `dest` already contains a data file.
+    pub fn snapshot_to_dir(
+        &self,
+        dest: &Path,
+    ) -> Result<SnapshotInfo, error::SnapshotToDir> {
+        let () =
+            std::fs::create_dir_all(dest).map_err(|err| error::CreateDir {
+                path: dest.to_owned(),
+                source: err,
+            })?;
+        // heed begins the copy's read txn internally, so the commit seq
+        // cannot be read under the same txn. Read before the copy begins,
+        // so that it is a lower bound.
+        let commit_seq = self.last_commit_seq();
+        let timestamp = std::time::SystemTime::now();
+        let data_path = dest.join("data.mdb");
+        let file = self
+            .inner
+            .copy_to_file(&data_path, heed::CompactionOption::Enabled)
+            .map_err(|err| error::CopyToFile {
+                path: (*self.path).to_owned(),
+                dst: data_path.clone(),
+                source: err,
+            })?;
+        let manifest_path = dest.join(Self::SNAPSHOT_MANIFEST);
+        let write_manifest = || -> std::io::Result<u64> {
+            let bytes = file.metadata()?.len();
+            let unix_time = timestamp
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default();
+            let manifest = format!(
+                "commit_seq={commit_seq}\ntimestamp_ms={}\nbytes={bytes}\n",
+                unix_time.as_millis(),
+            );
+            let () = std::fs::write(&manifest_path, manifest)?;
+            Ok(bytes)
+        };
+        let bytes = write_manifest().map_err(|err| error::WriteManifest {
+            path: manifest_path.clone(),
+            source: err,
+        })?;
+        Ok(SnapshotInfo {
+            commit_seq,
+            timestamp,
+            bytes,
+        })
+    }
+
+    /// Open a db with keys and values as raw bytes, if it exists.
+    /// The db handle does not receive notifications for writes made using
+    /// other handles for the same db.
+    pub fn open_raw_db(
+        &self,
+        rotxn: &RoTxn<'_, 'id>,
+        name: &str,
+    ) -> Result<Option<crate::RawDatabaseUnique<'id>>, error::OpenDb> {
+        crate::RawDatabaseUnique::open(self, rotxn, name)
+    }
+
+    pub(crate) fn open_db_bytes(
+        &self,
+        rotxn: &RoTxn<'_, 'id>,
+        name: Option<&str>,
+    ) -> Result<heed::Database<Bytes, Bytes>, error::OpenDb> {
+        let db = self
+            .inner
+            .open_database::<Bytes, Bytes>(&rotxn.inner, name)
+            .map_err(|err| error::OpenDb {
+                name: name.unwrap_or_default().to_owned(),
+                path: (*self.path).to_owned(),
+                source: err,
+            })?
+            .expect("Listed database should exist");
+        Ok(db)
+    }
+
+    /// Name and `MDB_db` record for each named database, in name order.
+    /// The unnamed database contains an entry for each named database.
+    pub(crate) fn db_records(
+        &self,
+        rotxn: &RoTxn<'_, 'id>,
+    ) -> Result<Vec<(String, Vec<u8>)>, error::ListDbs> {
+        let path = &*self.path;
+        let main_db = self
+            .inner
+            .open_database::<Bytes, Bytes>(&rotxn.inner, None)
+            .map_err(|err| db::error::IterInit {
+                db_name: String::new(),
+                db_path: path.to_owned(),
+                source: err,
+            })?
+            .expect("The unnamed database always exists");
+        let main_db_entries =
+            main_db
+                .iter(&rotxn.inner)
+                .map_err(|err| db::error::IterInit {
+                    db_name: String::new(),
+                    db_path: path.to_owned(),
+                    source: err,
+                })?;
+        let mut res = Vec::new();
+        for main_db_entry in main_db_entries {
+            let (name_bytes, db_record) =
+                main_db_entry.map_err(|err| db::error::IterItem {
+                    db_name: String::new(),
+                    db_path: path.to_owned(),
+                    source: err,
+                })?;
+            let name =
+                String::from_utf8(name_bytes.to_vec()).map_err(|_| {
+                    error::InvalidDbName {
+                        path: path.to_owned(),
+                        name_bytes: name_bytes.to_vec(),
+                    }
+                })?;
+            res.push((name, db_record.to_vec()));
+        }
+        Ok(res)
+    }
+
+    /// Names of each named database in the env, in name order
+    pub fn database_names(
+        &self,
+        rotxn: &RoTxn<'_, 'id>,
+    ) -> Result<Vec<String>, error::ListDbs> {
+        let db_records = self.db_records(rotxn)?;
+        Ok(db_records.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// Disk usage for each named database, and for the env as a whole
+    pub fn usage_report(
+        &self,
+        rotxn: &RoTxn<'_, 'id>,
+    ) -> Result<UsageReport, error::UsageReport> {
+        let path = &*self.path;
+        let stat = |name: Option<&str>| {
+            let db = self.open_db_bytes(rotxn, name)?;
+            db.stat(&rotxn.inner).map_err(|err| {
+                let err = db::error::Stat {
+                    db_name: name.unwrap_or_default().to_owned(),
+                    db_path: path.to_owned(),
+                    source: err,
+                };
+                error::UsageReport::from(err)
+            })
+        };
+        let page_size = stat(None)?.page_size;
+        let mut databases = Vec::new();
+        for name in self.database_names(rotxn)? {
+            let stat = stat(Some(&name))?;
+            databases.push(DbUsage::new(name, &stat));
+        }
+        let info = self.inner.info();
+        let total_pages = info.map_size as u64 / page_size as u64;
+        // Pages are numbered from 0
+        let used_pages = info.last_page_number as u64 + 1;
+        Ok(UsageReport {
+            page_size,
+            map_size: info.map_size as u64,
+            used_pages,
+            free_pages: total_pages.saturating_sub(used_pages),
+            databases,
+        })
+    }
+
     pub fn read_txn(&self) -> Result<RoTxn<'_, 'id>, error::ReadTxn> {
+        // Read before creating the txn, so that the txn sees at least the
+        // writes committed by this commit seq
+        let snapshot_seq = self.last_commit_seq();
         let inner = self.inner.read_txn().map_err(|err| error::ReadTxn {
             db_dir: (*self.path).to_owned(),
             source: err,
         })?;
         Ok(RoTxn {
             inner,
-            _unique_guard: &self.unique_guard,
+            env: self,
+            snapshot_seq,
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!(
+                "read_txn",
+                db_dir = %self.path.display(),
+                txn_id = self.inner.info().last_txn_id,
+            ),
         })
     }
 
+    /// Create a snapshot, for consistent reads across multiple dbs.
+    /// See [`Snapshot`].
+    pub fn snapshot(&self) -> Result<Snapshot<'_, 'id>, error::ReadTxn> {
+        let rotxn = self.read_txn()?;
+        Ok(Snapshot::new(self, rotxn))
+    }
+
+    /// Call `f` with a snapshot, which is dropped once `f` returns.
+    /// See [`Snapshot`].
+    pub fn with_snapshot<F, T>(&self, f: F) -> Result<T, error::ReadTxn>
+    where
+        F: FnOnce(&Snapshot<'_, 'id>) -> T,
+    {
+        let snapshot = self.snapshot()?;
+        Ok(f(&snapshot))
+    }
+
+    /// Create a read txn that owns a clone of the env handle.
+    /// The txn can be sent between threads, and is `'static` if the env
+    /// tag is `'static`.
+    /// As with other read txns, avoid holding the txn for a long time.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn static_read_txn(
+        &self,
+    ) -> Result<crate::StaticRoTxn<'id>, error::ReadTxn> {
+        let inner = self.inner.clone().static_read_txn().map_err(|err| {
+            error::ReadTxn {
+                db_dir: (*self.path).to_owned(),
+                source: err,
+            }
+        })?;
+        Ok(crate::StaticRoTxn {
+            inner,
+            _env: self.clone(),
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!(
+                "read_txn",
+                db_dir = %self.path.display(),
+                txn_id = self.inner.info().last_txn_id,
+            ),
+        })
+    }
+
+    /// Create a write txn if `write` is set, or a read txn otherwise.
+    /// See [`crate::AnyTxn`].
+    pub fn transaction(
+        &self,
+        write: bool,
+    ) -> Result<crate::AnyTxn<'_, 'id>, error::Transaction> {
+        if write {
+            Ok(crate::AnyTxn::Write(self.write_txn()?))
+        } else {
+            Ok(crate::AnyTxn::Read(self.read_txn()?))
+        }
+    }
+
+    /// Create a write txn.
+    /// LMDB allows one write txn per env at a time, so this blocks while
+    /// another write txn is live, including one held by the current thread.
+    /// Use [`RwTxn::savepoints`] for nested txns instead.
+    ///
+    /// # Panics
+    /// With the `debug-guards` feature, panics instead of deadlocking if the
+    /// current thread already holds a write txn for this env.
     pub fn write_txn(&self) -> Result<RwTxn<'_, 'id>, error::WriteTxn> {
+        #[cfg(feature = "debug-guards")]
+        let write_marker = crate::rwtxn::WriteTxnMarker::acquire(&self.path);
         let inner = self.inner.write_txn().map_err(|err| error::WriteTxn {
             db_dir: (*self.path).to_owned(),
             source: err,
         })?;
         Ok(RwTxn {
             inner,
+            env: &self.inner,
             db_dir: &self.path,
             _unique_guard: &self.unique_guard,
+            commit_seq: &self.commit_seq,
+            write_map: self.write_map,
+            #[cfg(feature = "cache")]
+            raw_write: false,
             #[cfg(feature = "observe")]
             pending_writes: Default::default(),
+            ops_attempted: 0,
+            dbs_written: Default::default(),
+            deferred: Vec::new(),
+            #[cfg(feature = "debug-txn")]
+            drop_guard: crate::rwtxn::DropGuard::new(&self.path),
+            #[cfg(feature = "debug-guards")]
+            _write_marker: Some(write_marker),
+            // No other write txn can commit while this one is live,
+            // so the id of this txn follows the last committed txn id.
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!(
+                "write_txn",
+                db_dir = %self.path.display(),
+                txn_id = self.inner.info().last_txn_id + 1,
+            ),
+            #[cfg(feature = "tracing")]
+            writes: 0,
         })
     }
 }
@@ -0,0 +1,91 @@
+//! Durability levels, as combinations of env flags
+
+use heed::{EnvFlags, EnvOpenOptions};
+
+/// How much committed data survives a system crash.
+///
+/// Each level corresponds to a combination of the LMDB flags that control
+/// flushing to disk on commit. Application crashes do not lose committed
+/// data at any level, since commits are written to the OS before
+/// [`crate::RwTxn::commit`] returns; the levels differ in what can be lost
+/// if the OS crashes or the machine loses power before the OS flushes its
+/// buffers.
+///
+/// Durability can be set when opening an env with [`Self::apply`], and
+/// changed while the env is open with [`crate::Env::set_durability`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Durability {
+    /// Data and metadata are flushed to disk on every commit.
+    /// Committed txns survive a system crash.
+    #[default]
+    Full,
+    /// Data is flushed to disk on every commit, but metadata is only
+    /// flushed on the next commit.
+    /// A system crash can lose the last committed txn, but cannot corrupt
+    /// the env.
+    /// Sets `NO_META_SYNC`.
+    NoMetaSync,
+    /// Flushes are started on commit, but commits do not wait for them to
+    /// complete.
+    /// A system crash can lose the last committed txns, or corrupt the env.
+    /// This only has an effect on envs opened with `WRITE_MAP`; other envs
+    /// flush as with [`Self::Full`].
+    /// Sets `MAP_ASYNC`.
+    Async,
+    /// Nothing is flushed to disk on commit, and flushing is left to the
+    /// OS.
+    /// A system crash can lose the last committed txns, or corrupt the env.
+    /// Sets `NO_SYNC`.
+    None,
+}
+
+impl Durability {
+    /// Every flag that is used for some durability level
+    const MASK: EnvFlags = EnvFlags::NO_SYNC
+        .union(EnvFlags::NO_META_SYNC)
+        .union(EnvFlags::MAP_ASYNC);
+
+    /// The env flags for the durability level
+    pub fn env_flags(self) -> EnvFlags {
+        match self {
+            Self::Full => EnvFlags::empty(),
+            Self::NoMetaSync => EnvFlags::NO_META_SYNC,
+            Self::Async => EnvFlags::MAP_ASYNC,
+            Self::None => EnvFlags::NO_SYNC,
+        }
+    }
+
+    /// The durability level for a set of env flags.
+    /// Flags other than the durability flags are ignored.
+    /// If several durability flags are set, the weakest level is returned.
+    pub fn from_env_flags(flags: EnvFlags) -> Self {
+        if flags.contains(EnvFlags::NO_SYNC) {
+            Self::None
+        } else if flags.contains(EnvFlags::MAP_ASYNC) {
+            Self::Async
+        } else if flags.contains(EnvFlags::NO_META_SYNC) {
+            Self::NoMetaSync
+        } else {
+            Self::Full
+        }
+    }
+
+    /// Set the durability flags on env open options.
+    /// Flags already set on the options are kept, so this should be
+    /// applied to options that do not already have durability flags set.
+    ///
+    /// # Safety
+    /// See [`heed::EnvOpenOptions::flags`]
+    pub unsafe fn apply(
+        self,
+        opts: &mut EnvOpenOptions,
+    ) -> &mut EnvOpenOptions {
+        opts.flags(self.env_flags())
+    }
+
+    /// Flags to disable and enable to switch to this durability level
+    pub(in crate::env) fn flag_changes(self) -> (EnvFlags, EnvFlags) {
+        let enable = self.env_flags();
+        (Self::MASK.difference(enable), enable)
+    }
+}
@@ -0,0 +1,233 @@
+//! Consistent read-only views across multiple dbs
+
+use std::ops::RangeBounds;
+
+use educe::Educe;
+use fallible_iterator::FallibleIterator;
+use heed::{types::LazyDecode, BytesDecode, BytesEncode, Comparator};
+
+use crate::{
+    db::{self, RoDatabaseUnique},
+    env::error,
+    Env, RoTxn,
+};
+
+/// A read txn bundled with the env that it was created from, for
+/// consistent reads across multiple dbs.
+///
+/// Dbs opened with [`Self::db`] are opened against the snapshot's txn, and
+/// the snapshot can be used as the txn for reads, so that all reads share
+/// the same snapshot of the env. Writes committed after the snapshot was
+/// created are not visible.
+/// Snapshots are read-only.
+/// As with other read txns, avoid holding a snapshot for a long time.
+pub struct Snapshot<'env, 'env_id> {
+    env: &'env Env<'env_id>,
+    rotxn: RoTxn<'env, 'env_id>,
+}
+
+impl<'env, 'env_id> Snapshot<'env, 'env_id> {
+    pub(in crate::env) fn new(
+        env: &'env Env<'env_id>,
+        rotxn: RoTxn<'env, 'env_id>,
+    ) -> Self {
+        Self { env, rotxn }
+    }
+
+    /// Open a db against the snapshot's txn, if it exists.
+    pub fn db<KC, DC, C>(
+        &self,
+        name: &str,
+    ) -> Result<Option<RoDatabaseUnique<'env_id, KC, DC, C>>, error::OpenDb>
+    where
+        KC: 'static,
+        DC: 'static,
+        C: Comparator + 'static,
+    {
+        RoDatabaseUnique::open(self.env, &self.rotxn, name)
+    }
+
+    /// The snapshot's read txn
+    #[inline(always)]
+    pub fn txn(&self) -> &RoTxn<'env, 'env_id> {
+        &self.rotxn
+    }
+
+    /// Read from a db with the snapshot's txn, without passing the txn to
+    /// each read
+    #[inline(always)]
+    pub fn read<'s, KC, DC, C>(
+        &'s self,
+        db: &'s RoDatabaseUnique<'env_id, KC, DC, C>,
+    ) -> DbReader<'s, 'env, 'env_id, KC, DC, C> {
+        DbReader {
+            db,
+            rotxn: &self.rotxn,
+        }
+    }
+}
+
+impl<'env> crate::txn::private::Sealed<'env> for Snapshot<'env, '_> {
+    fn read_txn(&self) -> &heed::RoTxn<'env> {
+        &self.rotxn.inner
+    }
+}
+
+impl<'env, 'env_id> crate::txn::Txn<'env, 'env_id> for Snapshot<'env, 'env_id> {}
+
+/// Allows `impl Trait` return types to capture lifetimes that do not
+/// otherwise appear in their bounds
+pub trait Captures<'a> {}
+
+impl<T: ?Sized> Captures<'_> for T {}
+
+/// A db bound to the txn of a [`Snapshot`].
+/// See [`Snapshot::read`].
+#[derive(Educe)]
+#[educe(Clone)]
+pub struct DbReader<'s, 'env, 'env_id, KC, DC, C> {
+    db: &'s RoDatabaseUnique<'env_id, KC, DC, C>,
+    rotxn: &'s RoTxn<'env, 'env_id>,
+}
+
+impl<KC, DC, C> Copy for DbReader<'_, '_, '_, KC, DC, C> {}
+
+impl<'s, 'env, 'env_id, KC, DC, C> DbReader<'s, 'env, 'env_id, KC, DC, C> {
+    /// See [`RoDatabaseUnique::contains_key`]
+    #[inline(always)]
+    pub fn contains_key<'a>(
+        &self,
+        key: &'a KC::EItem,
+    ) -> Result<bool, db::error::TryGet>
+    where
+        KC: BytesEncode<'a>,
+        LazyDecode<DC>: BytesDecode<'s>,
+    {
+        self.db.contains_key(self.rotxn, key)
+    }
+
+    /// See [`RoDatabaseUnique::first`]
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn first(
+        &self,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, db::error::First>
+    where
+        KC: BytesDecode<'s>,
+        DC: BytesDecode<'s>,
+    {
+        self.db.first(self.rotxn)
+    }
+
+    /// See [`RoDatabaseUnique::get`]
+    #[inline(always)]
+    pub fn get<'a>(
+        &self,
+        key: &'a KC::EItem,
+    ) -> Result<DC::DItem, db::error::Get>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'s>,
+    {
+        self.db.get(self.rotxn, key)
+    }
+
+    /// See [`RoDatabaseUnique::iter`]
+    #[inline(always)]
+    pub fn iter(
+        &self,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = db::error::IterItem,
+            > + Captures<'env>
+            + Captures<'env_id>
+            + 's,
+        db::error::IterInit,
+    >
+    where
+        KC: BytesDecode<'s>,
+        DC: BytesDecode<'s>,
+    {
+        self.db.iter(self.rotxn)
+    }
+
+    /// See [`RoDatabaseUnique::iter_keys`]
+    #[inline(always)]
+    pub fn iter_keys(
+        &self,
+    ) -> Result<
+        impl FallibleIterator<Item = KC::DItem, Error = db::error::IterItem>
+            + Captures<'env>
+            + Captures<'env_id>
+            + 's,
+        db::error::IterInit,
+    >
+    where
+        KC: BytesDecode<'s>,
+        LazyDecode<DC>: BytesDecode<'s>,
+    {
+        self.db.iter_keys(self.rotxn)
+    }
+
+    /// Check if the db is empty
+    #[inline(always)]
+    pub fn is_empty(&self) -> Result<bool, db::error::Len> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// See [`RoDatabaseUnique::last`]
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    pub fn last(
+        &self,
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, db::error::Last>
+    where
+        KC: BytesDecode<'s>,
+        DC: BytesDecode<'s>,
+    {
+        self.db.last(self.rotxn)
+    }
+
+    /// See [`RoDatabaseUnique::len`]
+    #[inline(always)]
+    pub fn len(&self) -> Result<u64, db::error::Len> {
+        self.db.len(self.rotxn)
+    }
+
+    /// See [`RoDatabaseUnique::range`]
+    #[inline(always)]
+    pub fn range<R>(
+        &self,
+        range: &'s R,
+    ) -> Result<
+        impl FallibleIterator<
+                Item = (KC::DItem, DC::DItem),
+                Error = db::error::IterItem,
+            > + Captures<'env>
+            + Captures<'env_id>
+            + 's,
+        db::error::RangeInit,
+    >
+    where
+        KC: BytesEncode<'s> + BytesDecode<'s>,
+        R: RangeBounds<KC::EItem>,
+        DC: BytesDecode<'s>,
+        C: Comparator,
+    {
+        self.db.range(self.rotxn, range)
+    }
+
+    /// See [`RoDatabaseUnique::try_get`]
+    #[inline(always)]
+    pub fn try_get<'a>(
+        &self,
+        key: &'a KC::EItem,
+    ) -> Result<Option<DC::DItem>, db::error::TryGet>
+    where
+        KC: BytesEncode<'a>,
+        DC: BytesDecode<'s>,
+    {
+        self.db.try_get(self.rotxn, key)
+    }
+}
@@ -0,0 +1,281 @@
+//! Write access wrapper that records the current writer
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+use crate::{rwtxn, Env, RwTxn};
+
+pub mod error {
+    use std::{path::PathBuf, time::Duration};
+
+    use thiserror::Error;
+
+    use crate::env;
+
+    #[derive(Debug, Error)]
+    #[error(
+        "Timed out waiting for write access to database dir `{db_dir}`, held by `{holder_label}` for {held_for:?}"
+    )]
+    pub struct WriterBusy {
+        pub(crate) db_dir: PathBuf,
+        pub(crate) holder_label: String,
+        pub(crate) held_for: Duration,
+    }
+
+    impl WriterBusy {
+        /// Label supplied by the holder of the write txn
+        pub fn holder_label(&self) -> &str {
+            &self.holder_label
+        }
+
+        /// How long the write txn had been held when the timeout elapsed
+        pub fn held_for(&self) -> Duration {
+            self.held_for
+        }
+    }
+
+    /// Error type for [`super::WriteGuarded::write_txn`]
+    #[derive(Debug, Error)]
+    pub enum WriteTxn {
+        #[error(transparent)]
+        WriterBusy(#[from] WriterBusy),
+        #[error(transparent)]
+        WriteTxn(#[from] env::error::WriteTxn),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{WriteTxn, WriterBusy};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(WriterBusy {
+            db_dir: PathStr,
+            holder_label: Plain,
+            held_for: Plain,
+        });
+
+        serialize_error_enum!(WriteTxn {
+            WriterBusy,
+            WriteTxn
+        });
+    }
+}
+
+/// Information about the holder of a [`WriteGuarded`] write txn
+#[derive(Clone, Debug)]
+pub struct WriterInfo {
+    thread_id: ThreadId,
+    thread_name: Option<String>,
+    label: String,
+    acquired_at: Instant,
+}
+
+impl WriterInfo {
+    /// Id of the thread that acquired the write txn
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread_id
+    }
+
+    /// Name of the thread that acquired the write txn, if it is named
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    /// Label supplied when the write txn was acquired
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Time at which the write txn was acquired
+    pub fn acquired_at(&self) -> Instant {
+        self.acquired_at
+    }
+
+    /// Time elapsed since the write txn was acquired
+    pub fn held_for(&self) -> Duration {
+        self.acquired_at.elapsed()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    writer: Mutex<Option<WriterInfo>>,
+    released: Condvar,
+}
+
+impl State {
+    fn lock(&self) -> MutexGuard<'_, Option<WriterInfo>> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Releases the writer slot when dropped
+struct WriterSlot<'a> {
+    state: &'a State,
+}
+
+impl Drop for WriterSlot<'_> {
+    fn drop(&mut self) {
+        *self.state.lock() = None;
+        self.state.released.notify_one();
+    }
+}
+
+/// Env wrapper that serializes write txns through an internal lock, and
+/// records which thread holds the write txn, and why.
+///
+/// LMDB already allows only one write txn per env at a time, but a thread
+/// waiting on LMDB's lock cannot tell who it is waiting for, or give up
+/// after a timeout. Write txns created with [`Self::write_txn`] record the
+/// acquiring thread, a caller-supplied label, and the acquisition time,
+/// which are available from [`Self::current_writer`] while the txn is live.
+///
+/// Only write txns created through the same `WriteGuarded` (or its clones)
+/// are tracked. Write txns created directly with [`Env::write_txn`] bypass
+/// the lock, and are not reported.
+/// Requesting a second write txn on a thread that already holds one will
+/// block forever without a timeout, or fail with
+/// [`error::WriterBusy`] with a timeout.
+///
+/// ```
+/// # use sneed::{env::WriteGuarded, make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+/// use std::time::Duration;
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let guarded = WriteGuarded::new(env.clone());
+/// let mut rwtxn =
+///     guarded.write_txn("create db", Some(Duration::from_secs(1)))?;
+/// let db: DatabaseUnique<UnitKey, UnitKey> =
+///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+/// let () = db.put(&mut rwtxn, &(), &())?;
+/// assert_eq!(guarded.current_writer().unwrap().label(), "create db");
+/// let () = rwtxn.commit()?;
+/// assert!(guarded.current_writer().is_none());
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct WriteGuarded<'id> {
+    env: Env<'id>,
+    state: Arc<State>,
+}
+
+impl<'id> WriteGuarded<'id> {
+    pub fn new(env: Env<'id>) -> Self {
+        Self {
+            env,
+            state: Arc::default(),
+        }
+    }
+
+    pub fn env(&self) -> &Env<'id> {
+        &self.env
+    }
+
+    /// Information about the holder of the current write txn, if any
+    pub fn current_writer(&self) -> Option<WriterInfo> {
+        self.state.lock().clone()
+    }
+
+    /// Create a write txn, recording `label` as the reason for holding it.
+    /// If another write txn is held, waits for it to be released.
+    /// If `timeout` is set and the write txn is still held when it elapses,
+    /// returns [`error::WriterBusy`] with information about the holder.
+    ///
+    /// Write txns created directly with [`Env::write_txn`] bypass the
+    /// guard. They are not waited for here, so this may then block in LMDB
+    /// until they end, regardless of `timeout`.
+    pub fn write_txn(
+        &self,
+        label: &str,
+        timeout: Option<Duration>,
+    ) -> Result<GuardedRwTxn<'_, 'id>, error::WriteTxn> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut writer = self.state.lock();
+        while let Some(holder) = &*writer {
+            writer = match deadline {
+                None => self
+                    .state
+                    .released
+                    .wait(writer)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(error::WriterBusy {
+                            db_dir: (*self.env.path).to_owned(),
+                            holder_label: holder.label.clone(),
+                            held_for: holder.held_for(),
+                        }
+                        .into());
+                    }
+                    self.state
+                        .released
+                        .wait_timeout(writer, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+        let current_thread = thread::current();
+        *writer = Some(WriterInfo {
+            thread_id: current_thread.id(),
+            thread_name: current_thread.name().map(str::to_owned),
+            label: label.to_owned(),
+            acquired_at: Instant::now(),
+        });
+        drop(writer);
+        let slot = WriterSlot { state: &self.state };
+        let rwtxn = self.env.write_txn()?;
+        Ok(GuardedRwTxn { rwtxn, _slot: slot })
+    }
+}
+
+/// Write txn created by [`WriteGuarded::write_txn`].
+/// Dereferences to [`RwTxn`].
+/// The write txn is released when the txn is committed, aborted, or
+/// dropped.
+pub struct GuardedRwTxn<'a, 'id> {
+    rwtxn: RwTxn<'a, 'id>,
+    /// Dropped after the txn, so that the slot is only released once the
+    /// txn has ended
+    _slot: WriterSlot<'a>,
+}
+
+impl<'a, 'id> GuardedRwTxn<'a, 'id> {
+    pub fn commit(self) -> Result<(), rwtxn::error::Commit> {
+        let Self { rwtxn, _slot } = self;
+        rwtxn.commit()
+    }
+
+    pub fn abort(self) {
+        let Self { rwtxn, _slot } = self;
+        rwtxn.abort()
+    }
+}
+
+impl<'a, 'id> Deref for GuardedRwTxn<'a, 'id> {
+    type Target = RwTxn<'a, 'id>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rwtxn
+    }
+}
+
+impl<'a, 'id> DerefMut for GuardedRwTxn<'a, 'id> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rwtxn
+    }
+}
@@ -0,0 +1,244 @@
+//! Single-writer actor, which applies submitted writes in batched txns
+
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::oneshot;
+
+use crate::{rwtxn, Env, RwTxn};
+
+pub mod error {
+    use std::sync::Arc;
+
+    use thiserror::Error;
+
+    use crate::{env, rwtxn};
+
+    #[derive(Debug, Error)]
+    #[error("Writer stopped before the submitted write was applied")]
+    pub struct Stopped;
+
+    /// Error type for [`super::WriterHandle::submit`]
+    #[derive(Debug, Error)]
+    pub enum Submit<E> {
+        /// The submitted closure failed.
+        /// Writes made by the closure are discarded.
+        #[error("Submitted write failed")]
+        Closure(#[source] E),
+        /// Committing the shared txn failed.
+        /// Writes from every closure in the batch are discarded.
+        #[error(transparent)]
+        Commit(Arc<rwtxn::error::Commit>),
+        /// Creating or committing the nested txn for the closure failed
+        #[error(transparent)]
+        Nested(#[from] rwtxn::error::Nested),
+        #[error(transparent)]
+        Stopped(#[from] Stopped),
+        /// Creating the shared txn failed
+        #[error(transparent)]
+        WriteTxn(Arc<env::error::WriteTxn>),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use serde::{Serialize, Serializer};
+
+        use super::{Stopped, Submit};
+        use crate::serde_utils::serialize_error_struct;
+
+        serialize_error_struct!(Stopped {});
+
+        impl<E> Serialize for Submit<E>
+        where
+            E: Serialize,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                match self {
+                    Self::Closure(err) => err.serialize(serializer),
+                    Self::Commit(err) => (**err).serialize(serializer),
+                    Self::Nested(err) => err.serialize(serializer),
+                    Self::Stopped(err) => err.serialize(serializer),
+                    Self::WriteTxn(err) => (**err).serialize(serializer),
+                }
+            }
+        }
+    }
+}
+
+/// Resolves a submitter's result once the shared txn is committed
+type Completion<'id> =
+    Box<dyn FnOnce(Result<(), &Arc<rwtxn::error::Commit>>) + Send + 'id>;
+
+/// A submitted closure.
+/// Returns a completion if the closure succeeded.
+type Job<'id> = Box<
+    dyn for<'a, 'env> FnOnce(
+            Result<&'a mut RwTxn<'env, 'id>, &Arc<super::error::WriteTxn>>,
+        ) -> Option<Completion<'id>>
+        + Send
+        + 'id,
+>;
+
+/// Configuration for batching in a [`Writer`]
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    /// Maximum number of closures to apply in a single txn
+    pub max_batch_size: usize,
+    /// Maximum time to wait for more closures after receiving the first
+    /// closure in a batch
+    pub max_latency: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 256,
+            max_latency: Duration::from_millis(1),
+        }
+    }
+}
+
+/// Owns the write side of an env.
+/// Closures submitted via [`WriterHandle`]s are applied in batches,
+/// with each batch in a single write txn.
+/// Each closure runs in a nested txn, so that a failing closure does not
+/// affect other closures in the same batch.
+/// Writers cannot be used with envs that do not support nested txns.
+/// See [`Env::supports_nested_txns`].
+pub struct Writer<'id> {
+    env: Env<'id>,
+    config: Config,
+    jobs_rx: mpsc::Receiver<Job<'id>>,
+}
+
+impl<'id> Writer<'id> {
+    /// Create a writer, and a handle to submit writes to it.
+    /// The writer does nothing until [`Self::run`] is called.
+    pub fn new(env: Env<'id>, config: Config) -> (Self, WriterHandle<'id>) {
+        let (jobs_tx, jobs_rx) = mpsc::channel();
+        let writer = Self {
+            env,
+            config,
+            jobs_rx,
+        };
+        (writer, WriterHandle { jobs_tx })
+    }
+
+    /// Run the writer on a dedicated thread in `scope`.
+    /// The writer stops once every handle has been dropped.
+    pub fn spawn<'scope, 'env>(
+        scope: &'scope thread::Scope<'scope, 'env>,
+        env: Env<'id>,
+        config: Config,
+    ) -> WriterHandle<'id>
+    where
+        'id: 'scope,
+    {
+        let (writer, handle) = Self::new(env, config);
+        let _join_handle: thread::ScopedJoinHandle<'scope, ()> =
+            scope.spawn(|| writer.run());
+        handle
+    }
+
+    /// Receive the next batch of jobs.
+    /// Returns `None` if every handle has been dropped.
+    fn next_batch(&self) -> Option<Vec<Job<'id>>> {
+        let first = self.jobs_rx.recv().ok()?;
+        let deadline = Instant::now() + self.config.max_latency;
+        let mut batch = vec![first];
+        while batch.len() < self.config.max_batch_size {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.jobs_rx.recv_timeout(timeout) {
+                Ok(job) => batch.push(job),
+                Err(mpsc::RecvTimeoutError::Timeout)
+                | Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        Some(batch)
+    }
+
+    /// Apply batches of writes, blocking until every handle has been
+    /// dropped.
+    pub fn run(self) {
+        while let Some(batch) = self.next_batch() {
+            let mut rwtxn = match self.env.write_txn() {
+                Ok(rwtxn) => rwtxn,
+                Err(err) => {
+                    let err = Arc::new(err);
+                    batch.into_iter().for_each(|job| {
+                        let _completion: Option<Completion> = job(Err(&err));
+                    });
+                    continue;
+                }
+            };
+            let completions: Vec<_> = batch
+                .into_iter()
+                .filter_map(|job| job(Ok(&mut rwtxn)))
+                .collect();
+            let res = rwtxn.commit().map_err(Arc::new);
+            completions
+                .into_iter()
+                .for_each(|completion| completion(res.as_ref().map(|_| ())));
+        }
+    }
+}
+
+/// Handle for submitting writes to a [`Writer`]
+#[derive(Clone, Debug)]
+pub struct WriterHandle<'id> {
+    jobs_tx: mpsc::Sender<Job<'id>>,
+}
+
+impl<'id> WriterHandle<'id> {
+    /// Submit a closure to be applied by the writer.
+    /// Resolves once the txn containing the closure's writes has been
+    /// committed.
+    /// Databases used by the closure should be cloned into it.
+    pub async fn submit<F, T, E>(&self, f: F) -> Result<T, error::Submit<E>>
+    where
+        F: FnOnce(&mut RwTxn<'_, 'id>) -> Result<T, E> + Send + 'id,
+        T: Send + 'id,
+        E: Send + 'id,
+    {
+        let (res_tx, res_rx) = oneshot::channel();
+        let job: Job<'id> = Box::new(move |rwtxn| {
+            let rwtxn = match rwtxn {
+                Ok(rwtxn) => rwtxn,
+                Err(err) => {
+                    let _send: Result<(), _> =
+                        res_tx.send(Err(error::Submit::WriteTxn(err.clone())));
+                    return None;
+                }
+            };
+            let value = match rwtxn.nested(f) {
+                Ok(Ok(value)) => value,
+                Ok(Err(err)) => {
+                    let _send: Result<(), _> =
+                        res_tx.send(Err(error::Submit::Closure(err)));
+                    return None;
+                }
+                Err(err) => {
+                    let _send: Result<(), _> =
+                        res_tx.send(Err(error::Submit::Nested(err)));
+                    return None;
+                }
+            };
+            let completion: Completion<'id> = Box::new(move |commit_res| {
+                let res = match commit_res {
+                    Ok(()) => Ok(value),
+                    Err(err) => Err(error::Submit::Commit(err.clone())),
+                };
+                let _send: Result<(), _> = res_tx.send(res);
+            });
+            Some(completion)
+        });
+        self.jobs_tx.send(job).map_err(|_| error::Stopped)?;
+        res_rx.await.map_err(|_| error::Stopped)?
+    }
+}
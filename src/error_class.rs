@@ -0,0 +1,210 @@
+//! Coarse classification of errors
+
+use std::error::Error as StdError;
+
+use heed::MdbError;
+
+use crate::{db, env, rwtxn};
+
+/// Coarse class of an error, eg. for choosing an HTTP status code.
+/// See [`ErrorClass::of`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorClass {
+    /// A key or value that was required does not exist
+    NotFound,
+    /// The operation conflicts with existing data or with another handle,
+    /// eg. inserting a key that already exists
+    Conflict,
+    /// Stored data is corrupt or cannot be decoded
+    Corruption,
+    /// The env has run out of space, readers, or another limited resource
+    ResourceExhausted,
+    /// Any other error
+    Internal,
+}
+
+impl ErrorClass {
+    /// Classify a heed error
+    fn of_heed(err: &heed::Error) -> Self {
+        match err {
+            heed::Error::Mdb(MdbError::NotFound) => Self::NotFound,
+            heed::Error::Mdb(MdbError::KeyExist) => Self::Conflict,
+            heed::Error::Mdb(
+                MdbError::Corrupted
+                | MdbError::Incompatible
+                | MdbError::Invalid
+                | MdbError::PageNotFound
+                | MdbError::VersionMismatch,
+            )
+            | heed::Error::Decoding(_) => Self::Corruption,
+            heed::Error::Mdb(
+                MdbError::CursorFull
+                | MdbError::DbsFull
+                | MdbError::MapFull
+                | MdbError::PageFull
+                | MdbError::ReadersFull
+                | MdbError::TlsFull
+                | MdbError::TxnFull,
+            ) => Self::ResourceExhausted,
+            _ => Self::Internal,
+        }
+    }
+
+    /// Classify a single error, without inspecting its source.
+    /// Returns `None` if the class depends on the source.
+    fn of_single(err: &(dyn StdError + 'static)) -> Option<Self> {
+        if let Some(err) = err.downcast_ref::<heed::Error>() {
+            return Some(Self::of_heed(err));
+        }
+        if let Some(err) = err.downcast_ref::<db::error::Error>() {
+            return match err {
+                db::error::Error::Get(err) => Self::of_single(err),
+                db::error::Error::Inconsistent(_) => Some(Self::Corruption),
+                db::error::Error::Insert(err) => Self::of_single(err),
+                db::error::Error::KeyExists(_) => Some(Self::Conflict),
+                db::error::Error::PutWithFlags(err) => Self::of_single(err),
+                _ => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<db::error::Get>() {
+            return match err {
+                db::error::Get::MissingValue { .. } => Some(Self::NotFound),
+                db::error::Get::TryGet(_) => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<db::error::Insert>() {
+            return match err {
+                db::error::Insert::AlreadyExists { .. } => Some(Self::Conflict),
+                db::error::Insert::Put(_) => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<db::error::PutWithFlags>() {
+            return match err {
+                db::error::PutWithFlags::KeyExists(_) => Some(Self::Conflict),
+                db::error::PutWithFlags::InvalidFlags(_)
+                | db::error::PutWithFlags::Put(_) => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<db::error::PutSource>() {
+            return Some(match err {
+                db::error::PutSource::Heed(err) => Self::of_heed(err),
+                db::error::PutSource::ValueTooLarge(_) => {
+                    Self::ResourceExhausted
+                }
+            });
+        }
+        // Transparent variants forward `source` past the wrapped error, so
+        // the wrapped error must be classified directly
+        if let Some(err) = err.downcast_ref::<env::error::Error>() {
+            return match err {
+                env::error::Error::AlreadyOpen(err) => Self::of_single(err),
+                env::error::Error::StillReferenced(err) => Self::of_single(err),
+                _ => None,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<env::error::Open>() {
+            return match err {
+                env::error::Open::AlreadyOpen(err) => Self::of_single(err),
+                env::error::Open::OpenEnv(_) => None,
+            };
+        }
+        if let Some(env::error::OpenCreateDir::Open(err)) =
+            err.downcast_ref::<env::error::OpenCreateDir>()
+        {
+            return Self::of_single(err);
+        }
+        #[cfg(feature = "writer-lock")]
+        if let Some(env::error::OpenWithWriterLock::Open(err)) =
+            err.downcast_ref::<env::error::OpenWithWriterLock>()
+        {
+            return Self::of_single(err);
+        }
+        if let Some(env::error::Close::StillReferenced(err)) =
+            err.downcast_ref::<env::error::Close>()
+        {
+            return Self::of_single(err);
+        }
+        if let Some(rwtxn::error::CommitSource::Heed(err)) =
+            err.downcast_ref::<rwtxn::error::CommitSource>()
+        {
+            return Some(Self::of_heed(err));
+        }
+        if err.is::<db::error::KeyExists>()
+            || err.is::<env::error::AlreadyOpen>()
+            || err.is::<env::error::StillReferenced>()
+        {
+            return Some(Self::Conflict);
+        }
+        if err.is::<db::error::Inconsistent>() {
+            return Some(Self::Corruption);
+        }
+        if err.is::<db::error::ValueTooLarge>() {
+            return Some(Self::ResourceExhausted);
+        }
+        None
+    }
+
+    /// Classify an error, by the first error in its source chain that has
+    /// a class.
+    /// Errors that do not have a class, and whose sources do not have a
+    /// class, are [`Self::Internal`].
+    ///
+    /// ```
+    /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+    /// use sneed::ErrorClass;
+    ///
+    /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    /// # make_guard!(guard);
+    /// # let mut opts = EnvOpenOptions::new();
+    /// # let _ = opts.max_dbs(4);
+    /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+    /// let mut rwtxn = env.write_txn()?;
+    /// let db: DatabaseUnique<UnitKey, UnitKey> =
+    ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    /// let err = db.get(&rwtxn, &()).unwrap_err();
+    /// assert_eq!(ErrorClass::of(&err), ErrorClass::NotFound);
+    /// let () = db.insert(&mut rwtxn, &(), &())?;
+    /// let err = db.insert(&mut rwtxn, &(), &()).unwrap_err();
+    /// assert_eq!(ErrorClass::of(&err), ErrorClass::Conflict);
+    /// let err = sneed::db::error::Error::from(err);
+    /// assert_eq!(err.status_hint(), ErrorClass::Conflict);
+    /// # Ok(())
+    /// # }
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # example(dir.path()).unwrap();
+    /// ```
+    pub fn of(err: &(dyn StdError + 'static)) -> Self {
+        let mut next = Some(err);
+        while let Some(err) = next {
+            if let Some(class) = Self::of_single(err) {
+                return class;
+            }
+            next = err.source();
+        }
+        Self::Internal
+    }
+}
+
+impl db::error::Error {
+    /// Coarse class of the error.
+    /// See [`ErrorClass::of`].
+    pub fn status_hint(&self) -> ErrorClass {
+        ErrorClass::of(self)
+    }
+}
+
+impl env::error::Error {
+    /// Coarse class of the error.
+    /// See [`ErrorClass::of`].
+    pub fn status_hint(&self) -> ErrorClass {
+        ErrorClass::of(self)
+    }
+}
+
+impl rwtxn::error::Error {
+    /// Coarse class of the error.
+    /// See [`ErrorClass::of`].
+    pub fn status_hint(&self) -> ErrorClass {
+        ErrorClass::of(self)
+    }
+}
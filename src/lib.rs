@@ -1,11 +1,200 @@
 //! Database utils
+//!
+//! Each [`Env`] is branded with a unique lifetime, created with
+//! [`make_guard!`]. Transactions and databases carry the brand of the env
+//! that they were created from, so there is no need to declare tag types in
+//! order to distinguish environments.
+//!
+//! ```
+//! # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+//! # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+//! make_guard!(guard);
+//! let mut opts = EnvOpenOptions::new();
+//! let _ = opts.max_dbs(4);
+//! let env = unsafe { Env::open(guard, &opts, path) }?;
+//! let mut rwtxn = env.write_txn()?;
+//! let db: DatabaseUnique<UnitKey, UnitKey> =
+//!     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+//! let () = db.put(&mut rwtxn, &(), &())?;
+//! let () = rwtxn.commit()?;
+//! let rotxn = env.read_txn()?;
+//! assert_eq!(db.try_get(&rotxn, &())?, Some(()));
+//! # Ok(())
+//! # }
+//! # let dir = tempfile::tempdir().unwrap();
+//! # example(dir.path()).unwrap();
+//! ```
+//!
+//! Using a transaction from one env with a database from another env is a
+//! compile error, for both reads and writes.
+//!
+//! ```compile_fail
+//! # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+//! # fn example(
+//! #     path0: &std::path::Path,
+//! #     path1: &std::path::Path,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! make_guard!(guard0);
+//! make_guard!(guard1);
+//! let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), path0) }?;
+//! let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), path1) }?;
+//! let mut rwtxn0 = env0.write_txn()?;
+//! let db0: DatabaseUnique<UnitKey, UnitKey> =
+//!     DatabaseUnique::create(&env0, &mut rwtxn0, "db")?;
+//! let mut rwtxn1 = env1.write_txn()?;
+//! let () = db0.put(&mut rwtxn1, &(), &())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+//! # fn example(
+//! #     path0: &std::path::Path,
+//! #     path1: &std::path::Path,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! make_guard!(guard0);
+//! make_guard!(guard1);
+//! let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), path0) }?;
+//! let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), path1) }?;
+//! let mut rwtxn0 = env0.write_txn()?;
+//! let db0: DatabaseUnique<UnitKey, UnitKey> =
+//!     DatabaseUnique::create(&env0, &mut rwtxn0, "db")?;
+//! let rotxn1 = env1.read_txn()?;
+//! let _: Option<()> = db0.try_get(&rotxn1, &())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A database can only be created with a write txn from the same env.
+//!
+//! ```compile_fail
+//! # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+//! # fn example(
+//! #     path0: &std::path::Path,
+//! #     path1: &std::path::Path,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! make_guard!(guard0);
+//! make_guard!(guard1);
+//! let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), path0) }?;
+//! let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), path1) }?;
+//! let mut rwtxn1 = env1.write_txn()?;
+//! let _db0: DatabaseUnique<UnitKey, UnitKey> =
+//!     DatabaseUnique::create(&env0, &mut rwtxn1, "db")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The brands of two envs are always distinct.
+//!
+//! ```compile_fail
+//! # use sneed::{make_guard, Env, EnvOpenOptions};
+//! # fn example(
+//! #     path0: &std::path::Path,
+//! #     path1: &std::path::Path,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! fn same_brand<'id>(_: &Env<'id>, _: &Env<'id>) {}
+//!
+//! make_guard!(guard0);
+//! make_guard!(guard1);
+//! let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), path0) }?;
+//! let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), path1) }?;
+//! same_brand(&env0, &env1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Since brands are anonymous lifetimes rather than types, there is no tag
+//! type to name, and no `Tag` trait or derive for naming envs. Errors
+//! identify the env by its path instead. Env and txn errors include the env
+//! directory, and db errors include the db name and env directory.
+//!
+//! # Heed types
+//!
+//! Codecs, flags, comparators, and errors are re-exported in [`types`],
+//! [`flags`], [`comparator`], and [`heed_error`], so that crates using this
+//! crate do not need to depend on heed directly. Only the `raw` feature
+//! exposes heed types that are not re-exported.
+//! The `tests/no-heed` crate checks this, by using this crate without a
+//! heed dependency.
+//!
+//! ```
+//! // No heed types are in scope
+//! mod heed {}
+//!
+//! use sneed::{
+//!     comparator::{DefaultComparator, Reverse},
+//!     flags::EnvFlags,
+//!     make_guard,
+//!     types::{BigEndian, Str, U64},
+//!     DatabaseUnique, Env, EnvOpenOptions,
+//! };
+//!
+//! # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+//! make_guard!(guard);
+//! let mut opts = EnvOpenOptions::new();
+//! let _ = unsafe { opts.max_dbs(1).flags(EnvFlags::NO_TLS) };
+//! let env = unsafe { Env::open(guard, &opts, path) }?;
+//! let mut rwtxn = env.write_txn()?;
+//! let db: DatabaseUnique<U64<BigEndian>, Str, Reverse<DefaultComparator>> =
+//!     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+//! let () = db.put(&mut rwtxn, &0, "zero")?;
+//! let () = rwtxn.commit()?;
+//! # Ok(())
+//! # }
+//! # let dir = tempfile::tempdir().unwrap();
+//! # example(dir.path()).unwrap();
+//! ```
+//!
+//! # Raw handles
+//!
+//! With the `raw` feature, the underlying heed env, db, and txn handles
+//! are accessible, for heed features that are not wrapped by this crate.
+//! Using raw handles bypasses the guarantees of this crate, and the caller
+//! is responsible for:
+//! * using raw db handles only with txns from the env that the db was
+//!   created in, since raw handles are not branded
+//! * notifying watchers, since writes made with raw handles do not send
+//!   notifications to watchers of the db when the txn is committed
+//! * adding context to errors, since heed errors do not include the db
+//!   name or env directory
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 use thiserror::Error;
 
 pub use generativity::make_guard;
-pub use heed::{BytesDecode, BytesEncode, EnvOpenOptions};
+pub use heed::{BytesDecode, BytesEncode, EnvFlags, EnvOpenOptions, PutFlags};
+
+/// Codecs for use with databases
+pub mod codec {
+    /// Raw bytes, without encoding or decoding
+    pub use heed::types::Bytes as Raw;
+}
+
+/// Codecs for keys and values, including the codecs provided by heed.
+/// `SerdeBincode` and `SerdeJson` require the `serde-bincode` and
+/// `serde-json` features.
+pub mod types {
+    pub use heed::{
+        byteorder::{self, BigEndian, LittleEndian, NativeEndian},
+        types::*,
+        BoxedError, BytesDecode, BytesEncode,
+    };
+
+    pub use crate::{codec::Raw, UnitKey};
+}
+
+/// Flags for envs, dbs, and writes
+pub mod flags {
+    pub use heed::{DatabaseFlags, EnvFlags, PutFlags};
+}
+
+/// Errors returned by heed, which are the sources of many errors in this
+/// crate, eg. [`db::error::PutSource::Heed`]
+pub mod heed_error {
+    pub use heed::{Error, MdbError};
+}
 
 const UNIT_KEY_ENCODED: u8 = 0x69;
 
@@ -58,11 +247,37 @@ impl BytesEncode<'_> for UnitKey {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_utils;
+
 mod txn;
-pub use txn::{rotxn, rwtxn, RoTxn, RwTxn, Txn};
+#[cfg(feature = "stream")]
+pub use txn::StaticRoTxn;
+pub use txn::{rotxn, rwtxn, AnyTxn, ResetRoTxn, RoTxn, RwTxn, Txn};
 
 pub mod env;
 pub use env::Env;
 
+pub mod comparator;
+
 pub mod db;
-pub use db::{DatabaseDup, DatabaseUnique, RoDatabaseDup, RoDatabaseUnique};
+pub use db::{
+    DatabaseDup, DatabaseUnique, RawDatabaseDup, RawDatabaseUnique,
+    RoDatabaseDup, RoDatabaseUnique,
+};
+
+pub mod diff;
+
+mod error_class;
+pub use error_class::ErrorClass;
+
+mod schema;
+
+pub mod dump;
+
+#[cfg(feature = "test-utils")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-utils")))]
+pub mod test_utils;
+
+pub mod tools;
+pub mod usage;
@@ -0,0 +1,140 @@
+//! Macros for declaring a set of databases
+
+/// Declare a struct with a field for each db in a schema.
+///
+/// Each field is declared with the db type, without the env lifetime, and
+/// the name of the db. The db type must be [`DatabaseUnique`] or
+/// [`DatabaseDup`].
+/// The generated struct has an `open_or_create` fn that creates or opens
+/// every db in a single write txn, and an accessor for each db.
+///
+/// ```
+/// # use sneed::{databases, make_guard, Env, EnvOpenOptions};
+/// use sneed::types::{BigEndian, Str, U64};
+///
+/// databases! {
+///     pub struct Schema {
+///         users: DatabaseUnique<U64<BigEndian>, Str> = "users",
+///         events: DatabaseDup<U64<BigEndian>, Str> = "events",
+///     }
+/// }
+///
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// make_guard!(guard);
+/// let mut opts = EnvOpenOptions::new();
+/// let _ = opts.max_dbs(4);
+/// let env = unsafe { Env::open(guard, &opts, path) }?;
+/// let schema = Schema::open_or_create(&env)?;
+/// let mut rwtxn = env.write_txn()?;
+/// let () = schema.users().put(&mut rwtxn, &0, "alice")?;
+/// let () = schema.events().put(&mut rwtxn, &0, "signup")?;
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+///
+/// Other db types are a compile error.
+///
+/// ```compile_fail
+/// # use sneed::databases;
+/// use sneed::types::Str;
+///
+/// databases! {
+///     pub struct Schema {
+///         users: RoDatabaseUnique<Str, Str> = "users",
+///     }
+/// }
+/// ```
+///
+/// [`DatabaseUnique`]: crate::DatabaseUnique
+/// [`DatabaseDup`]: crate::DatabaseDup
+#[macro_export]
+macro_rules! databases {
+    (
+        $(#[$struct_attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident : $kind:ident < $($arg:ty),+ $(,)? > = $db_name:expr
+            ),* $(,)?
+        }
+    ) => {
+        $crate::__databases! {
+            @check [$($kind)*]
+            $(#[$struct_attr])*
+            $vis struct $name {
+                $(
+                    $(#[$field_attr])*
+                    $field: $kind<$($arg),+> = $db_name
+                ),*
+            }
+        }
+    };
+}
+
+/// Implementation of [`databases!`].
+/// Every db type is checked before anything is generated, so that an
+/// unsupported db type is reported once, without follow-on errors.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __databases {
+    (@check [DatabaseUnique $($kinds:ident)*] $($input:tt)*) => {
+        $crate::__databases! { @check [$($kinds)*] $($input)* }
+    };
+    (@check [DatabaseDup $($kinds:ident)*] $($input:tt)*) => {
+        $crate::__databases! { @check [$($kinds)*] $($input)* }
+    };
+    (@check [$kind:ident $($kinds:ident)*] $($input:tt)*) => {
+        ::core::compile_error!(::core::concat!(
+            "unsupported db type `",
+            ::core::stringify!($kind),
+            "`, expected `DatabaseUnique` or `DatabaseDup`",
+        ));
+    };
+    (
+        @check []
+        $(#[$struct_attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident : $kind:ident < $($arg:ty),+ > = $db_name:expr
+            ),*
+        }
+    ) => {
+        $(#[$struct_attr])*
+        $vis struct $name<'env_id> {
+            $(
+                $(#[$field_attr])*
+                $field: $crate::$kind<'env_id, $($arg),+>,
+            )*
+        }
+
+        impl<'env_id> $name<'env_id> {
+            /// Create each db if it does not exist, and open it if it does,
+            /// in a single write txn
+            $vis fn open_or_create(
+                env: &$crate::Env<'env_id>,
+            ) -> ::core::result::Result<Self, $crate::env::Error> {
+                let mut rwtxn = env.write_txn()?;
+                let res = Self {
+                    $(
+                        $field: $crate::$kind::create(
+                            env,
+                            &mut rwtxn,
+                            $db_name,
+                        )?,
+                    )*
+                };
+                let () = rwtxn.commit()?;
+                Ok(res)
+            }
+
+            $(
+                $vis fn $field(&self) -> &$crate::$kind<'env_id, $($arg),+> {
+                    &self.$field
+                }
+            )*
+        }
+    };
+}
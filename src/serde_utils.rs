@@ -0,0 +1,129 @@
+//! Helpers for serializing error types
+
+use std::{fmt::Display, path::Path};
+
+use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+/// Serializes bytes as a hex string
+pub(crate) struct Hex<'a>(pub &'a [u8]);
+
+impl Serialize for Hex<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex::encode(self.0))
+    }
+}
+
+/// Serializes encoded bytes as a hex string,
+/// or the encoding error as a struct with a single `encode_error` field
+pub(crate) struct EncodeResult<'a>(
+    pub &'a Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>,
+);
+
+impl Serialize for EncodeResult<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Ok(bytes) => Hex(bytes).serialize(serializer),
+            Err(err) => {
+                let mut state =
+                    serializer.serialize_struct("EncodeError", 1)?;
+                let () = state
+                    .serialize_field("encode_error", &DisplayStr(&**err))?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// Serializes a value by its `Display` impl
+pub(crate) struct DisplayStr<'a, T: ?Sized>(pub &'a T);
+
+impl<T> Serialize for DisplayStr<'_, T>
+where
+    T: Display + ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self.0)
+    }
+}
+
+/// Serializes a path as a string, replacing non-unicode data
+pub(crate) struct PathStr<'a>(pub &'a Path);
+
+impl Serialize for PathStr<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&self.0.display())
+    }
+}
+
+/// Serializes a value as-is
+pub(crate) struct Plain<'a, T: ?Sized>(pub &'a T);
+
+impl<T> Serialize for Plain<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Implement `Serialize` for an error struct, as a struct with a `kind`
+/// field set to the name of the error type, followed by each of the
+/// specified fields, serialized with the specified wrappers.
+macro_rules! serialize_error_struct {
+    ($ty:ident { $($field:ident: $wrapper:ident),* $(,)? }) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                use serde::ser::SerializeStruct as _;
+                const FIELDS: &[&str] = &[$(stringify!($field)),*];
+                let mut state = serializer
+                    .serialize_struct(stringify!($ty), 1 + FIELDS.len())?;
+                let () = state.serialize_field("kind", stringify!($ty))?;
+                $(
+                    let () = state.serialize_field(
+                        stringify!($field),
+                        &$crate::serde_utils::$wrapper(&self.$field),
+                    )?;
+                )*
+                state.end()
+            }
+        }
+    };
+}
+pub(crate) use serialize_error_struct;
+
+/// Implement `Serialize` for an error enum in which each variant wraps
+/// an error, by serializing the wrapped error.
+macro_rules! serialize_error_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                match self {
+                    $(Self::$variant(err) => err.serialize(serializer),)*
+                }
+            }
+        }
+    };
+}
+pub(crate) use serialize_error_enum;
@@ -0,0 +1,89 @@
+//! Utilities for testing with temporary environments
+
+use std::path::Path;
+
+use crate::{DatabaseUnique, Env, EnvOpenOptions};
+
+/// Map size used for test environments, in bytes
+const MAP_SIZE: usize = 10 * 1024 * 1024;
+
+/// Max number of named databases in test environments
+const MAX_DBS: u32 = 32;
+
+pub mod error {
+    use thiserror::Error;
+
+    use crate::{env, rwtxn};
+
+    #[derive(Debug, Error)]
+    #[error("Error creating temp dir for test env")]
+    pub struct TempDir {
+        pub(crate) source: std::io::Error,
+    }
+
+    /// General error type for test env operations
+    #[derive(Debug, Error)]
+    pub enum Error {
+        #[error(transparent)]
+        Commit(#[from] rwtxn::error::Commit),
+        #[error(transparent)]
+        CreateDb(#[from] env::error::CreateDb),
+        #[error(transparent)]
+        Open(#[from] env::error::Open),
+        #[error(transparent)]
+        TempDir(#[from] TempDir),
+        #[error(transparent)]
+        WriteTxn(#[from] env::error::WriteTxn),
+    }
+}
+pub use error::Error;
+
+/// An [`Env`] in a temporary directory.
+/// The directory is deleted when the `TestEnv` is dropped.
+#[derive(Debug)]
+pub struct TestEnv<'id> {
+    // Must be dropped before `dir`
+    env: Env<'id>,
+    dir: tempfile::TempDir,
+}
+
+impl<'id> TestEnv<'id> {
+    /// Open an env in a new temporary directory, with a 10MB map size and
+    /// up to 32 named databases.
+    pub fn new(unique_guard: generativity::Guard<'id>) -> Result<Self, Error> {
+        let dir = tempfile::tempdir()
+            .map_err(|err| error::TempDir { source: err })?;
+        let mut opts = EnvOpenOptions::new();
+        opts.map_size(MAP_SIZE).max_dbs(MAX_DBS);
+        // Safety: the directory is new, and is not opened elsewhere
+        let env = unsafe { Env::open(unique_guard, &opts, dir.path()) }?;
+        Ok(Self { env, dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Create a database in a new write txn, and commit it.
+    pub fn create_db<KC, DC>(
+        &self,
+        name: &str,
+    ) -> Result<DatabaseUnique<'id, KC, DC>, Error>
+    where
+        KC: 'static,
+        DC: 'static,
+    {
+        let mut rwtxn = self.env.write_txn()?;
+        let db = DatabaseUnique::create(&self.env, &mut rwtxn, name)?;
+        let () = rwtxn.commit()?;
+        Ok(db)
+    }
+}
+
+impl<'id> std::ops::Deref for TestEnv<'id> {
+    type Target = Env<'id>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.env
+    }
+}
@@ -0,0 +1,280 @@
+//! Utilities built on top of the database wrappers
+
+use std::ops::{Bound, RangeBounds};
+
+use fallible_iterator::FallibleIterator as _;
+use heed::{types::Bytes, BytesEncode, Comparator};
+
+use crate::{
+    db::{self, DatabaseUnique, RoDatabaseUnique},
+    Env, RoTxn, RwTxn,
+};
+
+pub mod counter;
+pub mod queue;
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::{db, env};
+
+    #[derive(Debug, Error)]
+    #[error("Database `{name}` does not exist in env at `{path}`")]
+    pub struct DbNotFound {
+        pub(crate) name: String,
+        pub(crate) path: PathBuf,
+    }
+
+    /// Error type for [`super::export_db`]
+    #[derive(Debug, Error)]
+    pub enum ExportDb {
+        #[error(transparent)]
+        CreateDb(#[from] env::error::CreateDb),
+        #[error(transparent)]
+        DbNotFound(#[from] DbNotFound),
+        #[error(transparent)]
+        IterInit(#[from] db::error::IterInit),
+        #[error(transparent)]
+        IterItem(#[from] db::error::IterItem),
+        #[error(transparent)]
+        OpenDb(#[from] env::error::OpenDb),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+    }
+
+    /// Error type for [`super::rename_db`]
+    #[derive(Debug, Error)]
+    pub enum RenameDb {
+        #[error(transparent)]
+        Clear(#[from] db::error::Clear),
+        #[error(transparent)]
+        CreateDb(#[from] env::error::CreateDb),
+        #[error(transparent)]
+        DbNotFound(#[from] DbNotFound),
+        #[error(transparent)]
+        IterItem(#[from] db::error::IterItem),
+        #[error(transparent)]
+        Len(#[from] db::error::Len),
+        #[error(transparent)]
+        OpenDb(#[from] env::error::OpenDb),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+        #[error(transparent)]
+        RangeInit(#[from] db::error::RangeInit),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{DbNotFound, ExportDb, RenameDb};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(DbNotFound {
+            name: Plain,
+            path: PathStr,
+        });
+
+        serialize_error_enum!(ExportDb {
+            CreateDb,
+            DbNotFound,
+            IterInit,
+            IterItem,
+            OpenDb,
+            Put
+        });
+
+        serialize_error_enum!(RenameDb {
+            Clear,
+            CreateDb,
+            DbNotFound,
+            IterItem,
+            Len,
+            OpenDb,
+            Put,
+            RangeInit
+        });
+    }
+}
+
+pub use counter::{Counter, Counters};
+pub use queue::Queue;
+
+/// Copy each item with a key in the specified range from `src` to `dst`,
+/// returning the number of items copied.
+/// Keys and values are copied as raw bytes, and are not decoded.
+/// Existing items in `dst` are overwritten.
+#[allow(clippy::result_large_err)]
+pub fn copy_db_range<'a, 'src_id, 'dst_id, KC, DC, C, R>(
+    src: &'a RoDatabaseUnique<'src_id, KC, DC, C>,
+    src_rotxn: &'a RoTxn<'_, 'src_id>,
+    dst: &DatabaseUnique<'dst_id, KC, DC, C>,
+    dst_rwtxn: &mut RwTxn<'_, 'dst_id>,
+    range: &'a R,
+) -> Result<u64, db::error::Error>
+where
+    KC: BytesEncode<'a>,
+    R: RangeBounds<KC::EItem>,
+    C: Comparator,
+{
+    let mut items = src.range_bytes(src_rotxn, range)?;
+    let mut copied = 0;
+    while let Some((key_bytes, value_bytes)) = items.next()? {
+        let () = dst.put_bytes(dst_rwtxn, key_bytes, value_bytes)?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Number of items held in memory at a time by [`rename_db`]
+const RENAME_DB_CHUNK_SIZE: usize = 1024;
+
+/// Move each item in the db named `old` to the db named `new`, returning the
+/// number of items moved.
+/// Keys and values are copied as raw bytes, and are not decoded.
+/// Items are copied in chunks, so the whole db is never held in memory.
+/// Returns an [`error::DbNotFound`] error if `old` does not exist.
+/// `new` is created if it does not exist, and existing items in it are
+/// overwritten.
+/// heed cannot delete dbs, so the `old` db is left empty, and still counts
+/// towards the env's maximum number of dbs. Existing handles to it remain
+/// valid.
+/// If `old` and `new` are the same, nothing is moved, and the number of
+/// items in the db is returned.
+pub fn rename_db<'env_id, KC, DC, C>(
+    env: &Env<'env_id>,
+    rwtxn: &mut RwTxn<'_, 'env_id>,
+    old: &str,
+    new: &str,
+) -> Result<u64, error::RenameDb>
+where
+    KC: 'static,
+    DC: 'static,
+    C: Comparator + 'static,
+{
+    let Some(old_db) = DatabaseUnique::<KC, DC, C>::open(env, rwtxn, old)?
+    else {
+        return Err(error::DbNotFound {
+            name: old.to_owned(),
+            path: env.path().to_path_buf(),
+        }
+        .into());
+    };
+    if old == new {
+        return Ok(old_db.len(rwtxn)?);
+    }
+    let new_db = DatabaseUnique::<KC, DC, C>::create(env, rwtxn, new)?;
+    let old_bytes = old_db.remap_types::<Bytes, Bytes>();
+    let mut chunk = Vec::with_capacity(RENAME_DB_CHUNK_SIZE);
+    let mut last_key: Option<Vec<u8>> = None;
+    let mut moved = 0;
+    loop {
+        {
+            let start = match &last_key {
+                Some(last_key) => Bound::Excluded(last_key.as_slice()),
+                None => Bound::Unbounded,
+            };
+            let range = (start, Bound::Unbounded);
+            let mut items = old_bytes.range_bytes(rwtxn, &range)?;
+            while chunk.len() < RENAME_DB_CHUNK_SIZE {
+                let Some((key_bytes, value_bytes)) = items.next()? else {
+                    break;
+                };
+                chunk.push((key_bytes.to_vec(), value_bytes.to_vec()));
+            }
+        }
+        let Some((chunk_last_key, _)) = chunk.last() else {
+            break;
+        };
+        last_key = Some(chunk_last_key.clone());
+        for (key_bytes, value_bytes) in chunk.drain(..) {
+            let () = new_db.put_bytes(rwtxn, &key_bytes, &value_bytes)?;
+            moved += 1;
+        }
+    }
+    let () = old_db.clear(rwtxn)?;
+    Ok(moved)
+}
+
+/// Copy each item in the db named `src_name` in `src_env` to the db named
+/// `dst_name` in `dst_env`, returning the number of items copied.
+/// Keys and values are copied as raw bytes, and are not decoded.
+/// The destination db is created if it does not exist, and existing items
+/// in it are overwritten.
+/// Returns an [`error::DbNotFound`] error if the source db does not exist.
+///
+/// ```
+/// # use sneed::{make_guard, tools, DatabaseUnique, Env, EnvOpenOptions};
+/// use sneed::{
+///     comparator::DefaultComparator,
+///     types::{BigEndian, Str, U32},
+/// };
+///
+/// # fn example(
+/// #     src_path: &std::path::Path,
+/// #     dst_path: &std::path::Path,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(src_guard);
+/// # make_guard!(dst_guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let src_env = unsafe { Env::open(src_guard, &opts, src_path) }?;
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let dst_env = unsafe { Env::open(dst_guard, &opts, dst_path) }?;
+/// let mut src_rwtxn = src_env.write_txn()?;
+/// let src_db: DatabaseUnique<U32<BigEndian>, Str> =
+///     DatabaseUnique::create(&src_env, &mut src_rwtxn, "users")?;
+/// let () = src_db.put(&mut src_rwtxn, &1, "alice")?;
+/// let () = src_rwtxn.commit()?;
+/// let src_rotxn = src_env.read_txn()?;
+/// let mut dst_rwtxn = dst_env.write_txn()?;
+/// let copied = tools::export_db::<U32<BigEndian>, Str, DefaultComparator>(
+///     &src_env,
+///     &src_rotxn,
+///     "users",
+///     &dst_env,
+///     &mut dst_rwtxn,
+///     "users",
+/// )?;
+/// assert_eq!(copied, 1);
+/// let () = dst_rwtxn.commit()?;
+/// # Ok(())
+/// # }
+/// # let (dir0, dir1) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+/// # example(dir0.path(), dir1.path()).unwrap();
+/// ```
+pub fn export_db<'src_id, 'dst_id, KC, DC, C>(
+    src_env: &Env<'src_id>,
+    src_rotxn: &RoTxn<'_, 'src_id>,
+    src_name: &str,
+    dst_env: &Env<'dst_id>,
+    dst_rwtxn: &mut RwTxn<'_, 'dst_id>,
+    dst_name: &str,
+) -> Result<u64, error::ExportDb>
+where
+    KC: 'static,
+    DC: 'static,
+    C: Comparator + 'static,
+{
+    let Some(src_db) =
+        RoDatabaseUnique::<KC, DC, C>::open(src_env, src_rotxn, src_name)?
+    else {
+        return Err(error::DbNotFound {
+            name: src_name.to_owned(),
+            path: src_env.path().to_path_buf(),
+        }
+        .into());
+    };
+    let dst_db =
+        DatabaseUnique::<KC, DC, C>::create(dst_env, dst_rwtxn, dst_name)?;
+    let mut items = src_db.iter_bytes(src_rotxn)?;
+    let mut copied = 0;
+    while let Some((key_bytes, value_bytes)) = items.next()? {
+        let () = dst_db.put_bytes(dst_rwtxn, key_bytes, value_bytes)?;
+        copied += 1;
+    }
+    Ok(copied)
+}
@@ -0,0 +1,161 @@
+//! Monotonic counters, eg. for allocating ids
+
+use std::ops::Range;
+
+use heed::{byteorder::BigEndian, types::U64, BytesEncode, DefaultComparator};
+
+use crate::{db, DatabaseUnique, RwTxn, Txn, UnitKey};
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::db;
+
+    #[derive(Debug, Error)]
+    #[error(
+        "Counter overflow in db `{db_name}` at `{db_path}` (key: `{}`): cannot increment {value} by {delta}",
+        hex::encode(.key_bytes)
+    )]
+    pub struct Overflow {
+        pub(crate) db_name: String,
+        pub(crate) db_path: PathBuf,
+        pub(crate) key_bytes: Vec<u8>,
+        pub(crate) value: u64,
+        pub(crate) delta: u64,
+    }
+
+    /// Error type for incrementing counters
+    #[derive(Debug, Error)]
+    pub enum Increment {
+        #[error(transparent)]
+        Overflow(#[from] Overflow),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+        #[error(transparent)]
+        TryGet(#[from] db::error::TryGet),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{Increment, Overflow};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(Overflow {
+            db_name: Plain,
+            db_path: PathStr,
+            key_bytes: Hex,
+            value: Plain,
+            delta: Plain,
+        });
+
+        serialize_error_enum!(Increment {
+            Overflow,
+            Put,
+            TryGet
+        });
+    }
+}
+
+/// Counters for each key in a db.
+/// Counters that have not been written are zero.
+pub struct Counters<'env_id, KC, C = DefaultComparator> {
+    db: DatabaseUnique<'env_id, KC, U64<BigEndian>, C>,
+}
+
+impl<'env_id, KC, C> Counters<'env_id, KC, C> {
+    pub fn new(db: DatabaseUnique<'env_id, KC, U64<BigEndian>, C>) -> Self {
+        Self { db }
+    }
+
+    /// Get the value of the counter for a key
+    pub fn get<'a, 'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+        key: &'a KC::EItem,
+    ) -> Result<u64, db::error::TryGet>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        KC: BytesEncode<'a>,
+    {
+        self.db.get_or_default(txn, key)
+    }
+
+    /// Add `delta` to the counter for a key, returning the new value.
+    /// Returns an [`error::Overflow`] error if the new value would exceed
+    /// [`u64::MAX`].
+    pub fn increment<K>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        key: &K,
+        delta: u64,
+    ) -> Result<u64, error::Increment>
+    where
+        K: ?Sized,
+        KC: for<'a> BytesEncode<'a, EItem = K>,
+    {
+        let value = self.db.get_or_default(rwtxn, key)?;
+        let Some(new_value) = value.checked_add(delta) else {
+            let key_bytes = KC::bytes_encode(key)
+                .expect("Key should encode, as try_get succeeded")
+                .into_owned();
+            return Err(error::Overflow {
+                db_name: self.db.name().to_owned(),
+                db_path: self.db.path().to_owned(),
+                key_bytes,
+                value,
+                delta,
+            }
+            .into());
+        };
+        let () = self.db.put(rwtxn, key, &new_value)?;
+        Ok(new_value)
+    }
+}
+
+/// A single counter, eg. for allocating ids.
+/// Allocation happens in the caller's txn, so that it is atomic with writes
+/// that use the allocated ids.
+pub struct Counter<'env_id> {
+    inner: Counters<'env_id, UnitKey>,
+}
+
+impl<'env_id> Counter<'env_id> {
+    pub fn new(db: DatabaseUnique<'env_id, UnitKey, U64<BigEndian>>) -> Self {
+        Self {
+            inner: Counters::new(db),
+        }
+    }
+
+    /// The value that will be returned by the next call to [`Self::next`]
+    pub fn peek<'env, Tx>(&self, txn: &Tx) -> Result<u64, db::error::TryGet>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.inner.get(txn, &())
+    }
+
+    /// Allocate the next value.
+    /// The first value allocated is zero.
+    pub fn next(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<u64, error::Increment> {
+        let block = self.allocate_block(rwtxn, 1)?;
+        Ok(block.start)
+    }
+
+    /// Allocate `n` consecutive values
+    pub fn allocate_block(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        n: u64,
+    ) -> Result<Range<u64>, error::Increment> {
+        let end = self.inner.increment(rwtxn, &(), n)?;
+        Ok((end - n)..end)
+    }
+}
@@ -0,0 +1,147 @@
+//! Persistent FIFO queues
+
+use heed::{byteorder::BigEndian, types::U64, BytesDecode, BytesEncode};
+#[cfg(feature = "observe")]
+use tokio::sync::watch;
+
+use crate::{
+    db,
+    tools::{counter, Counter},
+    DatabaseUnique, RwTxn, Txn, UnitKey,
+};
+
+pub mod error {
+    use std::path::PathBuf;
+
+    use thiserror::Error;
+
+    use crate::db;
+
+    #[derive(Debug, Error)]
+    #[error(
+        "Queue sequence numbers exhausted in db `{db_name}` at `{db_path}`"
+    )]
+    pub struct Exhausted {
+        pub(crate) db_name: String,
+        pub(crate) db_path: PathBuf,
+    }
+
+    /// Error type for [`super::Queue::push_back`]
+    #[derive(Debug, Error)]
+    pub enum Push {
+        #[error(transparent)]
+        Exhausted(#[from] Exhausted),
+        #[error(transparent)]
+        Put(#[from] db::error::Put),
+        #[error(transparent)]
+        TryGet(#[from] db::error::TryGet),
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use super::{Exhausted, Push};
+        use crate::serde_utils::{
+            serialize_error_enum, serialize_error_struct,
+        };
+
+        serialize_error_struct!(Exhausted {
+            db_name: Plain,
+            db_path: PathStr,
+        });
+
+        serialize_error_enum!(Push {
+            Exhausted,
+            Put,
+            TryGet
+        });
+    }
+}
+
+/// A FIFO queue, in which each item is keyed by a sequence number.
+/// Sequence numbers are allocated from a [`Counter`], so they increase with
+/// each push, and are never reused, even once the queue is empty.
+pub struct Queue<'env_id, DC> {
+    db: DatabaseUnique<'env_id, U64<BigEndian>, DC>,
+    seq: Counter<'env_id>,
+}
+
+impl<'env_id, DC> Queue<'env_id, DC> {
+    /// The dbs should be empty, or only written to by a queue, and must be
+    /// used together.
+    pub fn new(
+        db: DatabaseUnique<'env_id, U64<BigEndian>, DC>,
+        seq_db: DatabaseUnique<'env_id, UnitKey, U64<BigEndian>>,
+    ) -> Self {
+        Self {
+            db,
+            seq: Counter::new(seq_db),
+        }
+    }
+
+    /// Push an item to the back of the queue.
+    /// Returns an [`error::Exhausted`] error once every sequence number has
+    /// been allocated.
+    pub fn push_back<T>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+        item: &T,
+    ) -> Result<(), error::Push>
+    where
+        T: ?Sized,
+        DC: for<'a> BytesEncode<'a, EItem = T>,
+    {
+        let seq = self.seq.next(rwtxn).map_err(|err| match err {
+            counter::error::Increment::Overflow(_) => {
+                error::Push::from(error::Exhausted {
+                    db_name: self.db.name().to_owned(),
+                    db_path: self.db.path().to_owned(),
+                })
+            }
+            counter::error::Increment::Put(err) => err.into(),
+            counter::error::Increment::TryGet(err) => err.into(),
+        })?;
+        let () = self.db.put(rwtxn, &seq, item)?;
+        Ok(())
+    }
+
+    /// Remove and return the item at the front of the queue, if any
+    pub fn pop_front<T>(
+        &self,
+        rwtxn: &mut RwTxn<'_, 'env_id>,
+    ) -> Result<Option<T>, db::error::PopFirst>
+    where
+        DC: for<'a> BytesDecode<'a, DItem = T>,
+    {
+        let item = self.db.pop_first(rwtxn)?;
+        Ok(item.map(|(_seq, item): (u64, _)| item))
+    }
+
+    /// Get the item at the front of the queue, if any
+    pub fn peek_front<'env, 'txn, Tx>(
+        &self,
+        txn: &'txn Tx,
+    ) -> Result<Option<DC::DItem>, db::error::First>
+    where
+        'env: 'txn,
+        Tx: Txn<'env, 'env_id>,
+        DC: BytesDecode<'txn>,
+    {
+        let item = self.db.first(txn)?;
+        Ok(item.map(|(_seq, item)| item))
+    }
+
+    /// Number of items in the queue
+    pub fn len<'env, Tx>(&self, txn: &Tx) -> Result<u64, db::error::Len>
+    where
+        Tx: Txn<'env, 'env_id>,
+    {
+        self.db.len(txn)
+    }
+
+    #[cfg(feature = "observe")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "observe")))]
+    /// Receive notifications when the queue is updated
+    pub fn watch(&self) -> &watch::Receiver<()> {
+        self.db.watch()
+    }
+}
@@ -6,14 +6,123 @@ pub(crate) mod private {
     }
 }
 
+/// Txns that can be used for reads.
+///
+/// Implemented by [`RoTxn`], and by [`RwTxn`] so that reads within a write
+/// txn see its uncommitted writes. Reads borrow heed's txn from the
+/// wrapper, so no unsafe casts between wrapper types are involved.
 pub trait Txn<'env, 'env_id>: private::Sealed<'env> {}
 
 pub mod rotxn {
+    pub mod error {
+        use std::path::PathBuf;
+
+        use thiserror::Error;
+
+        #[derive(Debug, Error)]
+        #[error("Error renewing read txn for database dir `{db_dir}`")]
+        pub struct Renew {
+            pub(crate) db_dir: PathBuf,
+            pub(crate) source: heed::Error,
+        }
+
+        #[cfg(feature = "serde")]
+        mod serialize {
+            use super::Renew;
+            use crate::serde_utils::serialize_error_struct;
+
+            serialize_error_struct!(Renew {
+                db_dir: PathStr,
+                source: DisplayStr,
+            });
+        }
+    }
 
     /// Wrapper for heed's `RoTxn`
     pub struct RoTxn<'env, 'env_id> {
         pub(crate) inner: heed::RoTxn<'env>,
-        pub(crate) _unique_guard: &'env generativity::Guard<'env_id>,
+        pub(crate) env: &'env crate::Env<'env_id>,
+        /// Commit sequence number when the txn was created
+        pub(crate) snapshot_seq: u64,
+        /// Span covering the lifetime of the txn
+        #[cfg(feature = "tracing")]
+        pub(crate) _span: tracing::Span,
+    }
+
+    impl<'env> RoTxn<'env, '_> {
+        /// Re-tag the txn for use with a re-tagged env.
+        ///
+        /// # Safety
+        /// `env` must be a clone of the env that this txn was created from.
+        /// See [`crate::Env::clone_with_tag`].
+        pub unsafe fn retag<'a, 'new>(
+            self,
+            env: &'a crate::Env<'new>,
+        ) -> RoTxn<'a, 'new>
+        where
+            'env: 'a,
+        {
+            RoTxn {
+                inner: self.inner,
+                env,
+                snapshot_seq: self.snapshot_seq,
+                #[cfg(feature = "tracing")]
+                _span: self._span,
+            }
+        }
+
+        /// The underlying heed txn.
+        /// See [raw handles](crate#raw-handles).
+        #[cfg(feature = "raw")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+        #[inline(always)]
+        pub fn as_heed(&self) -> &heed::RoTxn<'env> {
+            &self.inner
+        }
+    }
+
+    impl<'env, 'env_id> RoTxn<'env, 'env_id> {
+        /// Commit sequence number of the env when the txn was created.
+        /// The txn sees at least the writes committed up to this sequence
+        /// number, and may see later commits that completed while the txn
+        /// was being created.
+        /// A txn created later with a greater sequence number may see newer
+        /// data. See [`crate::Env::last_commit_seq`].
+        pub fn snapshot_seq(&self) -> u64 {
+            self.snapshot_seq
+        }
+
+        /// Release the txn's snapshot. The returned [`ResetRoTxn`] keeps the
+        /// env borrow and tag, and can be renewed with
+        /// [`ResetRoTxn::renew`].
+        ///
+        /// # Limitations
+        /// heed 0.20 does not expose LMDB's `mdb_txn_reset` or
+        /// `mdb_txn_renew`. The underlying txn is aborted, releasing its
+        /// reader slot, and [`ResetRoTxn::renew`] begins a new txn. This is
+        /// equivalent to dropping the txn and calling
+        /// [`crate::Env::read_txn`], so it does not reduce reader slot churn.
+        pub fn reset(self) -> ResetRoTxn<'env, 'env_id> {
+            let Self { env, .. } = self;
+            ResetRoTxn { env }
+        }
+    }
+
+    /// A read txn that has been reset with [`RoTxn::reset`].
+    /// Holds no snapshot until it is renewed.
+    pub struct ResetRoTxn<'env, 'env_id> {
+        env: &'env crate::Env<'env_id>,
+    }
+
+    impl<'env, 'env_id> ResetRoTxn<'env, 'env_id> {
+        /// Renew the txn, with a snapshot of the latest committed data.
+        /// Begins a new txn; see [`RoTxn::reset`].
+        pub fn renew(self) -> Result<RoTxn<'env, 'env_id>, error::Renew> {
+            self.env.read_txn().map_err(|err| error::Renew {
+                db_dir: err.db_dir,
+                source: err.source,
+            })
+        }
     }
 
     impl<'env> crate::txn::private::Sealed<'env> for RoTxn<'env, '_> {
@@ -23,14 +132,47 @@ pub mod rotxn {
     }
 
     impl<'env, 'env_id> crate::txn::Txn<'env, 'env_id> for RoTxn<'env, 'env_id> {}
+
+    /// Read txn that owns a handle to the env, and can be sent between
+    /// threads.
+    /// See [`crate::Env::static_read_txn`].
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub struct StaticRoTxn<'env_id> {
+        pub(crate) inner: heed::RoTxn<'static>,
+        /// Keeps the env registered while the txn is live
+        pub(crate) _env: crate::Env<'env_id>,
+        /// Span covering the lifetime of the txn
+        #[cfg(feature = "tracing")]
+        pub(crate) _span: tracing::Span,
+    }
+
+    #[cfg(feature = "stream")]
+    impl crate::txn::private::Sealed<'static> for StaticRoTxn<'_> {
+        fn read_txn(&self) -> &heed::RoTxn<'static> {
+            &self.inner
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    impl<'env_id> crate::txn::Txn<'static, 'env_id> for StaticRoTxn<'env_id> {}
 }
 
-pub use rotxn::RoTxn;
+#[cfg(feature = "stream")]
+pub use rotxn::StaticRoTxn;
+pub use rotxn::{ResetRoTxn, RoTxn};
 
 pub mod rwtxn {
-    use std::path::Path;
     #[cfg(feature = "observe")]
-    use std::{collections::HashMap, sync::Arc};
+    use std::collections::HashMap;
+    #[cfg(feature = "debug-guards")]
+    use std::{cell::RefCell, collections::BTreeSet, path::PathBuf};
+    use std::{
+        collections::HashSet,
+        path::Path,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
 
     #[cfg(feature = "observe")]
     use tokio::sync::watch;
@@ -40,11 +182,92 @@ pub mod rwtxn {
 
         use thiserror::Error;
 
+        /// Cause of a [`Commit`] error
+        #[derive(Debug, Error)]
+        pub enum CommitSource {
+            /// A deferred operation failed, so the txn was aborted.
+            /// See [`super::RwTxn::defer`].
+            #[error("Deferred operation {index} failed")]
+            Deferred {
+                /// Index of the failed operation, in the order that
+                /// operations were deferred
+                index: usize,
+                source: Box<crate::db::error::Error>,
+            },
+            #[error(transparent)]
+            Heed(#[from] heed::Error),
+        }
+
         #[derive(Debug, Error)]
         #[error("Error commiting write txn for database dir `{db_dir}`")]
         pub struct Commit {
             pub(crate) db_dir: PathBuf,
-            pub(crate) source: heed::Error,
+            pub(crate) source: CommitSource,
+        }
+
+        /// Error creating or committing a nested txn
+        #[cfg(feature = "tokio")]
+        #[derive(Debug, Error)]
+        pub enum Nested {
+            #[error(transparent)]
+            Commit(#[from] Commit),
+            #[error(transparent)]
+            UnsupportedWithWriteMap(
+                #[from] crate::env::error::UnsupportedWithWriteMap,
+            ),
+            #[error(transparent)]
+            WriteTxn(#[from] crate::env::error::WriteTxn),
+        }
+
+        #[cfg(feature = "tokio")]
+        impl From<crate::env::error::NestedWriteTxn> for Nested {
+            fn from(err: crate::env::error::NestedWriteTxn) -> Self {
+                match err {
+                    crate::env::error::NestedWriteTxn::UnsupportedWithWriteMap(
+                        err,
+                    ) => Self::UnsupportedWithWriteMap(err),
+                    crate::env::error::NestedWriteTxn::WriteTxn(err) => {
+                        Self::WriteTxn(err)
+                    }
+                }
+            }
+        }
+
+        #[derive(Debug, Error)]
+        #[error(
+            "No savepoint named `{name}` in write txn for database dir `{db_dir}`"
+        )]
+        pub struct UnknownSavepoint {
+            pub(crate) db_dir: PathBuf,
+            pub(crate) name: String,
+        }
+
+        /// Error type for [`super::Savepoints`] operations
+        #[derive(Debug, Error)]
+        pub enum Savepoint {
+            #[error(transparent)]
+            Commit(#[from] Commit),
+            #[error(transparent)]
+            UnknownSavepoint(#[from] UnknownSavepoint),
+            #[error(transparent)]
+            UnsupportedWithWriteMap(
+                #[from] crate::env::error::UnsupportedWithWriteMap,
+            ),
+            #[error(transparent)]
+            WriteTxn(#[from] crate::env::error::WriteTxn),
+        }
+
+        impl From<crate::env::error::NestedWriteTxn> for Savepoint {
+            fn from(err: crate::env::error::NestedWriteTxn) -> Self {
+                match err {
+                    crate::env::error::NestedWriteTxn::UnsupportedWithWriteMap(
+                        err,
+                    ) => Self::UnsupportedWithWriteMap(err),
+                    crate::env::error::NestedWriteTxn::WriteTxn(err) => {
+                        Self::WriteTxn(err)
+                    }
+                }
+            }
         }
 
         /// General error type for RwTxn operations
@@ -53,32 +276,687 @@ pub mod rwtxn {
             #[error(transparent)]
             Commit(#[from] Commit),
         }
+
+        #[cfg(feature = "serde")]
+        mod serialize {
+            #[cfg(feature = "tokio")]
+            use super::Nested;
+            use super::{Commit, Error, Savepoint, UnknownSavepoint};
+            use crate::serde_utils::{
+                serialize_error_enum, serialize_error_struct,
+            };
+
+            serialize_error_struct!(Commit {
+                db_dir: PathStr,
+                source: DisplayStr,
+            });
+
+            #[cfg(feature = "tokio")]
+            serialize_error_enum!(Nested {
+                Commit,
+                UnsupportedWithWriteMap,
+                WriteTxn
+            });
+
+            serialize_error_enum!(Savepoint {
+                Commit,
+                UnknownSavepoint,
+                UnsupportedWithWriteMap,
+                WriteTxn
+            });
+
+            serialize_error_struct!(UnknownSavepoint {
+                db_dir: PathStr,
+                name: Plain,
+            });
+
+            serialize_error_enum!(Error { Commit });
+        }
     }
     pub use error::Error;
 
+    mod savepoints;
+    pub use savepoints::Savepoints;
+
+    /// Stats for a committed write txn, returned by
+    /// [`RwTxn::commit_with_stats`]
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct CommitStats {
+        /// Time taken to commit the txn
+        pub duration: Duration,
+        /// Number of distinct dbs that write operations were attempted on
+        pub dbs_written: usize,
+        /// Number of write operations attempted in the txn, including
+        /// operations in committed nested txns.
+        /// Each call to a db write method, such as `put` or `delete`,
+        /// counts as a single operation, whether or not it succeeded or
+        /// modified the db.
+        pub ops_attempted: usize,
+        /// Commit sequence number of the txn.
+        /// See [`crate::Env::last_commit_seq`].
+        pub commit_seq: u64,
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialize {
+        use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+        use super::CommitStats;
+
+        impl Serialize for CommitStats {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut state =
+                    serializer.serialize_struct("CommitStats", 4)?;
+                let () = state.serialize_field("duration", &self.duration)?;
+                let () =
+                    state.serialize_field("dbs_written", &self.dbs_written)?;
+                let () = state
+                    .serialize_field("ops_attempted", &self.ops_attempted)?;
+                let () =
+                    state.serialize_field("commit_seq", &self.commit_seq)?;
+                state.end()
+            }
+        }
+    }
+
+    /// Writes from a committed nested txn, to be merged into the parent txn
+    pub(crate) struct NestedWrites {
+        ops_attempted: usize,
+        dbs_written: HashSet<Arc<str>>,
+        #[cfg(feature = "cache")]
+        raw_write: bool,
+        #[cfg(feature = "observe")]
+        pending_writes: HashMap<Arc<str>, watch::Sender<()>>,
+        #[cfg(feature = "debug-txn")]
+        dirty: bool,
+        #[cfg(feature = "tracing")]
+        writes: usize,
+    }
+
+    /// Logs a warning if a txn with pending writes is dropped without being
+    /// explicitly committed or aborted
+    #[cfg(feature = "debug-txn")]
+    pub(crate) struct DropGuard<'env> {
+        db_dir: &'env Path,
+        /// Set when the txn is written to
+        dirty: bool,
+        /// Set when the txn is explicitly committed or aborted
+        finished: bool,
+    }
+
+    #[cfg(feature = "debug-txn")]
+    impl<'env> DropGuard<'env> {
+        pub(crate) fn new(db_dir: &'env Path) -> Self {
+            Self {
+                db_dir,
+                dirty: false,
+                finished: false,
+            }
+        }
+    }
+
+    #[cfg(feature = "debug-txn")]
+    impl Drop for DropGuard<'_> {
+        fn drop(&mut self) {
+            // Txns are expected to be dropped while unwinding
+            if self.dirty && !self.finished && !std::thread::panicking() {
+                tracing::warn!(
+                    db_dir = %self.db_dir.display(),
+                    "Write txn with pending writes was dropped without \
+                     commit or abort; writes were discarded"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "debug-guards")]
+    thread_local! {
+        /// Dirs of the envs for which this thread holds a write txn
+        static WRITE_TXN_DIRS: RefCell<BTreeSet<PathBuf>> =
+            const { RefCell::new(BTreeSet::new()) };
+    }
+
+    /// Marks the env dir as having a live write txn on the current thread,
+    /// until dropped
+    #[cfg(feature = "debug-guards")]
+    pub(crate) struct WriteTxnMarker<'env> {
+        db_dir: &'env Path,
+    }
+
+    #[cfg(feature = "debug-guards")]
+    impl<'env> WriteTxnMarker<'env> {
+        /// Must be called before creating a write txn.
+        ///
+        /// # Panics
+        /// Panics if the current thread already holds a write txn for the
+        /// env, since creating another would deadlock on the LMDB writer
+        /// lock.
+        pub(crate) fn acquire(db_dir: &'env Path) -> Self {
+            let inserted = WRITE_TXN_DIRS
+                .with(|dirs| dirs.borrow_mut().insert(db_dir.to_owned()));
+            assert!(
+                inserted,
+                "Current thread already holds a write txn for database dir \
+                 `{}`; creating another would deadlock. Use \
+                 `RwTxn::savepoints` for nested txns instead.",
+                db_dir.display()
+            );
+            Self { db_dir }
+        }
+    }
+
+    #[cfg(feature = "debug-guards")]
+    impl Drop for WriteTxnMarker<'_> {
+        fn drop(&mut self) {
+            // The thread-local may already be destroyed if the txn is
+            // dropped during thread teardown
+            let _: Result<bool, _> = WRITE_TXN_DIRS
+                .try_with(|dirs| dirs.borrow_mut().remove(self.db_dir));
+        }
+    }
+
+    /// Operation deferred until commit. See [`RwTxn::defer`].
+    pub(crate) type DeferredOp<'env, 'env_id> = Box<
+        dyn FnOnce(
+                &mut RwTxn<'env, 'env_id>,
+            ) -> Result<(), crate::db::error::Error>
+            + 'env,
+    >;
+
+    // `RwTxn::retag` transmutes deferred ops to change only their
+    // lifetimes. Each op must stay a boxed trait object, so that the
+    // transmute does not reinterpret any data that depends on the txn.
+    static_assertions::assert_eq_size!(
+        DeferredOp<'static, 'static>,
+        Box<dyn FnOnce()>
+    );
+    static_assertions::assert_eq_align!(
+        DeferredOp<'static, 'static>,
+        Box<dyn FnOnce()>
+    );
+
     /// Wrapper for heed's `RwTxn`
     pub struct RwTxn<'env, 'env_id> {
         pub(crate) inner: heed::RwTxn<'env>,
+        pub(crate) env: &'env heed::Env,
         pub(crate) db_dir: &'env Path,
         pub(crate) _unique_guard: &'env generativity::Guard<'env_id>,
+        /// Commit sequence counter for the env
+        pub(crate) commit_seq: &'env crate::env::CommitSeq,
+        /// `true` if the env was opened with `WRITE_MAP`
+        pub(crate) write_map: bool,
+        /// `true` if the txn was written to with raw handles, so it might
+        /// have written to any db
+        #[cfg(feature = "cache")]
+        pub(crate) raw_write: bool,
         #[cfg(feature = "observe")]
         pub(crate) pending_writes: HashMap<Arc<str>, watch::Sender<()>>,
+        /// Number of db write operations attempted in the txn
+        pub(crate) ops_attempted: usize,
+        /// Names of the dbs that write operations were attempted on
+        pub(crate) dbs_written: HashSet<Arc<str>>,
+        /// Operations to run before commit, in order
+        pub(crate) deferred: Vec<DeferredOp<'env, 'env_id>>,
+        #[cfg(feature = "debug-txn")]
+        pub(crate) drop_guard: DropGuard<'env>,
+        /// Set for top-level txns only, since nested txns are created from
+        /// a parent txn that already holds the marker
+        #[cfg(feature = "debug-guards")]
+        pub(crate) _write_marker: Option<WriteTxnMarker<'env>>,
+        /// Span covering the lifetime of the txn
+        #[cfg(feature = "tracing")]
+        pub(crate) span: tracing::Span,
+        /// Number of write operations in the txn
+        #[cfg(feature = "tracing")]
+        pub(crate) writes: usize,
     }
 
-    impl<'env> RwTxn<'env, '_> {
+    impl<'env, 'env_id> RwTxn<'env, 'env_id> {
         pub fn commit(self) -> Result<(), error::Commit> {
-            let () = self.inner.commit().map_err(|err| error::Commit {
-                db_dir: self.db_dir.to_owned(),
-                source: err,
-            })?;
+            let _: CommitStats = self.commit_with_stats()?;
+            Ok(())
+        }
+
+        /// Commit the txn, returning stats for the txn and the commit.
+        /// Deferred operations are included in the stats, and are not
+        /// included in the commit duration.
+        pub fn commit_with_stats(self) -> Result<CommitStats, error::Commit> {
+            let (stats, _txn_id) = self.commit_with_stats_and_id()?;
+            Ok(stats)
+        }
+
+        /// Commit the txn, returning stats for the txn and the commit, and
+        /// the LMDB txn id of the committed txn
+        fn commit_with_stats_and_id(
+            mut self,
+        ) -> Result<(CommitStats, usize), error::Commit> {
+            #[cfg(feature = "debug-txn")]
+            {
+                self.drop_guard.finished = true;
+            }
+            let () = self.run_deferred()?;
+            let start = Instant::now();
+            // Nested txns are committed with `commit_nested`, so this is
+            // always a top-level txn.
+            let res = self
+                .commit_seq
+                .commit(|_commit_seq| {
+                    // Recorded before commit, so that caches are never
+                    // used by read txns that see this txn's writes without
+                    // being invalidated
+                    #[cfg(feature = "cache")]
+                    let () = self.commit_seq.last_write_seqs.record(
+                        _commit_seq,
+                        &self.dbs_written,
+                        self.raw_write,
+                    );
+                    let () = self.inner.commit()?;
+                    // Read after commit, since LMDB does not persist empty
+                    // txns. The commit lock is still held, so no other txn
+                    // in this process can have committed since.
+                    Ok::<_, heed::Error>(self.env.info().last_txn_id)
+                })
+                .map_err(|err| error::Commit {
+                    db_dir: self.db_dir.to_owned(),
+                    source: err.into(),
+                });
+            let duration = start.elapsed();
+            #[cfg(feature = "tracing")]
+            self.span.in_scope(|| match &res {
+                Ok(_) => tracing::debug!(writes = self.writes, "committed"),
+                Err(err) => tracing::warn!(
+                    writes = self.writes,
+                    error = %err,
+                    "commit failed"
+                ),
+            });
+            let (txn_id, commit_seq) = res?;
             #[cfg(feature = "observe")]
             self.pending_writes
                 .iter()
                 .for_each(|(_db_name, watch_tx)| watch_tx.send_replace(()));
+            let stats = CommitStats {
+                duration,
+                dbs_written: self.dbs_written.len(),
+                ops_attempted: self.ops_attempted,
+                commit_seq,
+            };
+            Ok((stats, txn_id))
+        }
+
+        /// Abort the txn, discarding any writes.
+        ///
+        /// Nothing written in an aborted txn is persisted, and no watch
+        /// notifications are sent for writes in an aborted txn.
+        /// This includes writes from nested txns that were committed into
+        /// this txn, since notifications are only sent when the outermost
+        /// txn is committed.
+        /// Deferred operations are discarded without being run.
+        /// Dropping a txn without committing it has the same effect.
+        pub fn abort(mut self) {
+            #[cfg(feature = "debug-txn")]
+            {
+                self.drop_guard.finished = true;
+            }
+            // Pending notifications must not be sent or merged into a
+            // parent txn
+            #[cfg(feature = "observe")]
+            self.pending_writes.clear();
+            self.deferred.clear();
+            #[cfg(feature = "tracing")]
+            self.span.in_scope(|| {
+                tracing::debug!(writes = self.writes, "aborted");
+            });
+            self.inner.abort()
+        }
+
+        /// Commit the txn, returning its commit sequence number.
+        /// See [`crate::Env::last_commit_seq`].
+        pub fn commit_with_seq(self) -> Result<u64, error::Commit> {
+            let stats = self.commit_with_stats()?;
+            Ok(stats.commit_seq)
+        }
+
+        /// Commit the txn, returning the LMDB txn id of the committed txn.
+        /// Txn ids increase strictly with each commit that writes to the
+        /// env.
+        ///
+        /// # Empty txns
+        /// LMDB does not persist a txn that made no changes, so no id is
+        /// consumed. The id returned for an empty txn is the id of the last
+        /// committed txn.
+        /// [`Self::commit_with_seq`] returns a sequence number that
+        /// advances with every commit, including empty txns, but is not
+        /// persisted.
+        ///
+        /// # Multiple processes
+        /// If another process commits to the env immediately after this
+        /// txn, the returned id may be the id of the other process's txn.
+        pub fn commit_with_id(self) -> Result<usize, error::Commit> {
+            let (_stats, txn_id) = self.commit_with_stats_and_id()?;
+            Ok(txn_id)
+        }
+
+        /// Re-tag the txn for use with a re-tagged env.
+        ///
+        /// # Safety
+        /// `env` must be a clone of the env that this txn was created from.
+        /// See [`crate::Env::clone_with_tag`].
+        pub unsafe fn retag<'a, 'new>(
+            self,
+            env: &'a crate::Env<'new>,
+        ) -> RwTxn<'a, 'new>
+        where
+            'env: 'a,
+        {
+            RwTxn {
+                inner: self.inner,
+                env: self.env,
+                db_dir: self.db_dir,
+                _unique_guard: env.unique_guard(),
+                commit_seq: self.commit_seq,
+                write_map: self.write_map,
+                #[cfg(feature = "cache")]
+                raw_write: self.raw_write,
+                #[cfg(feature = "observe")]
+                pending_writes: self.pending_writes,
+                ops_attempted: self.ops_attempted,
+                dbs_written: self.dbs_written,
+                // Safety: the ops differ from the retagged ops only in the
+                // lifetimes of the txn. The txn is valid for `'a`, and the
+                // caller guarantees that `env` is a clone of the env that
+                // this txn was created from, so the brands are
+                // interchangeable.
+                deferred: std::mem::transmute::<
+                    Vec<DeferredOp<'env, 'env_id>>,
+                    Vec<DeferredOp<'a, 'new>>,
+                >(self.deferred),
+                #[cfg(feature = "debug-txn")]
+                drop_guard: self.drop_guard,
+                #[cfg(feature = "debug-guards")]
+                _write_marker: self._write_marker,
+                #[cfg(feature = "tracing")]
+                span: self.span,
+                #[cfg(feature = "tracing")]
+                writes: self.writes,
+            }
+        }
+
+        /// Begin a nested txn.
+        /// The nested txn must be committed with [`Self::commit_nested`] or
+        /// aborted before this txn is used again.
+        pub(crate) fn begin_nested(
+            &mut self,
+        ) -> Result<RwTxn<'_, 'env_id>, crate::env::error::NestedWriteTxn>
+        {
+            let env = self.env;
+            let db_dir = self.db_dir;
+            if self.write_map {
+                return Err(crate::env::error::UnsupportedWithWriteMap {
+                    db_dir: db_dir.to_owned(),
+                }
+                .into());
+            }
+            let inner =
+                env.nested_write_txn(&mut self.inner).map_err(|err| {
+                    crate::env::error::WriteTxn {
+                        db_dir: db_dir.to_owned(),
+                        source: err,
+                    }
+                })?;
+            Ok(RwTxn {
+                inner,
+                env,
+                db_dir,
+                _unique_guard: self._unique_guard,
+                commit_seq: self.commit_seq,
+                write_map: self.write_map,
+                #[cfg(feature = "cache")]
+                raw_write: false,
+                #[cfg(feature = "observe")]
+                pending_writes: HashMap::new(),
+                ops_attempted: 0,
+                dbs_written: HashSet::new(),
+                deferred: Vec::new(),
+                #[cfg(feature = "debug-txn")]
+                drop_guard: DropGuard::new(db_dir),
+                #[cfg(feature = "debug-guards")]
+                _write_marker: None,
+                #[cfg(feature = "tracing")]
+                span: tracing::debug_span!(
+                    parent: &self.span,
+                    "nested_write_txn"
+                ),
+                #[cfg(feature = "tracing")]
+                writes: 0,
+            })
+        }
+
+        /// Commit a nested txn, returning the writes to merge into the
+        /// parent txn with [`Self::merge_nested`]
+        pub(crate) fn commit_nested(
+            mut self,
+        ) -> Result<NestedWrites, error::Commit> {
+            let () = self.run_deferred()?;
+            let RwTxn {
+                inner,
+                db_dir,
+                #[cfg(feature = "observe")]
+                pending_writes,
+                ops_attempted,
+                dbs_written,
+                #[cfg(feature = "cache")]
+                raw_write,
+                #[cfg(feature = "debug-txn")]
+                mut drop_guard,
+                #[cfg(feature = "tracing")]
+                writes,
+                ..
+            } = self;
+            #[cfg(feature = "debug-txn")]
+            {
+                drop_guard.finished = true;
+            }
+            let () = inner.commit().map_err(|err| error::Commit {
+                db_dir: db_dir.to_owned(),
+                source: err.into(),
+            })?;
+            Ok(NestedWrites {
+                ops_attempted,
+                dbs_written,
+                #[cfg(feature = "cache")]
+                raw_write,
+                #[cfg(feature = "observe")]
+                pending_writes,
+                #[cfg(feature = "debug-txn")]
+                dirty: drop_guard.dirty,
+                #[cfg(feature = "tracing")]
+                writes,
+            })
+        }
+
+        /// Merge the writes from a committed nested txn
+        pub(crate) fn merge_nested(&mut self, nested_writes: NestedWrites) {
+            let NestedWrites {
+                ops_attempted,
+                dbs_written,
+                #[cfg(feature = "cache")]
+                raw_write,
+                #[cfg(feature = "observe")]
+                pending_writes,
+                #[cfg(feature = "debug-txn")]
+                dirty,
+                #[cfg(feature = "tracing")]
+                writes,
+            } = nested_writes;
+            self.ops_attempted += ops_attempted;
+            self.dbs_written.extend(dbs_written);
+            #[cfg(feature = "cache")]
+            {
+                self.raw_write |= raw_write;
+            }
+            // Notifications are sent when the parent txn is committed
+            #[cfg(feature = "observe")]
+            self.pending_writes.extend(pending_writes);
+            #[cfg(feature = "debug-txn")]
+            {
+                self.drop_guard.dirty |= dirty;
+            }
+            #[cfg(feature = "tracing")]
+            {
+                self.writes += writes;
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        /// Run `f` in a nested txn.
+        /// The nested txn is committed if `f` succeeds, and aborted
+        /// otherwise.
+        /// Pending notifications for writes in the nested txn are merged
+        /// into the parent txn only if the nested txn is committed.
+        pub(crate) fn nested<F, T, E>(
+            &mut self,
+            f: F,
+        ) -> Result<Result<T, E>, error::Nested>
+        where
+            F: FnOnce(&mut RwTxn<'_, 'env_id>) -> Result<T, E>,
+        {
+            let mut nested = self.begin_nested()?;
+            let res = f(&mut nested);
+            if res.is_ok() {
+                let nested_writes = nested.commit_nested()?;
+                let () = self.merge_nested(nested_writes);
+            } else {
+                // Pending notifications are discarded with the nested txn
+                nested.abort()
+            }
+            Ok(res)
+        }
+
+        /// Create a stack of named savepoints within this txn.
+        /// See [`Savepoints`].
+        #[inline(always)]
+        pub fn savepoints(&mut self) -> Savepoints<'_, 'env, 'env_id> {
+            Savepoints::new(self)
+        }
+
+        /// The underlying heed txn, for reads.
+        /// See [raw handles](crate#raw-handles).
+        #[cfg(feature = "raw")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+        #[inline(always)]
+        pub fn as_heed(&self) -> &heed::RoTxn<'env> {
+            &self.inner
+        }
+
+        /// The underlying heed txn, for writes.
+        /// Writes made through the heed txn are not tracked by this txn, so
+        /// watchers of the affected dbs are not notified when this txn is
+        /// committed.
+        /// With the `cache` feature, every cache of a db in the env is
+        /// cleared when this txn is committed, since any db might have been
+        /// written to.
+        /// See [raw handles](crate#raw-handles).
+        #[cfg(feature = "raw")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "raw")))]
+        #[inline(always)]
+        pub fn as_heed_mut(&mut self) -> &mut heed::RwTxn<'env> {
+            #[cfg(feature = "cache")]
+            {
+                self.raw_write = true;
+            }
+            self.write_txn()
+        }
+
+        /// Defer an operation until the txn is committed.
+        ///
+        /// Deferred operations are run in the order that they were
+        /// deferred, just before the txn is committed. Operations deferred
+        /// by a deferred operation are run after the operations that were
+        /// already deferred. If a deferred operation fails, the txn is
+        /// aborted, and commit fails with [`error::CommitSource::Deferred`].
+        /// Deferred operations are discarded if the txn is aborted or
+        /// dropped.
+        /// Operations deferred in a nested txn, such as a savepoint, are
+        /// run when the nested txn is committed into its parent.
+        ///
+        /// ```
+        /// # use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+        /// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        /// # make_guard!(guard);
+        /// # let mut opts = EnvOpenOptions::new();
+        /// # let _ = opts.max_dbs(4);
+        /// # let env = unsafe { Env::open(guard, &opts, path) }?;
+        /// let mut rwtxn = env.write_txn()?;
+        /// let db: DatabaseUnique<UnitKey, UnitKey> =
+        ///     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+        /// let () = rwtxn.defer({
+        ///     let db = db.clone();
+        ///     move |rwtxn| Ok(db.put(rwtxn, &(), &())?)
+        /// });
+        /// assert_eq!(db.try_get(&rwtxn, &())?, None);
+        /// let () = rwtxn.commit()?;
+        /// let rotxn = env.read_txn()?;
+        /// assert_eq!(db.try_get(&rotxn, &())?, Some(()));
+        /// # Ok(())
+        /// # }
+        /// # let dir = tempfile::tempdir().unwrap();
+        /// # example(dir.path()).unwrap();
+        /// ```
+        pub fn defer<F>(&mut self, f: F)
+        where
+            F: FnOnce(
+                    &mut RwTxn<'env, 'env_id>,
+                ) -> Result<(), crate::db::error::Error>
+                + 'env,
+        {
+            self.deferred.push(Box::new(f))
+        }
+
+        /// Run deferred operations, including any operations that they defer
+        fn run_deferred(&mut self) -> Result<(), error::Commit> {
+            let mut index = 0;
+            while !self.deferred.is_empty() {
+                for op in std::mem::take(&mut self.deferred) {
+                    let () = op(self).map_err(|err| error::Commit {
+                        db_dir: self.db_dir.to_owned(),
+                        source: error::CommitSource::Deferred {
+                            index,
+                            source: Box::new(err),
+                        },
+                    })?;
+                    index += 1;
+                }
+            }
             Ok(())
         }
 
+        /// Record an attempted write operation on a db, for
+        /// [`CommitStats`]
+        pub(crate) fn record_op(&mut self, db_name: &Arc<str>) {
+            self.ops_attempted += 1;
+            let () = self.record_write(db_name);
+        }
+
+        /// Record that a db was written to, without counting an operation
+        pub(crate) fn record_write(&mut self, db_name: &Arc<str>) {
+            if !self.dbs_written.contains(db_name) {
+                self.dbs_written.insert(db_name.clone());
+            }
+        }
+
         pub(crate) fn write_txn(&mut self) -> &mut heed::RwTxn<'env> {
+            #[cfg(feature = "debug-txn")]
+            {
+                self.drop_guard.dirty = true;
+            }
+            #[cfg(feature = "tracing")]
+            {
+                self.writes += 1;
+            }
             &mut self.inner
         }
     }
@@ -92,3 +970,87 @@ pub mod rwtxn {
     impl<'env, 'env_id> crate::txn::Txn<'env, 'env_id> for RwTxn<'env, 'env_id> {}
 }
 pub use rwtxn::RwTxn;
+
+/// A read txn or a write txn, for code that sometimes reads and sometimes
+/// writes.
+///
+/// `AnyTxn` implements [`Txn`], so it can be used for reads regardless of
+/// variant. Reads from the `Write` variant see its uncommitted writes.
+///
+/// ```
+/// # use sneed::{make_guard, AnyTxn, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+/// # fn example(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+/// # make_guard!(guard);
+/// # let mut opts = EnvOpenOptions::new();
+/// # let _ = opts.max_dbs(4);
+/// # let env = unsafe { Env::open(guard, &opts, path) }?;
+/// # let mut rwtxn = env.write_txn()?;
+/// # let db: DatabaseUnique<UnitKey, UnitKey> =
+/// #     DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+/// # let () = rwtxn.commit()?;
+/// let mut write = env.transaction(true)?;
+/// if let Some(rwtxn) = write.as_rwtxn_mut() {
+///     let () = db.put(rwtxn, &(), &())?;
+/// }
+/// assert_eq!(db.try_get(&write, &())?, Some(()));
+/// let () = write.commit()?;
+/// let read = env.transaction(false)?;
+/// assert!(matches!(read, AnyTxn::Read(_)));
+/// assert_eq!(db.try_get(&read, &())?, Some(()));
+/// # Ok(())
+/// # }
+/// # let dir = tempfile::tempdir().unwrap();
+/// # example(dir.path()).unwrap();
+/// ```
+// Boxing the write txn would allocate for every write txn, and the size
+// difference depends on which features are enabled
+#[allow(clippy::large_enum_variant)]
+pub enum AnyTxn<'env, 'env_id> {
+    Read(RoTxn<'env, 'env_id>),
+    Write(RwTxn<'env, 'env_id>),
+}
+
+impl<'env, 'env_id> AnyTxn<'env, 'env_id> {
+    pub fn is_write(&self) -> bool {
+        matches!(self, Self::Write(_))
+    }
+
+    /// The write txn, if this is a write txn
+    pub fn as_rwtxn_mut(&mut self) -> Option<&mut RwTxn<'env, 'env_id>> {
+        match self {
+            Self::Read(_) => None,
+            Self::Write(rwtxn) => Some(rwtxn),
+        }
+    }
+
+    /// Commit the txn if it is a write txn, or end it if it is a read txn
+    pub fn commit(self) -> Result<(), rwtxn::error::Commit> {
+        match self {
+            Self::Read(_) => Ok(()),
+            Self::Write(rwtxn) => rwtxn.commit(),
+        }
+    }
+}
+
+impl<'env, 'env_id> From<RoTxn<'env, 'env_id>> for AnyTxn<'env, 'env_id> {
+    fn from(rotxn: RoTxn<'env, 'env_id>) -> Self {
+        Self::Read(rotxn)
+    }
+}
+
+impl<'env, 'env_id> From<RwTxn<'env, 'env_id>> for AnyTxn<'env, 'env_id> {
+    fn from(rwtxn: RwTxn<'env, 'env_id>) -> Self {
+        Self::Write(rwtxn)
+    }
+}
+
+impl<'env> private::Sealed<'env> for AnyTxn<'env, '_> {
+    fn read_txn(&self) -> &heed::RoTxn<'env> {
+        match self {
+            Self::Read(rotxn) => private::Sealed::read_txn(rotxn),
+            Self::Write(rwtxn) => private::Sealed::read_txn(rwtxn),
+        }
+    }
+}
+
+impl<'env, 'env_id> Txn<'env, 'env_id> for AnyTxn<'env, 'env_id> {}
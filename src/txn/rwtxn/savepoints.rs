@@ -0,0 +1,117 @@
+//! Named savepoints within a write txn
+
+use super::{error, RwTxn};
+
+// `Savepoints::push` extends the lifetime of each nested txn. This is only
+// sound if the nested txn cannot be duplicated, so that it cannot outlive
+// the savepoint that owns it.
+static_assertions::assert_not_impl_any!(RwTxn<'static, 'static>: Clone, Copy);
+
+/// Stack of named savepoints within a write txn, similar to SQL
+/// savepoints.
+///
+/// Each savepoint is a nested txn of the savepoint below it, or of the
+/// base txn if it is the outermost savepoint. Writes should be made with
+/// [`Self::txn`], which is the txn for the innermost savepoint.
+/// Savepoints that have not been released when the stack is dropped are
+/// rolled back.
+/// See [`RwTxn::savepoints`].
+pub struct Savepoints<'a, 'env, 'env_id> {
+    base: &'a mut RwTxn<'env, 'env_id>,
+    /// Savepoint names and nested txns, innermost last.
+    /// Each nested txn borrows the txn below it. These borrows are erased,
+    /// which is sound as long as only the innermost txn is accessible, and
+    /// nested txns are finished innermost first.
+    levels: Vec<(String, RwTxn<'env, 'env_id>)>,
+}
+
+impl<'a, 'env, 'env_id> Savepoints<'a, 'env, 'env_id> {
+    pub(in crate::txn) fn new(base: &'a mut RwTxn<'env, 'env_id>) -> Self {
+        Self {
+            base,
+            levels: Vec::new(),
+        }
+    }
+
+    /// The txn for the innermost savepoint, or the base txn if there are no
+    /// savepoints
+    pub fn txn(&mut self) -> &mut RwTxn<'env, 'env_id> {
+        match self.levels.last_mut() {
+            Some((_, txn)) => txn,
+            None => self.base,
+        }
+    }
+
+    /// Index of the innermost savepoint with the specified name
+    fn position(&self, name: &str) -> Result<usize, error::UnknownSavepoint> {
+        self.levels
+            .iter()
+            .rposition(|(level_name, _)| level_name == name)
+            .ok_or_else(|| error::UnknownSavepoint {
+                db_dir: self.base.db_dir.to_owned(),
+                name: name.to_owned(),
+            })
+    }
+
+    /// Push a new savepoint.
+    /// Savepoint names do not need to be unique; operations on a name apply
+    /// to the innermost savepoint with that name.
+    /// Savepoints are not supported in envs opened with `WRITE_MAP`.
+    /// See [`crate::Env::supports_nested_txns`].
+    pub fn push<Name>(
+        &mut self,
+        name: Name,
+    ) -> Result<(), crate::env::error::NestedWriteTxn>
+    where
+        Name: Into<String>,
+    {
+        let nested = self.txn().begin_nested()?;
+        // Safety: the parent txn is not accessible until the nested txn is
+        // popped, and nested txns are always finished before the parent
+        // txn.
+        let nested = unsafe {
+            std::mem::transmute::<RwTxn<'_, 'env_id>, RwTxn<'env, 'env_id>>(
+                nested,
+            )
+        };
+        self.levels.push((name.into(), nested));
+        Ok(())
+    }
+
+    /// Roll back to the innermost savepoint with the specified name,
+    /// discarding any writes made since it was pushed.
+    /// Savepoints pushed after it are removed, and the savepoint itself
+    /// remains on the stack.
+    pub fn rollback_to(&mut self, name: &str) -> Result<(), error::Savepoint> {
+        let index = self.position(name)?;
+        let mut name = String::new();
+        while self.levels.len() > index {
+            let (level_name, txn) = self.levels.pop().unwrap();
+            let () = txn.abort();
+            name = level_name;
+        }
+        let () = self.push(name)?;
+        Ok(())
+    }
+
+    /// Release the innermost savepoint with the specified name, merging its
+    /// writes into the txn below it.
+    /// Savepoints pushed after it are also released.
+    pub fn release(&mut self, name: &str) -> Result<(), error::Savepoint> {
+        let index = self.position(name)?;
+        while self.levels.len() > index {
+            let (_, txn) = self.levels.pop().unwrap();
+            let nested_writes = txn.commit_nested()?;
+            let () = self.txn().merge_nested(nested_writes);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Savepoints<'_, '_, '_> {
+    fn drop(&mut self) {
+        while let Some((_, txn)) = self.levels.pop() {
+            let () = txn.abort();
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Disk usage reports for envs.
+//! See [`crate::Env::usage_report`].
+
+use std::fmt::{self, Display};
+
+/// Disk usage for a named database
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DbUsage {
+    pub name: String,
+    pub entries: u64,
+    /// Depth of the B-tree
+    pub depth: u32,
+    pub branch_pages: u64,
+    pub leaf_pages: u64,
+    pub overflow_pages: u64,
+    /// Approximate size in bytes, ie. total pages multiplied by page size
+    pub bytes: u64,
+}
+
+impl DbUsage {
+    pub(crate) fn new(name: String, stat: &heed::DatabaseStat) -> Self {
+        let pages =
+            (stat.branch_pages + stat.leaf_pages + stat.overflow_pages) as u64;
+        Self {
+            name,
+            entries: stat.entries as u64,
+            depth: stat.depth,
+            branch_pages: stat.branch_pages as u64,
+            leaf_pages: stat.leaf_pages as u64,
+            overflow_pages: stat.overflow_pages as u64,
+            bytes: pages * stat.page_size as u64,
+        }
+    }
+
+    pub fn pages(&self) -> u64 {
+        self.branch_pages + self.leaf_pages + self.overflow_pages
+    }
+}
+
+/// Disk usage for an env
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageReport {
+    pub page_size: u32,
+    pub map_size: u64,
+    /// Pages that have been allocated in the map,
+    /// including pages that are on LMDB's free list
+    pub used_pages: u64,
+    /// Pages in the map that have never been allocated
+    pub free_pages: u64,
+    /// Named databases, in name order
+    pub databases: Vec<DbUsage>,
+}
+
+impl Display for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const HEADERS: [&str; 7] = [
+            "database", "entries", "depth", "branch", "leaf", "overflow",
+            "bytes",
+        ];
+        let rows: Vec<[String; 7]> = self
+            .databases
+            .iter()
+            .map(|db| {
+                [
+                    db.name.clone(),
+                    db.entries.to_string(),
+                    db.depth.to_string(),
+                    db.branch_pages.to_string(),
+                    db.leaf_pages.to_string(),
+                    db.overflow_pages.to_string(),
+                    db.bytes.to_string(),
+                ]
+            })
+            .collect();
+        let mut widths = HEADERS.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        // Database names are left-aligned, and numbers right-aligned
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[&str]| {
+            write!(f, "{:<width$}", row[0], width = widths[0])?;
+            for (cell, width) in row.iter().zip(widths).skip(1) {
+                write!(f, "  {cell:>width$}")?;
+            }
+            writeln!(f)
+        };
+        let () = write_row(f, &HEADERS)?;
+        let total_width = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+        writeln!(f, "{}", "-".repeat(total_width))?;
+        for row in &rows {
+            let row: Vec<&str> = row.iter().map(String::as_str).collect();
+            let () = write_row(f, &row)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "page size:  {}", self.page_size)?;
+        writeln!(f, "map size:   {}", self.map_size)?;
+        writeln!(f, "used pages: {}", self.used_pages)?;
+        write!(f, "free pages: {}", self.free_pages)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+
+    use super::{DbUsage, UsageReport};
+
+    impl Serialize for DbUsage {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = serializer.serialize_struct("DbUsage", 7)?;
+            let () = state.serialize_field("name", &self.name)?;
+            let () = state.serialize_field("entries", &self.entries)?;
+            let () = state.serialize_field("depth", &self.depth)?;
+            let () =
+                state.serialize_field("branch_pages", &self.branch_pages)?;
+            let () = state.serialize_field("leaf_pages", &self.leaf_pages)?;
+            let () = state
+                .serialize_field("overflow_pages", &self.overflow_pages)?;
+            let () = state.serialize_field("bytes", &self.bytes)?;
+            state.end()
+        }
+    }
+
+    impl Serialize for UsageReport {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = serializer.serialize_struct("UsageReport", 5)?;
+            let () = state.serialize_field("page_size", &self.page_size)?;
+            let () = state.serialize_field("map_size", &self.map_size)?;
+            let () = state.serialize_field("used_pages", &self.used_pages)?;
+            let () = state.serialize_field("free_pages", &self.free_pages)?;
+            let () = state.serialize_field("databases", &self.databases)?;
+            state.end()
+        }
+    }
+}
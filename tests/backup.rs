@@ -0,0 +1,39 @@
+#![cfg(feature = "backup")]
+
+use sneed::{make_guard, types::Str, DatabaseUnique, Env, EnvOpenOptions};
+
+#[test]
+fn backup_hash_tracks_data() {
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<Str, Str> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, "key", "value").unwrap();
+    let () = rwtxn.commit().unwrap();
+    let backup = |name: &str| {
+        let path = backup_dir.path().join(name);
+        let report = env.backup(&path, true).unwrap();
+        assert_eq!(report.bytes, std::fs::metadata(&path).unwrap().len());
+        report.blake3
+    };
+    let hash = backup("0.mdb");
+    // Unchanged data
+    assert_eq!(backup("1.mdb"), hash);
+    // A single byte changed
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, "key", "valuf").unwrap();
+    let () = rwtxn.commit().unwrap();
+    assert_ne!(backup("2.mdb"), hash);
+    // Restored
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, "key", "value").unwrap();
+    let () = rwtxn.commit().unwrap();
+    assert_eq!(backup("3.mdb"), hash);
+    // An existing file is not overwritten
+    assert!(env.backup(&backup_dir.path().join("0.mdb"), true).is_err());
+}
@@ -0,0 +1,82 @@
+use sneed::{
+    db::WriteBatch,
+    make_guard,
+    types::{BigEndian, Str, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, Str, U32<BigEndian>>;
+
+fn assert_send<T: Send>() {}
+
+#[test]
+fn write_batch_is_send() {
+    assert_send::<WriteBatch<'static>>();
+}
+
+#[test]
+fn apply_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, "deleted", &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+    // Build the batch on another thread, while holding a read txn
+    let batch = std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let rotxn = env.read_txn().unwrap();
+                let mut batch = WriteBatch::new();
+                let () = batch.put_unique(&db, "a", &1).unwrap();
+                let () = batch.delete_unique(&db, "deleted").unwrap();
+                let () = batch.put_unique(&db, "b", &2).unwrap();
+                let () = batch.put_unique(&db, "a", &3).unwrap();
+                drop(rotxn);
+                batch
+            })
+            .join()
+            .unwrap()
+    });
+    assert_eq!(batch.len(), 4);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = batch.apply(&mut rwtxn).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    // The later put overrides the earlier one
+    assert_eq!(db.try_get(&rotxn, "a").unwrap(), Some(3));
+    assert_eq!(db.try_get(&rotxn, "b").unwrap(), Some(2));
+    assert_eq!(db.try_get(&rotxn, "deleted").unwrap(), None);
+}
+
+#[test]
+fn failure_reports_op_index() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    // LMDB rejects keys longer than 511 bytes
+    let long_key = "k".repeat(1024);
+    let mut batch = WriteBatch::new();
+    let () = batch.put_unique(&db, "a", &1).unwrap();
+    let () = batch.put_unique(&db, "b", &2).unwrap();
+    let () = batch.put_unique(&db, &long_key, &3).unwrap();
+    let () = batch.put_unique(&db, "c", &4).unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let err = batch.apply(&mut rwtxn).unwrap_err();
+    assert_eq!(err.index(), 2);
+    assert_eq!(err.to_string(), "Failed to apply op 2 of write batch");
+    // Earlier ops were applied, later ops were not
+    assert_eq!(db.try_get(&rwtxn, "b").unwrap(), Some(2));
+    assert_eq!(db.try_get(&rwtxn, "c").unwrap(), None);
+    rwtxn.abort();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, "a").unwrap(), None);
+}
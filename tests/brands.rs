@@ -0,0 +1,9 @@
+//! Compile tests for env brands: txns and dbs from different envs must
+//! never be usable together.
+
+#[test]
+fn brands() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "cache")]
+
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use sneed::{
+    db::CachedDatabase,
+    flags::EnvFlags,
+    make_guard,
+    types::{BigEndian, U64},
+    DatabaseUnique, Env, EnvOpenOptions, UnitKey,
+};
+
+type Db<'id> = DatabaseUnique<'id, UnitKey, U64<BigEndian>>;
+
+fn capacity() -> NonZeroUsize {
+    NonZeroUsize::new(16).unwrap()
+}
+
+#[test]
+fn write_invalidates_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &(), &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let cache = CachedDatabase::new(&env, (*db).clone(), capacity());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(cache.try_get_cached(&rotxn, &()).unwrap(), Some(0));
+    assert_eq!(cache.try_get_cached(&rotxn, &()).unwrap(), Some(0));
+    assert_eq!(cache.stats().hits, 1);
+    drop(rotxn);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &(), &1).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(cache.try_get_cached(&rotxn, &()).unwrap(), Some(1));
+}
+
+/// Reads from txns created after a commit must never see values cached
+/// before the commit
+#[test]
+fn no_stale_reads_during_concurrent_writes() {
+    const WRITES: u64 = 2000;
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    // Readers keep old pages in use, so the map must be large enough for
+    // every write
+    let _ = unsafe {
+        opts.max_dbs(1)
+            .map_size(1 << 30)
+            .flags(EnvFlags::NO_SYNC | EnvFlags::NO_TLS)
+    };
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &(), &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let cache = CachedDatabase::new(&env, (*db).clone(), capacity());
+    let done = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    let rotxn = env.read_txn().unwrap();
+                    let cached = cache.try_get_cached(&rotxn, &()).unwrap();
+                    let uncached = db.try_get(&rotxn, &()).unwrap();
+                    assert_eq!(cached, uncached);
+                }
+            });
+        }
+        let writer = scope.spawn(|| {
+            for value in 1..=WRITES {
+                let mut rwtxn = env.write_txn().unwrap();
+                let () = db.put(&mut rwtxn, &(), &value).unwrap();
+                let () = rwtxn.commit().unwrap();
+            }
+        });
+        // Stop the readers even if the writer panics
+        let res = writer.join();
+        done.store(true, Ordering::SeqCst);
+        res.unwrap();
+    });
+}
+
+#[cfg(feature = "raw")]
+#[test]
+fn raw_write_invalidates_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &(), &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let cache = CachedDatabase::new(&env, (*db).clone(), capacity());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(cache.try_get_cached(&rotxn, &()).unwrap(), Some(0));
+    drop(rotxn);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.as_heed().put(rwtxn.as_heed_mut(), &(), &1).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(cache.try_get_cached(&rotxn, &()).unwrap(), Some(1));
+}
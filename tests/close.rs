@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+/// Closing the sole handle allows the env to be reopened immediately,
+/// with every committed write visible
+#[test]
+fn close_then_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..100 {
+        make_guard!(guard);
+        let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+        let mut rwtxn = env.write_txn().unwrap();
+        let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+            DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+        assert_eq!(db.len(&rwtxn).unwrap(), u64::from(i));
+        let () = db.put(&mut rwtxn, &i, &i).unwrap();
+        let () = rwtxn.commit().unwrap();
+        let closing = env.close();
+        assert_eq!(closing.references(), 0);
+        let () = closing.wait_timeout(Duration::from_secs(10)).unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+#[test]
+fn commit_ids_strictly_increase() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let mut last_id = rwtxn.commit_with_id().unwrap();
+    for key in 0..10 {
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &key, &key).unwrap();
+        let id = rwtxn.commit_with_id().unwrap();
+        assert!(id > last_id);
+        last_id = id;
+    }
+    // Empty txns consume no id, and return the last committed id
+    let empty_id = env.write_txn().unwrap().commit_with_id().unwrap();
+    assert_eq!(empty_id, last_id);
+    assert_eq!(env.write_txn().unwrap().commit_with_id().unwrap(), last_id);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &10, &10).unwrap();
+    let id = rwtxn.commit_with_id().unwrap();
+    assert!(id > empty_id);
+}
+
+#[test]
+fn commit_seqs_advance_for_empty_txns() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap();
+    let seq0 = env.write_txn().unwrap().commit_with_seq().unwrap();
+    let seq1 = env.write_txn().unwrap().commit_with_seq().unwrap();
+    assert!(seq1 > seq0);
+}
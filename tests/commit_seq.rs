@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+
+use sneed::{
+    flags::EnvFlags,
+    make_guard,
+    types::{BigEndian, U64},
+    DatabaseUnique, Env, EnvOpenOptions, UnitKey,
+};
+
+const THREADS: usize = 8;
+const COMMITS_PER_THREAD: u64 = 2000;
+
+/// Sequence numbers must follow commit order, even when write txns are
+/// committed from several threads
+#[test]
+fn commit_seq_follows_commit_order() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    // Syncing is not needed for the test, and makes commits slower, which
+    // makes reordering less likely
+    let _ = unsafe { opts.max_dbs(1).flags(EnvFlags::NO_SYNC) };
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<UnitKey, U64<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "counter").unwrap();
+    let () = db.put(&mut rwtxn, &(), &0).unwrap();
+    let initial_seq = rwtxn.commit_with_seq().unwrap();
+    // (counter value written, commit seq)
+    let commits = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..COMMITS_PER_THREAD {
+                    let mut rwtxn = env.write_txn().unwrap();
+                    let value = db.get(&rwtxn, &()).unwrap() + 1;
+                    let () = db.put(&mut rwtxn, &(), &value).unwrap();
+                    let seq = rwtxn.commit_with_seq().unwrap();
+                    commits.lock().unwrap().push((value, seq));
+                }
+            });
+        }
+    });
+    let mut commits = commits.into_inner().unwrap();
+    commits.sort_unstable();
+    assert_eq!(commits.len() as u64, THREADS as u64 * COMMITS_PER_THREAD);
+    for (value, seq) in commits {
+        assert_eq!(seq, initial_seq + value);
+    }
+    assert_eq!(
+        env.last_commit_seq(),
+        initial_seq + THREADS as u64 * COMMITS_PER_THREAD
+    );
+}
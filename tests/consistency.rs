@@ -0,0 +1,100 @@
+use sneed::{
+    db::{
+        consistency::{check_bijection, Mode},
+        error::{
+            self,
+            inconsistent::{ByKey, ByValue, Mismatch, Xor},
+        },
+    },
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn put_all<'id>(env: &Env<'id>, db: &Db<'id>, entries: &[(u32, u32)]) {
+    let mut rwtxn = env.write_txn().unwrap();
+    for (key, value) in entries {
+        let () = db.put(&mut rwtxn, key, value).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+}
+
+#[test]
+fn desynced_bijection() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let forward: Db =
+        DatabaseUnique::create(&env, &mut rwtxn, "forward").unwrap();
+    let reverse: Db =
+        DatabaseUnique::create(&env, &mut rwtxn, "reverse").unwrap();
+    let () = rwtxn.commit().unwrap();
+    put_all(&env, &forward, &[(1, 10), (2, 20)]);
+    put_all(&env, &reverse, &[(10, 1), (20, 2)]);
+    let rotxn = env.read_txn().unwrap();
+    let report =
+        check_bijection(&rotxn, &forward, &reverse, Mode::FailFast).unwrap();
+    assert!(report.is_consistent());
+    drop(rotxn);
+
+    // `3 -> 30` is missing from `reverse`, `20` maps back to `5` instead of
+    // `2`, and `40 -> 4` is missing from `forward`
+    put_all(&env, &forward, &[(3, 30)]);
+    put_all(&env, &reverse, &[(20, 5), (40, 4)]);
+    let expected: Vec<String> = vec![
+        Mismatch::new(
+            &20,
+            ByValue(&*forward),
+            &2u32.to_be_bytes(),
+            ByKey(&*reverse),
+            &5u32.to_be_bytes(),
+        )
+        .to_string(),
+        Xor::new(&30, ByValue(&*forward), ByKey(&*reverse)).to_string(),
+        Xor::new(&5, ByValue(&*reverse), ByKey(&*forward)).to_string(),
+        Xor::new(&4, ByValue(&*reverse), ByKey(&*forward)).to_string(),
+    ];
+    let rotxn = env.read_txn().unwrap();
+
+    let report = check_bijection(
+        &rotxn,
+        &forward,
+        &reverse,
+        Mode::Report { max_errors: 10 },
+    )
+    .unwrap();
+    let reported: Vec<String> = report
+        .inconsistencies
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(reported, expected);
+    assert!(!report.truncated);
+
+    let report = check_bijection(
+        &rotxn,
+        &forward,
+        &reverse,
+        Mode::Report { max_errors: 2 },
+    )
+    .unwrap();
+    let reported: Vec<String> = report
+        .inconsistencies
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(reported, expected[..2]);
+    assert!(report.truncated);
+
+    let err = check_bijection(&rotxn, &forward, &reverse, Mode::FailFast)
+        .unwrap_err();
+    let error::Error::Inconsistent(err) = err else {
+        panic!("expected an inconsistency, got {err:?}")
+    };
+    assert_eq!(err.to_string(), expected[0]);
+}
@@ -0,0 +1,75 @@
+use sneed::{
+    make_guard,
+    tools::{counter::error, Counter, Counters},
+    types::{BigEndian, Str, U64},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+#[test]
+fn counter_allocates_values() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let counter =
+        Counter::new(DatabaseUnique::create(&env, &mut rwtxn, "ids").unwrap());
+    // First use
+    assert_eq!(counter.peek(&rwtxn).unwrap(), 0);
+    assert_eq!(counter.next(&mut rwtxn).unwrap(), 0);
+    assert_eq!(counter.next(&mut rwtxn).unwrap(), 1);
+    // Block allocation
+    assert_eq!(counter.allocate_block(&mut rwtxn, 10).unwrap(), 2..12);
+    assert_eq!(counter.allocate_block(&mut rwtxn, 0).unwrap(), 12..12);
+    assert_eq!(counter.next(&mut rwtxn).unwrap(), 12);
+    let () = rwtxn.commit().unwrap();
+    // Allocations in aborted txns are rolled back
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(counter.allocate_block(&mut rwtxn, 5).unwrap(), 13..18);
+    rwtxn.abort();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(counter.peek(&rotxn).unwrap(), 13);
+}
+
+#[test]
+fn counter_overflow() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let counter =
+        Counter::new(DatabaseUnique::create(&env, &mut rwtxn, "ids").unwrap());
+    let block = counter.allocate_block(&mut rwtxn, u64::MAX - 1).unwrap();
+    assert_eq!(block, 0..u64::MAX - 1);
+    assert_eq!(counter.next(&mut rwtxn).unwrap(), u64::MAX - 1);
+    let err = counter.next(&mut rwtxn).unwrap_err();
+    assert!(matches!(err, error::Increment::Overflow(_)), "{err:?}");
+    let err = counter.allocate_block(&mut rwtxn, 2).unwrap_err();
+    assert!(matches!(err, error::Increment::Overflow(_)), "{err:?}");
+    // The counter is unchanged by failed allocations
+    assert_eq!(counter.peek(&rwtxn).unwrap(), u64::MAX);
+}
+
+#[test]
+fn counters_per_key() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<Str, U64<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "counts").unwrap();
+    let counters = Counters::new(db);
+    assert_eq!(counters.get(&rwtxn, "a").unwrap(), 0);
+    assert_eq!(counters.increment(&mut rwtxn, "a", 2).unwrap(), 2);
+    assert_eq!(counters.increment(&mut rwtxn, "a", 3).unwrap(), 5);
+    assert_eq!(counters.increment(&mut rwtxn, "b", 1).unwrap(), 1);
+    assert_eq!(counters.get(&rwtxn, "a").unwrap(), 5);
+    let err = counters.increment(&mut rwtxn, "b", u64::MAX).unwrap_err();
+    assert!(matches!(err, error::Increment::Overflow(_)), "{err:?}");
+    assert_eq!(counters.get(&rwtxn, "b").unwrap(), 1);
+}
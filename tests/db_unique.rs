@@ -0,0 +1,218 @@
+//! Single-db operations on [`DatabaseUnique`]
+
+use sneed::{
+    db::error,
+    make_guard,
+    types::{BigEndian, Str, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    opts
+}
+
+/// Create a db containing `(key, key * 10)` for each key
+fn create_db<'id>(
+    env: &Env<'id>,
+    keys: impl IntoIterator<Item = u32>,
+) -> Db<'id> {
+    let mut rwtxn = env.write_txn().unwrap();
+    let db = DatabaseUnique::create(env, &mut rwtxn, "db").unwrap();
+    for key in keys {
+        let () = db.put(&mut rwtxn, &key, &(key * 10)).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    db
+}
+
+#[test]
+fn get_or_default() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, [1]);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.get_or_default(&rotxn, &1).unwrap(), 10);
+    assert_eq!(db.get_or_default(&rotxn, &2).unwrap(), 0);
+    drop(rotxn);
+    // Borrowed values
+    let mut rwtxn = env.write_txn().unwrap();
+    let strs: DatabaseUnique<Str, Str> =
+        DatabaseUnique::create(&env, &mut rwtxn, "strs").unwrap();
+    let () = strs.put(&mut rwtxn, "a", "b").unwrap();
+    assert_eq!(strs.get_or_default(&rwtxn, "a").unwrap(), "b");
+    assert_eq!(strs.get_or_default(&rwtxn, "b").unwrap(), "");
+}
+
+#[test]
+fn update_range() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, 0..10);
+    let mut rwtxn = env.write_txn().unwrap();
+    let mut visited = Vec::new();
+    // Replace odd values in the range
+    let updated = db
+        .update_range(&mut rwtxn, &(2..8), |key, value| {
+            visited.push(*key);
+            (key % 2 == 1).then_some(value + 1)
+        })
+        .unwrap();
+    assert_eq!(updated, 3);
+    assert_eq!(visited, [2, 3, 4, 5, 6, 7]);
+    let updated = db
+        .update_range(&mut rwtxn, &(20..), |_, _| Some(0))
+        .unwrap();
+    assert_eq!(updated, 0);
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let values: Vec<u32> =
+        (0..10).map(|key| db.get(&rotxn, &key).unwrap()).collect();
+    assert_eq!(values, [0, 10, 20, 31, 40, 51, 60, 71, 80, 90]);
+}
+
+#[test]
+fn rename_key() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, [1, 2]);
+    let mut rwtxn = env.write_txn().unwrap();
+    assert!(db.rename_key(&mut rwtxn, &1, &3, false).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), None);
+    assert_eq!(db.try_get(&rwtxn, &3).unwrap(), Some(10));
+    // Missing and equal keys
+    assert!(!db.rename_key(&mut rwtxn, &1, &4, false).unwrap());
+    assert!(!db.rename_key(&mut rwtxn, &3, &3, false).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &3).unwrap(), Some(10));
+    // Existing new key
+    let err = db.rename_key(&mut rwtxn, &3, &2, false).unwrap_err();
+    assert!(matches!(err, error::RenameKey::DuplicateKey(_)), "{err:?}");
+    assert_eq!(db.try_get(&rwtxn, &2).unwrap(), Some(20));
+    assert!(db.rename_key(&mut rwtxn, &3, &2, true).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &2).unwrap(), Some(10));
+    assert_eq!(db.try_get(&rwtxn, &3).unwrap(), None);
+    assert_eq!(db.len(&rwtxn).unwrap(), 1);
+}
+
+#[test]
+fn swap_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, [1, 2]);
+    let mut rwtxn = env.write_txn().unwrap();
+    assert!(db.swap_keys(&mut rwtxn, &1, &2).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(20));
+    assert_eq!(db.try_get(&rwtxn, &2).unwrap(), Some(10));
+    // Swapping a key with itself
+    assert!(db.swap_keys(&mut rwtxn, &1, &1).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(20));
+    // A missing key is not written
+    assert!(!db.swap_keys(&mut rwtxn, &1, &3).unwrap());
+    assert!(!db.swap_keys(&mut rwtxn, &3, &1).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(20));
+    assert_eq!(db.try_get(&rwtxn, &3).unwrap(), None);
+}
+
+#[test]
+fn insert() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, [1]);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.insert(&mut rwtxn, &2, &21).unwrap();
+    assert_eq!(db.try_get(&rwtxn, &2).unwrap(), Some(21));
+    let err = db.insert(&mut rwtxn, &1, &11).unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            error::Insert::AlreadyExists { key_bytes, .. }
+                if *key_bytes == 1u32.to_be_bytes()
+        ),
+        "{err:?}"
+    );
+    // The existing value is not overwritten
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(10));
+    assert_eq!(db.len(&rwtxn).unwrap(), 2);
+}
+
+#[test]
+fn fold() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, []);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.fold(&rotxn, 7, |acc, _, _| acc + 1).unwrap(), 7);
+    drop(rotxn);
+    let db = create_db(&env, [3, 1, 2]);
+    let rotxn = env.read_txn().unwrap();
+    let items = db
+        .fold(&rotxn, Vec::new(), |mut acc, key, value| {
+            acc.push((key, value));
+            acc
+        })
+        .unwrap();
+    assert_eq!(items, [(1, 10), (2, 20), (3, 30)]);
+    // try_fold stops at the first error
+    let mut visited = Vec::new();
+    let res: Result<u32, error::Iter> =
+        db.try_fold(&rotxn, 0, |acc, key, value| {
+            visited.push(key);
+            Ok(acc + value)
+        });
+    assert_eq!(res.unwrap(), 60);
+    #[derive(Debug)]
+    enum StopAt {
+        Iter(#[allow(dead_code)] error::Iter),
+        Key(u32),
+    }
+    impl From<error::Iter> for StopAt {
+        fn from(err: error::Iter) -> Self {
+            Self::Iter(err)
+        }
+    }
+    let res = db.try_fold(&rotxn, 0, |acc, key, value| {
+        if key == 2 {
+            Err(StopAt::Key(acc))
+        } else {
+            Ok(acc + value)
+        }
+    });
+    assert!(matches!(res, Err(StopAt::Key(10))), "{res:?}");
+    assert_eq!(visited, [1, 2, 3]);
+}
+
+#[test]
+fn nth() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env, []);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.nth(&rotxn, 0).unwrap(), None);
+    assert_eq!(db.nth_key(&rotxn, 0).unwrap(), None);
+    drop(rotxn);
+    let db = create_db(&env, [4, 2, 8, 6]);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.nth(&rotxn, 0).unwrap(), db.first(&rotxn).unwrap());
+    assert_eq!(db.nth(&rotxn, 3).unwrap(), db.last(&rotxn).unwrap());
+    let items: Vec<_> = (0..4).map(|n| db.nth(&rotxn, n).unwrap()).collect();
+    assert_eq!(
+        items,
+        [Some((2, 20)), Some((4, 40)), Some((6, 60)), Some((8, 80))]
+    );
+    let keys: Vec<_> = (0..4).map(|n| db.nth_key(&rotxn, n).unwrap()).collect();
+    assert_eq!(keys, [Some(2), Some(4), Some(6), Some(8)]);
+    for n in [4, 5, u64::MAX] {
+        assert_eq!(db.nth(&rotxn, n).unwrap(), None);
+        assert_eq!(db.nth_key(&rotxn, n).unwrap(), None);
+    }
+}
@@ -0,0 +1,101 @@
+// Deferred ops must return `sneed::db::error::Error`
+#![allow(clippy::result_large_err)]
+
+use std::{cell::Cell, rc::Rc};
+
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+fn create_db<'id>(env: &Env<'id>) -> Db<'id> {
+    let mut rwtxn = env.write_txn().unwrap();
+    let db = DatabaseUnique::create(env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    db
+}
+
+#[test]
+fn deferred_ops_do_not_run_after_abort() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let ran = Rc::new(Cell::new(false));
+    let mut rwtxn = env.write_txn().unwrap();
+    rwtxn.defer({
+        let db = db.clone();
+        let ran = ran.clone();
+        move |rwtxn| {
+            ran.set(true);
+            Ok(db.put(rwtxn, &1, &1)?)
+        }
+    });
+    rwtxn.abort();
+    assert!(!ran.get());
+    // Dropping a txn is the same as aborting it
+    let mut rwtxn = env.write_txn().unwrap();
+    rwtxn.defer({
+        let ran = ran.clone();
+        move |_| {
+            ran.set(true);
+            Ok(())
+        }
+    });
+    drop(rwtxn);
+    assert!(!ran.get());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), 0);
+}
+
+#[test]
+fn deferred_ops_do_not_run_after_rollback() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let ran = Rc::new(Cell::new(false));
+    let mut rwtxn = env.write_txn().unwrap();
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("a").unwrap();
+        savepoints.txn().defer({
+            let ran = ran.clone();
+            move |_| {
+                ran.set(true);
+                Ok(())
+            }
+        });
+        let () = savepoints.rollback_to("a").unwrap();
+        let () = savepoints.release("a").unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    assert!(!ran.get());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), 0);
+}
+
+#[test]
+fn deferred_ops_run_on_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rwtxn = env.write_txn().unwrap();
+    rwtxn.defer({
+        let db = db.clone();
+        move |rwtxn| Ok(db.put(rwtxn, &1, &1)?)
+    });
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, &1).unwrap(), Some(1));
+}
@@ -0,0 +1,155 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    comparator::CaseInsensitive,
+    diff::{diff_dup, diff_unique, DiffEntry},
+    make_guard,
+    types::{BigEndian, Str, U32},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Codec = U32<BigEndian>;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    opts
+}
+
+fn key(key: u32) -> Vec<u8> {
+    key.to_be_bytes().to_vec()
+}
+
+#[test]
+fn diff_unique_dbs() {
+    let dir_a = tempfile::tempdir().unwrap();
+    let dir_b = tempfile::tempdir().unwrap();
+    make_guard!(guard_a);
+    make_guard!(guard_b);
+    let env_a = unsafe { Env::open(guard_a, &opts(), dir_a.path()) }.unwrap();
+    let env_b = unsafe { Env::open(guard_b, &opts(), dir_b.path()) }.unwrap();
+    let mut rwtxn_a = env_a.write_txn().unwrap();
+    let mut rwtxn_b = env_b.write_txn().unwrap();
+    let a: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env_a, &mut rwtxn_a, "db").unwrap();
+    let b: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env_b, &mut rwtxn_b, "db").unwrap();
+    let disjoint_a: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env_a, &mut rwtxn_a, "disjoint").unwrap();
+    for k in 0..100 {
+        let () = a.put(&mut rwtxn_a, &k, &k).unwrap();
+        let () = b.put(&mut rwtxn_b, &k, &k).unwrap();
+        let () = disjoint_a.put(&mut rwtxn_a, &(k + 100), &k).unwrap();
+    }
+    let () = rwtxn_a.commit().unwrap();
+    let () = rwtxn_b.commit().unwrap();
+
+    // Identical
+    let rotxn_a = env_a.read_txn().unwrap();
+    let rotxn_b = env_b.read_txn().unwrap();
+    let diffs: Vec<_> = diff_unique(&rotxn_a, &a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs, vec![]);
+
+    // Disjoint
+    let diffs: Vec<_> = diff_unique(&rotxn_a, &disjoint_a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs.len(), 200);
+    assert!(diffs[..100]
+        .iter()
+        .zip(0..)
+        .all(|(diff, k)| *diff == DiffEntry::OnlyInB(key(k))));
+    assert!(diffs[100..]
+        .iter()
+        .zip(100..)
+        .all(|(diff, k)| *diff == DiffEntry::OnlyInA(key(k))));
+    drop(rotxn_b);
+
+    // One value differs
+    let mut rwtxn_b = env_b.write_txn().unwrap();
+    let () = b.put(&mut rwtxn_b, &42, &0).unwrap();
+    let () = rwtxn_b.commit().unwrap();
+    let rotxn_b = env_b.read_txn().unwrap();
+    let diffs: Vec<_> = diff_unique(&rotxn_a, &a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs, vec![DiffEntry::ValueDiffers(key(42))]);
+}
+
+#[test]
+fn diff_dup_dbs() {
+    let dir_a = tempfile::tempdir().unwrap();
+    let dir_b = tempfile::tempdir().unwrap();
+    make_guard!(guard_a);
+    make_guard!(guard_b);
+    let env_a = unsafe { Env::open(guard_a, &opts(), dir_a.path()) }.unwrap();
+    let env_b = unsafe { Env::open(guard_b, &opts(), dir_b.path()) }.unwrap();
+    let mut rwtxn_a = env_a.write_txn().unwrap();
+    let mut rwtxn_b = env_b.write_txn().unwrap();
+    let a: DatabaseDup<Codec, Codec> =
+        DatabaseDup::create(&env_a, &mut rwtxn_a, "db").unwrap();
+    let b: DatabaseDup<Codec, Codec> =
+        DatabaseDup::create(&env_b, &mut rwtxn_b, "db").unwrap();
+    for k in 0..10 {
+        for v in 0..3 {
+            let () = a.put(&mut rwtxn_a, &k, &v).unwrap();
+            let () = b.put(&mut rwtxn_b, &k, &v).unwrap();
+        }
+    }
+    let () = rwtxn_a.commit().unwrap();
+    let () = rwtxn_b.commit().unwrap();
+    let rotxn_a = env_a.read_txn().unwrap();
+    let rotxn_b = env_b.read_txn().unwrap();
+    let diffs: Vec<_> = diff_dup(&rotxn_a, &a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs, vec![]);
+    drop(rotxn_b);
+
+    // One more value for a single key
+    let mut rwtxn_b = env_b.write_txn().unwrap();
+    let () = b.put(&mut rwtxn_b, &7, &3).unwrap();
+    let () = rwtxn_b.commit().unwrap();
+    let rotxn_b = env_b.read_txn().unwrap();
+    let diffs: Vec<_> = diff_dup(&rotxn_a, &a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs, vec![DiffEntry::ValueDiffers(key(7))]);
+}
+
+/// Keys that are equal under the comparator are compared, even if they
+/// differ bytewise
+#[test]
+fn diff_with_comparator() {
+    let dir_a = tempfile::tempdir().unwrap();
+    let dir_b = tempfile::tempdir().unwrap();
+    make_guard!(guard_a);
+    make_guard!(guard_b);
+    let env_a = unsafe { Env::open(guard_a, &opts(), dir_a.path()) }.unwrap();
+    let env_b = unsafe { Env::open(guard_b, &opts(), dir_b.path()) }.unwrap();
+    let mut rwtxn_a = env_a.write_txn().unwrap();
+    let mut rwtxn_b = env_b.write_txn().unwrap();
+    let a: DatabaseUnique<Str, Str, CaseInsensitive> =
+        DatabaseUnique::create(&env_a, &mut rwtxn_a, "db").unwrap();
+    let b: DatabaseUnique<Str, Str, CaseInsensitive> =
+        DatabaseUnique::create(&env_b, &mut rwtxn_b, "db").unwrap();
+    let () = a.put(&mut rwtxn_a, "apple", "1").unwrap();
+    let () = a.put(&mut rwtxn_a, "Banana", "2").unwrap();
+    let () = b.put(&mut rwtxn_b, "APPLE", "1").unwrap();
+    let () = b.put(&mut rwtxn_b, "banana", "3").unwrap();
+    let () = rwtxn_a.commit().unwrap();
+    let () = rwtxn_b.commit().unwrap();
+    let rotxn_a = env_a.read_txn().unwrap();
+    let rotxn_b = env_b.read_txn().unwrap();
+    let diffs: Vec<_> = diff_unique(&rotxn_a, &a, &rotxn_b, &b)
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(diffs, vec![DiffEntry::ValueDiffers(b"Banana".to_vec())]);
+}
@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    dump::{self, error::Restore},
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+const UNIQUE_ENTRIES: u32 = 5000;
+const DUP_KEYS: u32 = 1000;
+const DUPS_PER_KEY: u32 = 5;
+
+type Unique<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+type Dup<'id> = DatabaseDup<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.map_size(64 * 1024 * 1024).max_dbs(2);
+    opts
+}
+
+/// Dump of an env with a unique db and a dup db
+fn source_dump(dir: &Path) -> Vec<u8> {
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let unique: Unique =
+        DatabaseUnique::create(&env, &mut rwtxn, "unique").unwrap();
+    let dup: Dup = DatabaseDup::create(&env, &mut rwtxn, "dup").unwrap();
+    for key in 0..UNIQUE_ENTRIES {
+        let () = unique.put(&mut rwtxn, &key, &(key * 3)).unwrap();
+    }
+    for key in 0..DUP_KEYS {
+        for value in 0..DUPS_PER_KEY {
+            let () = dup.put(&mut rwtxn, &key, &(key + value)).unwrap();
+        }
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let mut bytes = Vec::new();
+    let () = dump::dump_env(&env, &rotxn, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trip() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let bytes = source_dump(src_dir.path());
+    let dst_dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dst_dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = dump::restore_env(&env, &mut rwtxn, bytes.as_slice()).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let mut redumped = Vec::new();
+    let () = dump::dump_env(&env, &rotxn, &mut redumped).unwrap();
+    assert!(redumped == bytes);
+}
+
+/// Restored dbs can be opened with their original types and flags in
+/// another env
+#[test]
+fn cross_env_restore() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let bytes = source_dump(src_dir.path());
+    let dst_dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dst_dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = dump::restore_env(&env, &mut rwtxn, bytes.as_slice()).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let unique: Unique =
+        DatabaseUnique::create(&env, &mut rwtxn, "unique").unwrap();
+    let dup: Dup = DatabaseDup::create(&env, &mut rwtxn, "dup").unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(unique.len(&rotxn).unwrap(), u64::from(UNIQUE_ENTRIES));
+    let items: Vec<_> = unique.iter(&rotxn).unwrap().collect().unwrap();
+    assert!(items
+        .iter()
+        .zip(0..)
+        .all(|(&(key, value), expected)| key == expected && value == key * 3));
+    assert_eq!(dup.len(&rotxn).unwrap(), u64::from(DUP_KEYS * DUPS_PER_KEY));
+    let values: Vec<_> = dup.get(&rotxn, &7).unwrap().collect().unwrap();
+    assert_eq!(values, [7, 8, 9, 10, 11]);
+}
+
+fn restore(bytes: &[u8]) -> Result<(), Restore> {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    dump::restore_env(&env, &mut rwtxn, bytes)
+}
+
+#[test]
+fn truncated_or_corrupt_input() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let bytes = source_dump(src_dir.path());
+    for len in [0, 5, 9, 10, 13, 20, 100, bytes.len() / 2, bytes.len() - 1] {
+        let err = restore(&bytes[..len]).unwrap_err();
+        assert!(matches!(err, Restore::Truncated(_)), "{len}: {err:?}");
+    }
+
+    let mut corrupt = bytes.clone();
+    corrupt[0] = b'X';
+    let err = restore(&corrupt).unwrap_err();
+    assert!(matches!(err, Restore::InvalidMagic(_)), "{err:?}");
+
+    let mut corrupt = bytes.clone();
+    corrupt[8] = 0xff;
+    let err = restore(&corrupt).unwrap_err();
+    assert!(matches!(err, Restore::InvalidFormatVersion(_)), "{err:?}");
+
+    let mut corrupt = bytes.clone();
+    corrupt[9] = 0x07;
+    let err = restore(&corrupt).unwrap_err();
+    assert!(matches!(err, Restore::InvalidTag(_)), "{err:?}");
+    assert!(err.to_string().contains("offset 9"), "{err}");
+
+    // A name length of 4GiB must not be allocated up front
+    let mut corrupt = bytes.clone();
+    corrupt[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+    let err = restore(&corrupt).unwrap_err();
+    let Restore::Truncated(err) = err else {
+        panic!("expected Truncated, got {err:?}")
+    };
+    assert!(
+        err.to_string().contains(&format!("offset {}", bytes.len())),
+        "{err}"
+    );
+}
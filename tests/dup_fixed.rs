@@ -0,0 +1,73 @@
+use std::error::Error as _;
+
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    db::error::PutSource,
+    make_guard,
+    types::{BigEndian, U32, U64},
+    DatabaseDup, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseDup<'id, U32<BigEndian>, U64<BigEndian>>;
+type WideDb<'id> = DatabaseDup<'id, U32<BigEndian>, sneed::types::Bytes>;
+
+fn is_bad_val_size(err: &sneed::db::error::Put) -> bool {
+    matches!(
+        err.source()
+            .and_then(|source| source.downcast_ref::<PutSource>()),
+        Some(PutSource::Heed(heed::Error::Mdb(
+            heed::MdbError::BadValSize
+        )))
+    )
+}
+
+#[test]
+fn bulk_read_fixed_values() {
+    const VALUES: u64 = 1000;
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseDup::create_fixed(&env, &mut rwtxn, "fixed").unwrap();
+    for value in (0..VALUES).rev() {
+        let () = db.put(&mut rwtxn, &1, &value).unwrap();
+    }
+    let () = db.put(&mut rwtxn, &2, &VALUES).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let values = db.get_duplicates_bulk(&rotxn, &1).unwrap();
+    assert_eq!(values, (0..VALUES).collect::<Vec<_>>());
+    let expected: Vec<u64> = db.get(&rotxn, &1).unwrap().collect().unwrap();
+    assert_eq!(values, expected);
+    assert!(db.get_duplicates_bulk(&rotxn, &3).unwrap().is_empty());
+}
+
+#[test]
+fn reopened_fixed_db_checks_value_size() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: WideDb =
+        DatabaseDup::create_fixed(&env, &mut rwtxn, "fixed").unwrap();
+    let () = db.put(&mut rwtxn, &1, &[0; 8]).unwrap();
+    let () = rwtxn.commit().unwrap();
+    // Opening an existing fixed-size db with `create` keeps its flags
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: WideDb = DatabaseDup::create(&env, &mut rwtxn, "fixed").unwrap();
+    let err = db.put(&mut rwtxn, &1, &[0; 4]).unwrap_err();
+    assert!(is_bad_val_size(&err));
+    let () = db.put(&mut rwtxn, &1, &[1; 8]).unwrap();
+    // Opening an existing db with `create_fixed` does not make it
+    // fixed-size
+    let db: WideDb = DatabaseDup::create(&env, &mut rwtxn, "any").unwrap();
+    let () = db.put(&mut rwtxn, &1, &[0; 8]).unwrap();
+    let db: WideDb =
+        DatabaseDup::create_fixed(&env, &mut rwtxn, "any").unwrap();
+    let () = db.put(&mut rwtxn, &1, &[0; 4]).unwrap();
+    let () = rwtxn.commit().unwrap();
+}
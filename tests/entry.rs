@@ -0,0 +1,53 @@
+use sneed::{
+    db::Entry,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn inc(value: &mut u32) {
+    *value += 1;
+}
+
+#[test]
+fn modify_or_insert() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &1, &10).unwrap();
+
+    // Present
+    let entry = db.entry(&mut rwtxn, &1).unwrap();
+    assert!(matches!(entry, Entry::Occupied(_)));
+    let value = entry.and_modify(inc).unwrap().or_insert(0).unwrap();
+    assert_eq!(value, 11);
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(11));
+
+    // Absent
+    let entry = db.entry(&mut rwtxn, &2).unwrap();
+    assert!(matches!(entry, Entry::Vacant(_)));
+    assert_eq!(entry.get(), None);
+    let value = entry.and_modify(inc).unwrap().or_insert(0).unwrap();
+    assert_eq!(value, 0);
+    assert_eq!(db.try_get(&rwtxn, &2).unwrap(), Some(0));
+
+    // Counting from absent
+    for _ in 0..3 {
+        let _: u32 = db
+            .entry(&mut rwtxn, &3)
+            .unwrap()
+            .and_modify(inc)
+            .unwrap()
+            .or_insert(1)
+            .unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, &3).unwrap(), Some(3));
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use sneed::{env, make_guard, Env, EnvOpenOptions, ErrorClass};
+
+struct TagA;
+
+struct TagB;
+
+#[test]
+fn open_with_mismatched_tag_is_conflict() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard0);
+    let _env = unsafe {
+        Env::open_tagged::<TagA>(guard0, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap();
+    make_guard!(guard1);
+    let err = unsafe {
+        Env::open_tagged::<TagB>(guard1, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap_err();
+    assert!(matches!(err, env::error::Open::AlreadyOpen(_)));
+    assert_eq!(ErrorClass::of(&err), ErrorClass::Conflict);
+    let err = env::Error::from(err);
+    assert_eq!(err.status_hint(), ErrorClass::Conflict);
+}
+
+#[test]
+fn close_while_referenced_is_conflict() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap();
+    let other = env.clone();
+    let closing = env.close();
+    let err = closing.wait_timeout(Duration::from_millis(1)).unwrap_err();
+    assert_eq!(ErrorClass::of(&err), ErrorClass::Conflict);
+    let err = env::Error::from(err);
+    assert_eq!(err.status_hint(), ErrorClass::Conflict);
+    drop(other);
+    let () = closing.wait_timeout(Duration::from_secs(10)).unwrap();
+}
+
+#[test]
+fn reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard0);
+    let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap();
+    // Untagged envs can be opened more than once
+    make_guard!(guard1);
+    let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap();
+    make_guard!(guard2);
+    let err = unsafe {
+        Env::open_tagged::<TagA>(guard2, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap_err();
+    assert!(matches!(err, env::error::Open::AlreadyOpen(_)));
+    let other = env1.clone();
+    drop(env0);
+    drop(env1);
+    make_guard!(guard3);
+    let err = unsafe {
+        Env::open_tagged::<TagA>(guard3, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap_err();
+    assert!(matches!(err, env::error::Open::AlreadyOpen(_)));
+    drop(other);
+    // The tag is released once every clone is dropped
+    make_guard!(guard4);
+    let _env = unsafe {
+        Env::open_tagged::<TagA>(guard4, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap();
+    make_guard!(guard5);
+    let _env = unsafe {
+        Env::open_tagged::<TagA>(guard5, &EnvOpenOptions::new(), dir.path())
+    }
+    .unwrap();
+    make_guard!(guard6);
+    let err = unsafe { Env::open(guard6, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap_err();
+    assert!(matches!(err, env::error::Open::AlreadyOpen(_)));
+}
@@ -0,0 +1,92 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sneed::{
+    db::ExpiringDatabase,
+    make_guard,
+    types::{BigEndian, U32},
+    Env, EnvOpenOptions,
+};
+
+type Db<'id> = ExpiringDatabase<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn at(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(3);
+    opts
+}
+
+#[test]
+fn expiry_boundary() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = ExpiringDatabase::create(&env, &mut rwtxn, "db").unwrap();
+    let ttl = Duration::from_millis(100);
+    let () = db.put_with_ttl(&mut rwtxn, &1, &10, ttl, at(1000)).unwrap();
+    assert_eq!(db.try_get(&rwtxn, &1, at(1000)).unwrap(), Some(10));
+    assert_eq!(db.try_get(&rwtxn, &1, at(1099)).unwrap(), Some(10));
+    // Expired at the deadline
+    assert_eq!(db.try_get(&rwtxn, &1, at(1100)).unwrap(), None);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(1099)).unwrap(), 0);
+    assert_eq!(db.data().try_get(&rwtxn, &1).unwrap(), Some(10));
+    assert_eq!(db.purge_expired(&mut rwtxn, at(1100)).unwrap(), 1);
+    assert_eq!(db.data().try_get(&rwtxn, &1).unwrap(), None);
+}
+
+#[test]
+fn purge_is_idempotent() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = ExpiringDatabase::create(&env, &mut rwtxn, "db").unwrap();
+    for key in 0..10 {
+        let ttl = Duration::from_millis(u64::from(key) * 10);
+        let () = db.put_with_ttl(&mut rwtxn, &key, &key, ttl, at(0)).unwrap();
+    }
+    // Keys 0..=5 have expired
+    assert_eq!(db.purge_expired(&mut rwtxn, at(50)).unwrap(), 6);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(50)).unwrap(), 0);
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(db.purge_expired(&mut rwtxn, at(50)).unwrap(), 0);
+    assert_eq!(db.data().len(&rwtxn).unwrap(), 4);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(1000)).unwrap(), 4);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(1000)).unwrap(), 0);
+    assert_eq!(db.data().len(&rwtxn).unwrap(), 0);
+}
+
+#[test]
+fn overwrite_replaces_ttl() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = ExpiringDatabase::create(&env, &mut rwtxn, "db").unwrap();
+    let short = Duration::from_millis(10);
+    let long = Duration::from_millis(1000);
+    let () = db.put_with_ttl(&mut rwtxn, &1, &10, short, at(0)).unwrap();
+    // Extended
+    let () = db.put_with_ttl(&mut rwtxn, &1, &11, long, at(0)).unwrap();
+    assert_eq!(db.try_get(&rwtxn, &1, at(500)).unwrap(), Some(11));
+    // The old deadline no longer applies
+    assert_eq!(db.purge_expired(&mut rwtxn, at(500)).unwrap(), 0);
+    assert_eq!(db.try_get(&rwtxn, &1, at(500)).unwrap(), Some(11));
+    // Shortened
+    let () = db
+        .put_with_ttl(&mut rwtxn, &1, &12, short, at(500))
+        .unwrap();
+    assert_eq!(db.try_get(&rwtxn, &1, at(510)).unwrap(), None);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(510)).unwrap(), 1);
+    assert_eq!(db.purge_expired(&mut rwtxn, at(2000)).unwrap(), 0);
+    // Deleting removes the deadline
+    let () = db.put_with_ttl(&mut rwtxn, &2, &20, short, at(0)).unwrap();
+    assert!(db.delete(&mut rwtxn, &2).unwrap());
+    assert!(!db.delete(&mut rwtxn, &2).unwrap());
+    assert_eq!(db.purge_expired(&mut rwtxn, at(2000)).unwrap(), 0);
+}
@@ -0,0 +1,68 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    db::Indexed,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Codec = U32<BigEndian>;
+
+/// Index records by their last digit
+fn last_digit(record: &u32) -> Vec<u32> {
+    vec![record % 10]
+}
+
+#[test]
+fn index_tracks_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let primary: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env, &mut rwtxn, "primary").unwrap();
+    let index: DatabaseDup<Codec, Codec> =
+        DatabaseDup::create(&env, &mut rwtxn, "index").unwrap();
+    let indexed = Indexed::new(primary, index, last_digit);
+
+    // Insert
+    let () = indexed.put(&mut rwtxn, &1, &11).unwrap();
+    let () = indexed.put(&mut rwtxn, &2, &21).unwrap();
+    let () = indexed.put(&mut rwtxn, &3, &32).unwrap();
+    assert_eq!(
+        indexed.get_by_index(&rwtxn, &1).unwrap(),
+        vec![(1, 11), (2, 21)]
+    );
+    assert_eq!(indexed.get_by_index(&rwtxn, &2).unwrap(), vec![(3, 32)]);
+
+    // Field change
+    let () = indexed.put(&mut rwtxn, &2, &22).unwrap();
+    assert_eq!(indexed.get_by_index(&rwtxn, &1).unwrap(), vec![(1, 11)]);
+    assert_eq!(
+        indexed.get_by_index(&rwtxn, &2).unwrap(),
+        vec![(2, 22), (3, 32)]
+    );
+    assert!(indexed.update(&mut rwtxn, &1, |record| record + 2).unwrap());
+    assert!(!indexed.update(&mut rwtxn, &9, |record| record).unwrap());
+    assert_eq!(indexed.get_by_index(&rwtxn, &1).unwrap(), vec![]);
+    assert_eq!(indexed.get_by_index(&rwtxn, &3).unwrap(), vec![(1, 13)]);
+
+    // Delete
+    assert!(indexed.delete(&mut rwtxn, &3).unwrap());
+    assert!(!indexed.delete(&mut rwtxn, &3).unwrap());
+    assert_eq!(indexed.get_by_index(&rwtxn, &2).unwrap(), vec![(2, 22)]);
+    let () = rwtxn.commit().unwrap();
+
+    let rotxn = env.read_txn().unwrap();
+    let primary: Vec<_> =
+        indexed.primary().iter(&rotxn).unwrap().collect().unwrap();
+    assert_eq!(primary, vec![(1, 13), (2, 22)]);
+    let index = indexed.index();
+    assert_eq!(index.len(&rotxn).unwrap(), 2);
+    let ids: Vec<_> = index.get(&rotxn, &2).unwrap().collect().unwrap();
+    assert_eq!(ids, vec![2]);
+    let ids: Vec<_> = index.get(&rotxn, &3).unwrap().collect().unwrap();
+    assert_eq!(ids, vec![1]);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "jsonl")]
+
+use sneed::{
+    db::error::ImportJsonl,
+    make_guard,
+    types::{BigEndian, U32, U64},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(4);
+    opts
+}
+
+#[test]
+fn unique_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let src: DatabaseUnique<U32<BigEndian>, U64<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "src").unwrap();
+    let dst: DatabaseUnique<U32<BigEndian>, U64<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "dst").unwrap();
+    for key in [3, 1, 2] {
+        let () = src.put(&mut rwtxn, &key, &(u64::from(key) * 10)).unwrap();
+    }
+    let mut exported = Vec::new();
+    assert_eq!(src.export_jsonl(&rwtxn, &mut exported).unwrap(), 3);
+    assert_eq!(
+        String::from_utf8(exported.clone()).unwrap(),
+        "{\"key\":1,\"value\":10}\n\
+         {\"key\":2,\"value\":20}\n\
+         {\"key\":3,\"value\":30}\n"
+    );
+    let mut exported_hex = Vec::new();
+    assert_eq!(src.export_jsonl_hex(&rwtxn, &mut exported_hex).unwrap(), 3);
+    assert_eq!(
+        String::from_utf8(exported_hex)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap(),
+        "{\"key\":\"00000001\",\"value\":\"000000000000000a\"}"
+    );
+    assert_eq!(
+        dst.import_jsonl(&mut rwtxn, exported.as_slice()).unwrap(),
+        3
+    );
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let mut reexported = Vec::new();
+    let _lines: u64 = dst.export_jsonl(&rotxn, &mut reexported).unwrap();
+    assert_eq!(reexported, exported);
+}
+
+#[test]
+fn dup_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let src: DatabaseDup<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseDup::create(&env, &mut rwtxn, "src").unwrap();
+    let dst: DatabaseDup<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseDup::create(&env, &mut rwtxn, "dst").unwrap();
+    for (key, value) in [(1, 1), (1, 2), (1, 3), (2, 1)] {
+        let () = src.put(&mut rwtxn, &key, &value).unwrap();
+    }
+    let mut exported = Vec::new();
+    // One line per duplicate value
+    assert_eq!(src.export_jsonl(&rwtxn, &mut exported).unwrap(), 4);
+    assert_eq!(
+        dst.import_jsonl(&mut rwtxn, exported.as_slice()).unwrap(),
+        4
+    );
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let mut reexported = Vec::new();
+    let _lines: u64 = dst.export_jsonl(&rotxn, &mut reexported).unwrap();
+    assert_eq!(reexported, exported);
+}
+
+#[test]
+fn malformed_line() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U64<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let input = "{\"key\":1,\"value\":10}\n\
+                 \n\
+                 {\"key\":2,\"value\":\"twenty\"}\n\
+                 {\"key\":3,\"value\":30}\n";
+    let err = db.import_jsonl(&mut rwtxn, input.as_bytes()).unwrap_err();
+    assert!(matches!(err, ImportJsonl::Parse(_)), "{err:?}");
+    // Blank lines are counted
+    assert!(err.to_string().contains("line 3 "), "{err}");
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["kind"], "ParseJsonl");
+    assert_eq!(json["line"], 3);
+}
@@ -0,0 +1,33 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    make_guard,
+    types::{BigEndian, Str, U32},
+    DatabaseDup, Env, EnvOpenOptions,
+};
+
+#[test]
+fn key_counts() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseDup<Str, U32<BigEndian>> =
+        DatabaseDup::create(&env, &mut rwtxn, "db").unwrap();
+    let counts: Vec<(&str, u64)> =
+        db.key_counts(&rwtxn).unwrap().collect().unwrap();
+    assert_eq!(counts, []);
+    for (key, count) in [("a", 3), ("b", 1), ("c", 5)] {
+        for value in 0..count {
+            let () = db.put(&mut rwtxn, key, &value).unwrap();
+        }
+    }
+    // Duplicate items are not counted twice
+    let () = db.put(&mut rwtxn, "a", &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let counts: Vec<(&str, u64)> =
+        db.key_counts(&rotxn).unwrap().collect().unwrap();
+    assert_eq!(counts, [("a", 3), ("b", 1), ("c", 5)]);
+}
@@ -0,0 +1,134 @@
+//! Uses sneed without a direct heed dependency.
+//! heed is not a dependency of this crate, so naming any heed path here is
+//! a compile error.
+
+use std::{
+    borrow::Cow, cmp::Ordering, error::Error, io::Write as _, path::Path,
+};
+
+use sneed::{
+    comparator::{Comparator, DefaultComparator, Reverse},
+    db,
+    flags::{EnvFlags, PutFlags},
+    heed_error::{self, MdbError},
+    make_guard,
+    types::{BigEndian, BoxedError, Bytes, BytesDecode, BytesEncode, Str, U64},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions, UnitKey,
+};
+
+/// Codec for `u8`s, implemented without heed
+pub struct Byte;
+
+impl BytesEncode<'_> for Byte {
+    type EItem = u8;
+
+    fn bytes_encode(item: &u8) -> Result<Cow<'_, [u8]>, BoxedError> {
+        Ok(Cow::Owned(vec![*item]))
+    }
+}
+
+impl BytesDecode<'_> for Byte {
+    type DItem = u8;
+
+    fn bytes_decode(bytes: &[u8]) -> Result<u8, BoxedError> {
+        match bytes {
+            [byte] => Ok(*byte),
+            _ => Err("expected a single byte".into()),
+        }
+    }
+}
+
+/// Orders keys by length, then bytewise
+pub enum ByLength {}
+
+impl Comparator for ByLength {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+    }
+}
+
+/// Exercise each kind of heed type that appears in sneed's API
+pub fn run(path: &Path) -> Result<(), Box<dyn Error>> {
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ =
+        unsafe { opts.map_size(1 << 20).max_dbs(8).flags(EnvFlags::NO_TLS) };
+    let env = unsafe { Env::open(guard, &opts, path) }?;
+    let mut rwtxn = env.write_txn()?;
+    let counts: DatabaseUnique<
+        U64<BigEndian>,
+        Str,
+        Reverse<DefaultComparator>,
+    > = DatabaseUnique::create(&env, &mut rwtxn, "counts")?;
+    let () = counts.put(&mut rwtxn, &1, "one")?;
+    let () =
+        counts.put_with_flags(&mut rwtxn, PutFlags::NO_OVERWRITE, &2, "two")?;
+    let by_length: DatabaseUnique<Bytes, Byte, ByLength> =
+        DatabaseUnique::create(&env, &mut rwtxn, "by_length")?;
+    let () = by_length.put(&mut rwtxn, b"bb", &2)?;
+    let () = by_length.put(&mut rwtxn, b"a", &1)?;
+    assert_eq!(by_length.try_get(&rwtxn, b"a")?, Some(1));
+    let unit: DatabaseUnique<UnitKey, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "unit")?;
+    let () = unit.put_reserved(&mut rwtxn, &(), 4, |reserved| {
+        reserved.write_all(b"abcd")
+    })?;
+    let fixed: DatabaseDup<Byte, U64<BigEndian>> =
+        DatabaseDup::create_fixed(&env, &mut rwtxn, "fixed")?;
+    let () = fixed.put(&mut rwtxn, &0, &1)?;
+    let () = fixed.put(&mut rwtxn, &0, &2)?;
+    assert_eq!(fixed.get_duplicates_bulk(&rwtxn, &0)?, vec![1, 2]);
+    // Errors can be matched without naming heed
+    let large: DatabaseUnique<UnitKey, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "large")?;
+    let err = large.put(&mut rwtxn, &(), &vec![0; 2 << 20]).unwrap_err();
+    let source = err
+        .source()
+        .and_then(|source| source.downcast_ref::<db::error::PutSource>());
+    assert!(matches!(
+        source,
+        Some(db::error::PutSource::Heed(heed_error::Error::Mdb(
+            MdbError::MapFull
+        )))
+    ));
+    rwtxn.abort();
+    Ok(())
+}
+
+/// Serde codecs, from the `serde-bincode` and `serde-json` features
+#[cfg(all(feature = "serde-bincode", feature = "serde-json"))]
+pub fn run_serde(path: &Path) -> Result<(), Box<dyn Error>> {
+    use sneed::types::{SerdeBincode, SerdeJson};
+
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, path) }?;
+    let mut rwtxn = env.write_txn()?;
+    let bincode: DatabaseUnique<Str, SerdeBincode<(u32, String)>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "bincode")?;
+    let json: DatabaseUnique<Str, SerdeJson<Vec<u8>>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "json")?;
+    let () = bincode.put(&mut rwtxn, "a", &(1, "one".to_owned()))?;
+    let () = json.put(&mut rwtxn, "a", &vec![1, 2])?;
+    assert_eq!(bincode.try_get(&rwtxn, "a")?, Some((1, "one".to_owned())));
+    assert_eq!(json.try_get(&rwtxn, "a")?, Some(vec![1, 2]));
+    let () = rwtxn.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn run() {
+        let dir = tempfile::tempdir().unwrap();
+        super::run(dir.path()).unwrap();
+    }
+
+    #[cfg(all(feature = "serde-bincode", feature = "serde-json"))]
+    #[test]
+    fn run_serde() {
+        let dir = tempfile::tempdir().unwrap();
+        super::run_serde(dir.path()).unwrap();
+    }
+}
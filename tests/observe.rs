@@ -0,0 +1,182 @@
+#![cfg(feature = "observe")]
+
+use std::time::Duration;
+
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+fn create_db<'id>(env: &Env<'id>) -> Db<'id> {
+    let mut rwtxn = env.write_txn().unwrap();
+    let db = DatabaseUnique::create(env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    db
+}
+
+#[test]
+fn abort_does_not_notify_or_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rx = db.watch().clone();
+    let () = *rx.borrow_and_update();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &1, &1).unwrap();
+    rwtxn.abort();
+    assert!(!rx.has_changed().unwrap());
+    // Dropping a txn is the same as aborting it
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &2, &2).unwrap();
+    drop(rwtxn);
+    assert!(!rx.has_changed().unwrap());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), 0);
+    drop(rotxn);
+    // Committed writes do notify
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &3, &3).unwrap();
+    let () = rwtxn.commit().unwrap();
+    assert!(rx.has_changed().unwrap());
+}
+
+#[test]
+fn rolled_back_savepoint_does_not_notify() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rx = db.watch().clone();
+    let () = *rx.borrow_and_update();
+    let mut rwtxn = env.write_txn().unwrap();
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("a").unwrap();
+        let () = db.put(savepoints.txn(), &1, &1).unwrap();
+        let () = savepoints.rollback_to("a").unwrap();
+        let () = savepoints.release("a").unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    assert!(!rx.has_changed().unwrap());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn changed_after_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let write = async {
+        // Let `changed` start waiting first
+        let () = tokio::task::yield_now().await;
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &1, &1).unwrap();
+        let () = rwtxn.commit().unwrap();
+    };
+    let (changed, ()) =
+        tokio::join!(db.changed(Some(Duration::from_secs(10))), write);
+    assert!(changed.unwrap());
+}
+
+#[tokio::test]
+async fn changed_times_out() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let changed = db.changed(Some(Duration::from_millis(50))).await;
+    assert!(!changed.unwrap());
+}
+
+#[tokio::test]
+async fn changed_times_out_after_abort() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let write = async {
+        let () = tokio::task::yield_now().await;
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &1, &1).unwrap();
+        rwtxn.abort();
+    };
+    let (changed, ()) =
+        tokio::join!(db.changed(Some(Duration::from_millis(50))), write);
+    assert!(!changed.unwrap());
+}
+
+#[test]
+fn released_savepoint_then_abort_does_not_notify() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rx = db.watch().clone();
+    let () = *rx.borrow_and_update();
+    let mut rwtxn = env.write_txn().unwrap();
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("a").unwrap();
+        let () = db.put(savepoints.txn(), &1, &1).unwrap();
+        let () = savepoints.release("a").unwrap();
+    }
+    rwtxn.abort();
+    assert!(!rx.has_changed().unwrap());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), 0);
+}
+
+#[test]
+fn released_savepoint_then_commit_notifies() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rx = db.watch().clone();
+    let () = *rx.borrow_and_update();
+    let mut rwtxn = env.write_txn().unwrap();
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("a").unwrap();
+        let () = db.put(savepoints.txn(), &1, &1).unwrap();
+        let () = savepoints.release("a").unwrap();
+        // Not notified until the outermost txn is committed
+        assert!(!rx.has_changed().unwrap());
+    }
+    let () = rwtxn.commit().unwrap();
+    assert!(rx.has_changed().unwrap());
+}
+
+#[test]
+fn try_put_notifies_only_on_insert() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let db = create_db(&env);
+    let mut rx = db.watch().clone();
+    let () = *rx.borrow_and_update();
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(db.try_put(&mut rwtxn, &1, &1).unwrap(), None);
+    let () = rwtxn.commit().unwrap();
+    assert!(rx.has_changed().unwrap());
+    let () = *rx.borrow_and_update();
+    // The key exists, so nothing is written
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(db.try_put(&mut rwtxn, &1, &2).unwrap(), Some(1));
+    let () = rwtxn.commit().unwrap();
+    assert!(!rx.has_changed().unwrap());
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, &1).unwrap(), Some(1));
+}
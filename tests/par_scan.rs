@@ -0,0 +1,58 @@
+#![cfg(feature = "rayon")]
+
+use std::num::NonZeroUsize;
+
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    flags::EnvFlags,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+#[test]
+fn par_scan_matches_serial_scan() {
+    const ROWS: u32 = 100_000;
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ =
+        unsafe { opts.map_size(1 << 30).max_dbs(1).flags(EnvFlags::NO_TLS) };
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    for key in 0..ROWS {
+        let () = db.put(&mut rwtxn, &key, &key.wrapping_mul(7)).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let serial: Vec<(u32, u32)> = {
+        let rotxn = env.read_txn().unwrap();
+        db.iter(&rotxn).unwrap().collect().unwrap()
+    };
+    assert_eq!(serial.len(), ROWS as usize);
+    for partitions in [1, 2, 7, 16, 1000] {
+        let partitions = NonZeroUsize::new(partitions).unwrap();
+        let parallel = db.par_scan(&env, partitions, |k, v| (k, v)).unwrap();
+        assert_eq!(parallel, serial);
+    }
+}
+
+// The `stream` feature opens every env with `NO_TLS`
+#[cfg(not(feature = "stream"))]
+#[test]
+fn par_scan_requires_no_tls() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    let err = db
+        .par_scan(&env, NonZeroUsize::new(4).unwrap(), |k, v| (k, v))
+        .unwrap_err();
+    assert!(matches!(err, sneed::db::error::ParScan::RequiresNoTls(_)));
+}
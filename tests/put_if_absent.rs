@@ -0,0 +1,44 @@
+use std::error::Error as _;
+
+use sneed::{
+    db::error::PutSource,
+    make_guard,
+    types::{BigEndian, Bytes, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+#[test]
+fn put_if_absent_keeps_existing_value() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    assert!(db.put_if_absent(&mut rwtxn, &1, &10).unwrap());
+    assert!(!db.put_if_absent(&mut rwtxn, &1, &20).unwrap());
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(10));
+}
+
+#[test]
+fn put_if_absent_checks_value_size() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.map_size(1 << 20).max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let db = db.check_value_size(true);
+    let err = db
+        .put_if_absent(&mut rwtxn, &1, &vec![0; 2 << 20])
+        .unwrap_err();
+    let source = err
+        .source()
+        .and_then(|source| source.downcast_ref::<PutSource>());
+    assert!(matches!(source, Some(PutSource::ValueTooLarge(_))));
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), None);
+}
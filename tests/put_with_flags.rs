@@ -0,0 +1,107 @@
+use sneed::{
+    db::error,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseDup, DatabaseUnique, Env, EnvOpenOptions, PutFlags,
+};
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+#[test]
+fn put_with_flags_unique() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::empty(), &1, &10)
+        .unwrap();
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::empty(), &1, &11)
+        .unwrap();
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(11));
+    // NO_OVERWRITE
+    let err = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_OVERWRITE, &1, &12)
+        .unwrap_err();
+    assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(11));
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_OVERWRITE, &2, &20)
+        .unwrap();
+    // APPEND
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::APPEND, &3, &30)
+        .unwrap();
+    for key in [0, 3] {
+        let err = db
+            .put_with_flags(&mut rwtxn, PutFlags::APPEND, &key, &0)
+            .unwrap_err();
+        assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    }
+    // Dup-only flags
+    for flags in [PutFlags::NO_DUP_DATA, PutFlags::APPEND_DUP] {
+        let err = db.put_with_flags(&mut rwtxn, flags, &4, &40).unwrap_err();
+        assert!(
+            matches!(err, error::PutWithFlags::InvalidFlags(_)),
+            "{err:?}"
+        );
+    }
+    assert_eq!(db.try_get(&rwtxn, &4).unwrap(), None);
+    assert_eq!(db.len(&rwtxn).unwrap(), 3);
+}
+
+#[test]
+fn put_with_flags_dup() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseDup<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseDup::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::empty(), &1, &10)
+        .unwrap();
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::empty(), &1, &11)
+        .unwrap();
+    // NO_DUP_DATA
+    let err = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_DUP_DATA, &1, &10)
+        .unwrap_err();
+    assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_DUP_DATA, &1, &12)
+        .unwrap();
+    // NO_OVERWRITE
+    let err = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_OVERWRITE, &1, &13)
+        .unwrap_err();
+    assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::NO_OVERWRITE, &2, &20)
+        .unwrap();
+    // APPEND
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::APPEND, &3, &30)
+        .unwrap();
+    let err = db
+        .put_with_flags(&mut rwtxn, PutFlags::APPEND, &0, &0)
+        .unwrap_err();
+    assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    // APPEND_DUP
+    let () = db
+        .put_with_flags(&mut rwtxn, PutFlags::APPEND_DUP, &3, &31)
+        .unwrap();
+    let err = db
+        .put_with_flags(&mut rwtxn, PutFlags::APPEND_DUP, &3, &29)
+        .unwrap_err();
+    assert!(matches!(err, error::PutWithFlags::KeyExists(_)), "{err:?}");
+    assert_eq!(db.len(&rwtxn).unwrap(), 6);
+}
@@ -0,0 +1,118 @@
+use sneed::{
+    make_guard,
+    tools::Queue,
+    types::{BigEndian, U32, U64},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    opts
+}
+
+fn create_queue<'id>(env: &Env<'id>) -> Queue<'id, U32<BigEndian>> {
+    let mut rwtxn = env.write_txn().unwrap();
+    let db = DatabaseUnique::create(env, &mut rwtxn, "queue").unwrap();
+    let seq_db = DatabaseUnique::create(env, &mut rwtxn, "queue_seq").unwrap();
+    let () = rwtxn.commit().unwrap();
+    Queue::new(db, seq_db)
+}
+
+#[test]
+fn interleaved_push_pop() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let queue = create_queue(&env);
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), None);
+    let () = queue.push_back(&mut rwtxn, &1).unwrap();
+    let () = queue.push_back(&mut rwtxn, &2).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(1));
+    let () = queue.push_back(&mut rwtxn, &3).unwrap();
+    let () = rwtxn.commit().unwrap();
+    // Aborted pushes and pops are rolled back
+    let mut rwtxn = env.write_txn().unwrap();
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(2));
+    let () = queue.push_back(&mut rwtxn, &99).unwrap();
+    rwtxn.abort();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(queue.len(&rotxn).unwrap(), 2);
+    assert_eq!(queue.peek_front(&rotxn).unwrap(), Some(2));
+    drop(rotxn);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = queue.push_back(&mut rwtxn, &4).unwrap();
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(2));
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(3));
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(4));
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), None);
+    let () = rwtxn.commit().unwrap();
+}
+
+#[test]
+fn sequence_numbers_are_not_reused() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let queue = create_queue(&env);
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = queue.push_back(&mut rwtxn, &1).unwrap();
+    let () = queue.push_back(&mut rwtxn, &2).unwrap();
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(1));
+    assert_eq!(queue.pop_front(&mut rwtxn).unwrap(), Some(2));
+    // The queue is empty
+    let () = queue.push_back(&mut rwtxn, &3).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U64<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "queue").unwrap();
+    assert_eq!(db.first(&rwtxn).unwrap(), Some((2, 3)));
+}
+
+#[cfg(feature = "observe")]
+#[tokio::test]
+async fn consumer_waits_on_watch() {
+    use std::time::Duration;
+
+    const ITEMS: u32 = 5;
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let queue = create_queue(&env);
+    let mut rx = queue.watch().clone();
+    let () = *rx.borrow_and_update();
+    let consumer = async {
+        let mut received = Vec::new();
+        while received.len() < ITEMS as usize {
+            let mut rwtxn = env.write_txn().unwrap();
+            let item = queue.pop_front(&mut rwtxn).unwrap();
+            let () = rwtxn.commit().unwrap();
+            match item {
+                Some(item) => received.push(item),
+                None => {
+                    let () = tokio::time::timeout(
+                        Duration::from_secs(10),
+                        rx.changed(),
+                    )
+                    .await
+                    .expect("consumer should be notified")
+                    .unwrap();
+                }
+            }
+        }
+        received
+    };
+    let producer = async {
+        for item in 0..ITEMS {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            let mut rwtxn = env.write_txn().unwrap();
+            let () = queue.push_back(&mut rwtxn, &item).unwrap();
+            let () = rwtxn.commit().unwrap();
+        }
+    };
+    let (received, ()) = tokio::join!(consumer, producer);
+    assert_eq!(received, (0..ITEMS).collect::<Vec<_>>());
+}
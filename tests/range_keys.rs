@@ -0,0 +1,86 @@
+use std::{borrow::Cow, ops::Bound};
+
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    make_guard,
+    types::{BigEndian, BoxedError, U32},
+    BytesDecode, BytesEncode, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Range = (Bound<u32>, Bound<u32>);
+
+/// Values that must never be decoded
+struct PanicOnDecode;
+
+impl<'a> BytesEncode<'a> for PanicOnDecode {
+    type EItem = u32;
+
+    fn bytes_encode(item: &'a u32) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Owned(item.to_be_bytes().to_vec()))
+    }
+}
+
+impl BytesDecode<'_> for PanicOnDecode {
+    type DItem = u32;
+
+    fn bytes_decode(_bytes: &[u8]) -> Result<u32, BoxedError> {
+        panic!("values should not be decoded")
+    }
+}
+
+#[test]
+fn range_keys_bounds() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, PanicOnDecode> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    for key in (10..=100).step_by(10) {
+        let () = db.put(&mut rwtxn, &key, &key).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let keys = |range: &Range| -> Vec<u32> {
+        db.range_keys(&rotxn, range).unwrap().collect().unwrap()
+    };
+    let rev_keys = |range: &Range| -> Vec<u32> {
+        db.rev_range_keys(&rotxn, range).unwrap().collect().unwrap()
+    };
+    let cases: [(Range, Vec<u32>); 8] = [
+        (
+            (Bound::Unbounded, Bound::Unbounded),
+            (10..=100).step_by(10).collect(),
+        ),
+        ((Bound::Included(30), Bound::Excluded(60)), vec![30, 40, 50]),
+        (
+            (Bound::Included(30), Bound::Included(60)),
+            vec![30, 40, 50, 60],
+        ),
+        ((Bound::Excluded(30), Bound::Included(60)), vec![40, 50, 60]),
+        ((Bound::Included(25), Bound::Excluded(55)), vec![30, 40, 50]),
+        ((Bound::Unbounded, Bound::Excluded(30)), vec![10, 20]),
+        ((Bound::Excluded(80), Bound::Unbounded), vec![90, 100]),
+        ((Bound::Included(41), Bound::Included(49)), vec![]),
+    ];
+    for (range, expected) in cases {
+        assert_eq!(keys(&range), expected, "{range:?}");
+        let mut expected_rev = expected;
+        expected_rev.reverse();
+        assert_eq!(rev_keys(&range), expected_rev, "{range:?}");
+    }
+    let keys: Vec<u32> = db
+        .range_keys(&rotxn, &(20..=30))
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(keys, [20, 30]);
+    let keys: Vec<u32> = db
+        .rev_range_keys(&rotxn, &(..20))
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(keys, [10]);
+}
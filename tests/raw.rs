@@ -0,0 +1,46 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    codec::Raw,
+    make_guard,
+    types::{BigEndian, Str, U32},
+    BytesDecode as _, BytesEncode as _, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+#[test]
+fn raw_codec_passes_bytes_through() {
+    let bytes = [0u8, 1, 0xff];
+    let encoded = Raw::bytes_encode(&bytes).unwrap();
+    assert_eq!(&*encoded, bytes);
+    assert_eq!(Raw::bytes_decode(&encoded).unwrap(), bytes);
+}
+
+#[test]
+fn open_raw_db_reads_typed_db() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, Str> =
+        DatabaseUnique::create(&env, &mut rwtxn, "typed").unwrap();
+    let () = db.put(&mut rwtxn, &1, "one").unwrap();
+    let () = db.put(&mut rwtxn, &0x0102_0304, "big").unwrap();
+    let () = rwtxn.commit().unwrap();
+
+    let rotxn = env.read_txn().unwrap();
+    assert!(env.open_raw_db(&rotxn, "missing").unwrap().is_none());
+    let raw = env.open_raw_db(&rotxn, "typed").unwrap().unwrap();
+    assert_eq!(
+        raw.try_get(&rotxn, &[1, 2, 3, 4]).unwrap(),
+        Some(b"big".as_slice())
+    );
+    let items: Vec<_> = raw.iter(&rotxn).unwrap().collect().unwrap();
+    assert_eq!(
+        items,
+        [
+            ([0, 0, 0, 1].as_slice(), b"one".as_slice()),
+            ([1, 2, 3, 4].as_slice(), b"big".as_slice()),
+        ]
+    );
+}
@@ -0,0 +1,131 @@
+use std::{borrow::Cow, num::NonZeroUsize, ops::ControlFlow};
+
+use sneed::{
+    comparator::CaseInsensitive,
+    make_guard,
+    types::{BigEndian, BoxedError, U32},
+    BytesDecode, BytesEncode, DatabaseUnique, Env, EnvOpenOptions,
+};
+
+/// Strings, decoded as owned strings
+struct OwnedStr;
+
+impl<'a> BytesEncode<'a> for OwnedStr {
+    type EItem = str;
+
+    fn bytes_encode(item: &'a str) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Borrowed(item.as_bytes()))
+    }
+}
+
+impl BytesDecode<'_> for OwnedStr {
+    type DItem = String;
+
+    fn bytes_decode(bytes: &[u8]) -> Result<String, BoxedError> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+}
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    opts
+}
+
+fn chunk_size(n: usize) -> NonZeroUsize {
+    NonZeroUsize::new(n).unwrap()
+}
+
+#[test]
+fn scan_with_concurrent_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    // Even keys only
+    for key in (0..200).step_by(2) {
+        let () = db.put(&mut rwtxn, &key, &key).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+
+    let mut scanned = Vec::new();
+    let stats = db
+        .scan_chunked(&env, chunk_size(10), |chunk| {
+            scanned.extend(chunk.iter().map(|(key, _)| *key));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(stats.chunks, 10);
+    assert_eq!(stats.rows, 100);
+    assert_eq!(stats.non_increasing_keys, 0);
+    assert!(!stats.concurrent_writes);
+    assert_eq!(scanned, (0..200).step_by(2).collect::<Vec<_>>());
+
+    let mut scanned = Vec::new();
+    let stats = db
+        .scan_chunked(&env, chunk_size(10), |chunk| {
+            scanned.extend(chunk.iter().map(|(key, _)| *key));
+            if scanned.len() == 10 {
+                let mut rwtxn = env.write_txn().unwrap();
+                // Before the resume point, so skipped
+                let () = db.put(&mut rwtxn, &3, &3).unwrap();
+                // After the resume point, so scanned
+                let () = db.put(&mut rwtxn, &1001, &1001).unwrap();
+                // Not yet scanned, so skipped
+                assert!(db.delete(&mut rwtxn, &50).unwrap());
+                let () = rwtxn.commit().unwrap();
+            }
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert!(stats.concurrent_writes);
+    assert_eq!(stats.non_increasing_keys, 0);
+    assert_eq!(stats.rows, 100);
+    let expected: Vec<u32> = (0..200)
+        .step_by(2)
+        .filter(|key| *key != 50)
+        .chain([1001])
+        .collect();
+    assert_eq!(scanned, expected);
+
+    // Stops early
+    let stats = db
+        .scan_chunked(&env, chunk_size(10), |_| ControlFlow::Break(()))
+        .unwrap();
+    assert_eq!((stats.chunks, stats.rows), (1, 10));
+}
+
+/// A key that is replaced by an equal key under the comparator, between
+/// chunks, is scanned twice
+#[test]
+fn scan_detects_non_increasing_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<OwnedStr, OwnedStr, CaseInsensitive> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    for key in ["a", "b", "c"] {
+        let () = db.put(&mut rwtxn, key, key).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let mut scanned = Vec::new();
+    let stats = db
+        .scan_chunked(&env, chunk_size(1), |chunk| {
+            let (key, _) = &chunk[0];
+            scanned.push(key.clone());
+            if key == "a" {
+                let mut rwtxn = env.write_txn().unwrap();
+                assert!(db.delete(&mut rwtxn, "a").unwrap());
+                let () = db.put(&mut rwtxn, "A", "A").unwrap();
+                let () = rwtxn.commit().unwrap();
+            }
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(scanned, ["a", "A", "b", "c"]);
+    assert_eq!(stats.non_increasing_keys, 1);
+    assert!(stats.concurrent_writes);
+}
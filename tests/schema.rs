@@ -0,0 +1,61 @@
+use sneed::{
+    databases, make_guard,
+    types::{BigEndian, Str, U64},
+    Env, EnvOpenOptions,
+};
+
+databases! {
+    struct Schema {
+        users: DatabaseUnique<U64<BigEndian>, Str> = "users",
+        events: DatabaseDup<U64<BigEndian>, Str> = "events",
+    }
+}
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    opts
+}
+
+#[test]
+fn open_schema_twice() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let schema = Schema::open_or_create(&env).unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = schema.users().put(&mut rwtxn, &0, "alice").unwrap();
+    let () = schema.events().put(&mut rwtxn, &0, "signup").unwrap();
+    let () = schema.events().put(&mut rwtxn, &0, "login").unwrap();
+    let () = rwtxn.commit().unwrap();
+    // Opening again must open the existing dbs, and create nothing new
+    let schema = Schema::open_or_create(&env).unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(schema.users().try_get(&rotxn, &0).unwrap(), Some("alice"));
+    assert_eq!(schema.events().len(&rotxn).unwrap(), 2);
+}
+
+#[test]
+fn reopen_schema_after_env_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        make_guard!(guard);
+        let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+        let schema = Schema::open_or_create(&env).unwrap();
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = schema.users().put(&mut rwtxn, &0, "alice").unwrap();
+        let () = rwtxn.commit().unwrap();
+    }
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let schema = Schema::open_or_create(&env).unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(schema.users().try_get(&rotxn, &0).unwrap(), Some("alice"));
+    assert_eq!(schema.events().len(&rotxn).unwrap(), 0);
+}
+
+#[test]
+fn unsupported_db_type() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/schema/*.rs");
+}
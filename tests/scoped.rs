@@ -0,0 +1,87 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    db::ScopedDatabase,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions, RoTxn,
+};
+
+type Scope<'id> = ScopedDatabase<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn items<'id>(scope: &Scope<'id>, rotxn: &RoTxn<'_, 'id>) -> Vec<(u32, u32)> {
+    scope.iter(rotxn).unwrap().collect().unwrap()
+}
+
+#[test]
+fn scopes_are_isolated() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let a = db.scoped(b"a");
+    let b = db.scoped(b"b");
+    // Prefix without a successor, so the view extends to the end of the db
+    let last = db.scoped(&[0xff]);
+    for key in 1..=3 {
+        let () = a.put(&mut rwtxn, &key, &(10 + key)).unwrap();
+        let () = b.put(&mut rwtxn, &(key * 2), &(20 + key)).unwrap();
+        let () = last.put(&mut rwtxn, &key, &(30 + key)).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(items(&a, &rotxn), [(1, 11), (2, 12), (3, 13)]);
+    assert_eq!(items(&b, &rotxn), [(2, 21), (4, 22), (6, 23)]);
+    assert_eq!(items(&last, &rotxn), [(1, 31), (2, 32), (3, 33)]);
+    for scope in [&a, &b, &last] {
+        assert_eq!(scope.len(&rotxn).unwrap(), 3);
+    }
+    assert_eq!(db.len(&rotxn).unwrap(), 9);
+    assert_eq!(a.try_get(&rotxn, &4).unwrap(), None);
+    assert_eq!(b.try_get(&rotxn, &4).unwrap(), Some(22));
+    assert_eq!(a.first(&rotxn).unwrap(), Some((1, 11)));
+    assert_eq!(a.last(&rotxn).unwrap(), Some((3, 13)));
+    assert_eq!(b.first(&rotxn).unwrap(), Some((2, 21)));
+    assert_eq!(b.last(&rotxn).unwrap(), Some((6, 23)));
+    let range: Vec<_> = b.range(&rotxn, &(3..)).unwrap().collect().unwrap();
+    assert_eq!(range, [(4, 22), (6, 23)]);
+    let range: Vec<_> = a.range(&rotxn, &(..=2)).unwrap().collect().unwrap();
+    assert_eq!(range, [(1, 11), (2, 12)]);
+    drop(rotxn);
+
+    // Deleting through one scope does not affect the other
+    let mut rwtxn = env.write_txn().unwrap();
+    assert!(!a.delete(&mut rwtxn, &4).unwrap());
+    assert!(a.delete(&mut rwtxn, &2).unwrap());
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(items(&a, &rotxn), [(1, 11), (3, 13)]);
+    assert_eq!(items(&b, &rotxn), [(2, 21), (4, 22), (6, 23)]);
+}
+
+#[test]
+fn nested_scopes_compose() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let nested = db.scoped(b"a").scoped(b"x");
+    let flat = db.scoped(b"ax");
+    let sibling = db.scoped(b"a").scoped(b"y");
+    assert_eq!(nested.prefix(), b"ax");
+    let () = nested.put(&mut rwtxn, &1, &1).unwrap();
+    let () = sibling.put(&mut rwtxn, &1, &2).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(items(&nested, &rotxn), [(1, 1)]);
+    assert_eq!(items(&flat, &rotxn), [(1, 1)]);
+    assert_eq!(items(&sibling, &rotxn), [(1, 2)]);
+}
@@ -0,0 +1,46 @@
+use sneed::{
+    db::SearchResult,
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+#[test]
+fn search() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    for key in [10, 20, 30] {
+        let () = db.put(&mut rwtxn, &key, &key).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let search = |target| db.search(&rotxn, &target).unwrap();
+    assert_eq!(search(20), SearchResult::Found(20));
+    assert_eq!(
+        search(25),
+        SearchResult::NotFound {
+            prev: Some(20),
+            next: Some(30)
+        }
+    );
+    assert_eq!(
+        search(5),
+        SearchResult::NotFound {
+            prev: None,
+            next: Some(10)
+        }
+    );
+    assert_eq!(
+        search(35),
+        SearchResult::NotFound {
+            prev: Some(30),
+            next: None
+        }
+    );
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "serde")]
+
+use sneed::{make_guard, types::Bytes, DatabaseUnique, Env, EnvOpenOptions};
+
+#[test]
+fn put_error_json() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<Bytes, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    // Keys are limited to 511 bytes
+    let key = [0xab; 512];
+    let err = db.put(&mut rwtxn, &key, &[0xcd, 0xef]).unwrap_err();
+    let json = serde_json::to_value(&err).unwrap();
+    let fields = json.as_object().unwrap();
+    assert_eq!(
+        fields.keys().collect::<Vec<_>>(),
+        [
+            "db_name",
+            "db_path",
+            "key_bytes",
+            "kind",
+            "source",
+            "value_bytes"
+        ]
+    );
+    assert_eq!(json["kind"], "Put");
+    assert_eq!(json["db_name"], "db");
+    assert_eq!(json["db_path"], dir.path().to_str().unwrap());
+    assert_eq!(json["key_bytes"], hex(&key));
+    assert_eq!(json["value_bytes"], "cdef");
+    // The heed error is only serialized by its `Display` impl
+    assert!(json["source"].is_string());
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sneed::{
+    flags::EnvFlags,
+    make_guard,
+    types::{BigEndian, Bytes, U32},
+    DatabaseUnique, Env, EnvOpenOptions, UnitKey,
+};
+
+/// Size of the filler db, to slow the snapshot down
+const FILLER_BYTES: usize = 64 * 1024 * 1024;
+
+fn opts() -> EnvOpenOptions {
+    let mut opts = EnvOpenOptions::new();
+    // Syncing is not needed for the test, and slows the writer down
+    let _ = unsafe {
+        opts.map_size(4 * FILLER_BYTES)
+            .max_dbs(3)
+            .flags(EnvFlags::NO_SYNC)
+    };
+    opts
+}
+
+/// Writes committed while a snapshot is being copied must not appear in
+/// it, and must not be blocked by it
+#[test]
+fn snapshot_excludes_concurrent_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &opts(), dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let filler: DatabaseUnique<U32<BigEndian>, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "filler").unwrap();
+    // Number of writer commits, at key 0
+    let counter: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "counter").unwrap();
+    // One entry for each writer commit
+    let log: DatabaseUnique<U32<BigEndian>, UnitKey> =
+        DatabaseUnique::create(&env, &mut rwtxn, "log").unwrap();
+    let value = vec![0xab; 1024];
+    for key in 0..(FILLER_BYTES / value.len()) as u32 {
+        let () = filler.put(&mut rwtxn, &key, &value).unwrap();
+    }
+    let () = counter.put(&mut rwtxn, &0, &0).unwrap();
+    let initial_seq = rwtxn.commit_with_seq().unwrap();
+    let stop = AtomicBool::new(false);
+    let (info, seq_before, seq_after) = std::thread::scope(|scope| {
+        let writer = scope.spawn(|| {
+            while !stop.load(Ordering::SeqCst) {
+                let mut rwtxn = env.write_txn().unwrap();
+                let count = counter.get(&rwtxn, &0).unwrap() + 1;
+                let () = counter.put(&mut rwtxn, &0, &count).unwrap();
+                let () = log.put(&mut rwtxn, &count, &()).unwrap();
+                let () = rwtxn.commit().unwrap();
+            }
+        });
+        let seq_before = env.last_commit_seq();
+        let info = env.snapshot_to_dir(snapshot_dir.path()).unwrap();
+        let seq_after = env.last_commit_seq();
+        stop.store(true, Ordering::SeqCst);
+        let () = writer.join().unwrap();
+        (info, seq_before, seq_after)
+    });
+    assert!(info.commit_seq >= seq_before);
+
+    make_guard!(snapshot_guard);
+    let snapshot =
+        unsafe { Env::open(snapshot_guard, &opts(), snapshot_dir.path()) }
+            .unwrap();
+    let rotxn = snapshot.read_txn().unwrap();
+    let counter = snapshot.open_raw_db(&rotxn, "counter").unwrap().unwrap();
+    let log = snapshot.open_raw_db(&rotxn, "log").unwrap().unwrap();
+    let count = u32::from_be_bytes(
+        counter
+            .get(&rotxn, &0u32.to_be_bytes())
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    );
+    // The snapshot is a single consistent state
+    assert_eq!(log.len(&rotxn).unwrap(), u64::from(count));
+    // Every write in the snapshot was committed by the time the snapshot
+    // finished, and includes at least the writes committed before the
+    // manifest's commit seq
+    let snapshot_seq = initial_seq + u64::from(count);
+    assert!(snapshot_seq >= info.commit_seq);
+    // The writer was not blocked, and its writes during the snapshot were
+    // excluded
+    assert!(
+        seq_after > snapshot_seq,
+        "no writes committed during the snapshot"
+    );
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "stream")]
+
+use std::{future::poll_fn, num::NonZeroUsize, pin::Pin};
+
+use futures_core::Stream;
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+const ITEMS: u32 = 1000;
+
+async fn next<S>(stream: &mut S) -> Option<S::Item>
+where
+    S: Stream + Unpin,
+{
+    poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+}
+
+/// The stream reads a snapshot as of its read txn, and does not block
+/// writers while it is open
+#[tokio::test]
+async fn stream_while_writer_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    // Pages read by the stream cannot be reused until it ends
+    let _ = opts.map_size(64 * 1024 * 1024).max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    for key in 0..ITEMS {
+        let () = db.put(&mut rwtxn, &key, &key).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let mut stream = db.iter_stream(
+        env.static_read_txn().unwrap(),
+        NonZeroUsize::new(16).unwrap(),
+    );
+    let mut items = Vec::new();
+    while let Some(item) = next(&mut stream).await {
+        let (key, value) = item.unwrap();
+        items.push((key, value));
+        // Overwrite read items, and append new items, while the stream is
+        // open
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &key, &(value + 1)).unwrap();
+        let () = db.put(&mut rwtxn, &(ITEMS + key), &key).unwrap();
+        let () = rwtxn.commit().unwrap();
+    }
+    let expected: Vec<_> = (0..ITEMS).map(|key| (key, key)).collect();
+    assert_eq!(items, expected);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.len(&rotxn).unwrap(), u64::from(2 * ITEMS));
+}
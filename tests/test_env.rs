@@ -0,0 +1,61 @@
+#![cfg(feature = "test-utils")]
+
+use sneed::{
+    make_guard,
+    test_utils::TestEnv,
+    types::{BigEndian, U32},
+    DatabaseUnique,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+#[test]
+fn create_db_and_round_trip() {
+    make_guard!(guard);
+    let env = TestEnv::new(guard).unwrap();
+    let db: Db = env.create_db("db").unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &1, &2).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, &1).unwrap(), Some(2));
+}
+
+#[test]
+fn dir_removed_on_drop() {
+    make_guard!(guard);
+    let env = TestEnv::new(guard).unwrap();
+    let _db: Db = env.create_db("db").unwrap();
+    let dir = env.dir().to_owned();
+    assert!(dir.join("data.mdb").exists());
+    drop(env);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn envs_are_independent() {
+    make_guard!(guard_a);
+    make_guard!(guard_b);
+    let env_a = TestEnv::new(guard_a).unwrap();
+    let env_b = TestEnv::new(guard_b).unwrap();
+    assert_ne!(env_a.dir(), env_b.dir());
+    let db_a: Db = env_a.create_db("db").unwrap();
+    let db_b: Db = env_b.create_db("db").unwrap();
+    let mut rwtxn = env_a.write_txn().unwrap();
+    let () = db_a.put(&mut rwtxn, &1, &1).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env_b.read_txn().unwrap();
+    assert_eq!(db_b.len(&rotxn).unwrap(), 0);
+}
+
+#[test]
+fn max_dbs() {
+    make_guard!(guard);
+    let env = TestEnv::new(guard).unwrap();
+    for i in 0..32 {
+        let _db: Db = env.create_db(&format!("db{i}")).unwrap();
+    }
+    assert!(env
+        .create_db::<U32<BigEndian>, U32<BigEndian>>("db32")
+        .is_err());
+}
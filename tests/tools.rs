@@ -0,0 +1,100 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    comparator::DefaultComparator,
+    make_guard, tools,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+#[test]
+fn rename_db_moves_every_item() {
+    // More items than are moved in each chunk
+    const ITEMS: u32 = 3000;
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let old: Db = DatabaseUnique::create(&env, &mut rwtxn, "old").unwrap();
+    for key in 0..ITEMS {
+        let () = old.put(&mut rwtxn, &key, &(key * 2)).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let moved =
+        tools::rename_db::<U32<BigEndian>, U32<BigEndian>, DefaultComparator>(
+            &env, &mut rwtxn, "old", "new",
+        )
+        .unwrap();
+    assert_eq!(moved, u64::from(ITEMS));
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let new: Db = DatabaseUnique::create(&env, &mut rwtxn, "new").unwrap();
+    let items: Vec<(u32, u32)> = new.iter(&rwtxn).unwrap().collect().unwrap();
+    let expected: Vec<(u32, u32)> =
+        (0..ITEMS).map(|key| (key, key * 2)).collect();
+    assert_eq!(items, expected);
+    // The old db is left empty, and the old handle remains valid
+    assert_eq!(old.len(&rwtxn).unwrap(), 0);
+}
+
+#[test]
+fn rename_missing_db_is_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let err =
+        tools::rename_db::<U32<BigEndian>, U32<BigEndian>, DefaultComparator>(
+            &env, &mut rwtxn, "missing", "new",
+        )
+        .unwrap_err();
+    assert!(matches!(err, tools::error::RenameDb::DbNotFound(_)));
+}
+
+#[test]
+fn copy_db_range_between_envs() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let dst_dir = tempfile::tempdir().unwrap();
+    make_guard!(src_guard);
+    make_guard!(dst_guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let src_env =
+        unsafe { Env::open(src_guard, &opts, src_dir.path()) }.unwrap();
+    let dst_env =
+        unsafe { Env::open(dst_guard, &opts, dst_dir.path()) }.unwrap();
+    let mut rwtxn = src_env.write_txn().unwrap();
+    let src: Db = DatabaseUnique::create(&src_env, &mut rwtxn, "db").unwrap();
+    for key in 0..300 {
+        let () = src.put(&mut rwtxn, &key, &(key * 2)).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = dst_env.write_txn().unwrap();
+    let dst: Db = DatabaseUnique::create(&dst_env, &mut rwtxn, "db").unwrap();
+    // Overwritten by the copy
+    let () = dst.put(&mut rwtxn, &150, &0).unwrap();
+    // Outside the range, so left as is
+    let () = dst.put(&mut rwtxn, &250, &0).unwrap();
+    let () = rwtxn.commit().unwrap();
+
+    let src_rotxn = src_env.read_txn().unwrap();
+    let mut rwtxn = dst_env.write_txn().unwrap();
+    let copied =
+        tools::copy_db_range(&src, &src_rotxn, &dst, &mut rwtxn, &(100..200))
+            .unwrap();
+    assert_eq!(copied, 100);
+    let () = rwtxn.commit().unwrap();
+    let rotxn = dst_env.read_txn().unwrap();
+    let items: Vec<(u32, u32)> = dst.iter(&rotxn).unwrap().collect().unwrap();
+    let expected: Vec<(u32, u32)> = (100..200)
+        .map(|key| (key, key * 2))
+        .chain([(250, 0)])
+        .collect();
+    assert_eq!(items, expected);
+}
@@ -0,0 +1,107 @@
+#![cfg(any(feature = "debug-txn", feature = "tracing"))]
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+
+/// Log output captured by [`capture`]
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run `f`, capturing logs emitted on the current thread
+fn capture<F>(f: F) -> String
+where
+    F: FnOnce(),
+{
+    let logs = Logs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer({
+            let logs = logs.clone();
+            move || logs.clone()
+        })
+        .finish();
+    let () = tracing::subscriber::with_default(subscriber, f);
+    logs.contents()
+}
+
+#[cfg(feature = "debug-txn")]
+#[test]
+fn dirty_drop_warns() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<UnitKey, UnitKey> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    let warning = "dropped without commit or abort";
+
+    let logs = capture(|| {
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &(), &()).unwrap();
+        drop(rwtxn);
+    });
+    assert!(logs.contains("WARN"), "{logs}");
+    assert!(logs.contains(warning), "{logs}");
+    assert!(logs.contains(&dir.path().display().to_string()), "{logs}");
+
+    let logs = capture(|| {
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &(), &()).unwrap();
+        let () = rwtxn.commit().unwrap();
+        let mut rwtxn = env.write_txn().unwrap();
+        let () = db.put(&mut rwtxn, &(), &()).unwrap();
+        rwtxn.abort();
+        // Txns without writes are not dirty
+        let rwtxn = env.write_txn().unwrap();
+        drop(rwtxn);
+    });
+    assert!(!logs.contains(warning), "{logs}");
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn commit_event_has_db_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let logs = capture(|| {
+        let mut rwtxn = env.write_txn().unwrap();
+        let db: DatabaseUnique<UnitKey, UnitKey> =
+            DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+        let () = db.put(&mut rwtxn, &(), &()).unwrap();
+        let () = rwtxn.commit().unwrap();
+    });
+    let committed = logs
+        .lines()
+        .find(|line| line.contains(" committed writes="))
+        .unwrap_or_else(|| panic!("no committed event in {logs}"));
+    let db_dir = format!("db_dir={}", dir.path().display());
+    assert!(committed.contains("write_txn{"), "{committed}");
+    assert!(committed.contains(&db_dir), "{committed}");
+}
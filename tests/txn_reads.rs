@@ -0,0 +1,105 @@
+//! Reads through write txns and savepoints.
+//! LMDB is called through FFI, which Miri does not support, so these tests
+//! are ignored under Miri. The layout invariants that the txn wrappers rely
+//! on are checked at compile time, including in Miri builds.
+
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions, Txn,
+};
+
+type Db<'id> = DatabaseUnique<'id, U32<BigEndian>, U32<BigEndian>>;
+
+fn read_all<'env, 'id, Tx>(db: &Db<'id>, txn: &Tx) -> Vec<(u32, u32)>
+where
+    Tx: Txn<'env, 'id>,
+{
+    db.iter(txn).unwrap().collect().unwrap()
+}
+
+#[cfg_attr(miri, ignore = "LMDB FFI is not supported by Miri")]
+#[test]
+fn write_txn_reads_own_writes() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = rwtxn.commit().unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &1, &10).unwrap();
+    assert_eq!(db.try_get(&rwtxn, &1).unwrap(), Some(10));
+    assert_eq!(read_all(&db, &rwtxn), vec![(1, 10)]);
+    let () = db.put(&mut rwtxn, &2, &20).unwrap();
+    let () = db.put(&mut rwtxn, &1, &11).unwrap();
+    assert_eq!(read_all(&db, &rwtxn), vec![(1, 11), (2, 20)]);
+    // Uncommitted writes are not visible to read txns
+    {
+        let rotxn = env.read_txn().unwrap();
+        assert_eq!(read_all(&db, &rotxn), vec![]);
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(read_all(&db, &rotxn), vec![(1, 11), (2, 20)]);
+}
+
+#[cfg_attr(miri, ignore = "LMDB FFI is not supported by Miri")]
+#[test]
+fn savepoints_read_and_roll_back() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &1, &10).unwrap();
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("outer").unwrap();
+        let () = db.put(savepoints.txn(), &2, &20).unwrap();
+        let () = savepoints.push("inner").unwrap();
+        let () = db.put(savepoints.txn(), &3, &30).unwrap();
+        // Nested txns see the writes of the txns below them
+        assert_eq!(
+            read_all(&db, savepoints.txn()),
+            vec![(1, 10), (2, 20), (3, 30)]
+        );
+        let () = savepoints.rollback_to("inner").unwrap();
+        assert_eq!(read_all(&db, savepoints.txn()), vec![(1, 10), (2, 20)]);
+        let () = savepoints.release("outer").unwrap();
+        assert_eq!(read_all(&db, savepoints.txn()), vec![(1, 10), (2, 20)]);
+        let () = savepoints.push("dropped").unwrap();
+        let () = db.put(savepoints.txn(), &4, &40).unwrap();
+    }
+    // Savepoints that were not released are rolled back on drop
+    assert_eq!(read_all(&db, &rwtxn), vec![(1, 10), (2, 20)]);
+    let () = rwtxn.commit().unwrap();
+}
+
+#[cfg_attr(miri, ignore = "LMDB FFI is not supported by Miri")]
+#[test]
+fn reset_then_renew_sees_latest_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: Db = DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let () = db.put(&mut rwtxn, &1, &10).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(read_all(&db, &rotxn), vec![(1, 10)]);
+    let reset = rotxn.reset();
+    let mut rwtxn = env.write_txn().unwrap();
+    let () = db.put(&mut rwtxn, &2, &20).unwrap();
+    let () = rwtxn.commit().unwrap();
+    let rotxn = reset.renew().unwrap();
+    assert_eq!(read_all(&db, &rotxn), vec![(1, 10), (2, 20)]);
+    assert_eq!(rotxn.snapshot_seq(), env.last_commit_seq());
+}
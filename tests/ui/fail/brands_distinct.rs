@@ -0,0 +1,13 @@
+use sneed::{make_guard, Env, EnvOpenOptions};
+
+fn same_brand<'id>(_: &Env<'id>, _: &Env<'id>) {}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (dir0, dir1) = (tempfile::tempdir()?, tempfile::tempdir()?);
+    make_guard!(guard0);
+    make_guard!(guard1);
+    let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), dir0.path()) }?;
+    let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), dir1.path()) }?;
+    same_brand(&env0, &env1);
+    Ok(())
+}
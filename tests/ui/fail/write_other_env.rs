@@ -0,0 +1,17 @@
+use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (dir0, dir1) = (tempfile::tempdir()?, tempfile::tempdir()?);
+    make_guard!(guard0);
+    make_guard!(guard1);
+    let env0 = unsafe { Env::open(guard0, &EnvOpenOptions::new(), dir0.path()) }?;
+    let env1 = unsafe { Env::open(guard1, &EnvOpenOptions::new(), dir1.path()) }?;
+    let mut rwtxn0 = env0.write_txn()?;
+    let db0: DatabaseUnique<UnitKey, UnitKey> =
+        DatabaseUnique::create(&env0, &mut rwtxn0, "db")?;
+    let () = rwtxn0.commit()?;
+    let mut rwtxn1 = env1.write_txn()?;
+    let () = db0.put(&mut rwtxn1, &(), &())?;
+    let () = rwtxn1.commit()?;
+    Ok(())
+}
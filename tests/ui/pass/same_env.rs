@@ -0,0 +1,24 @@
+use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }?;
+    let mut rwtxn = env.write_txn()?;
+    let db: DatabaseUnique<UnitKey, UnitKey> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    let () = db.put(&mut rwtxn, &(), &())?;
+    // Write txns can be used for reads
+    assert_eq!(db.try_get(&rwtxn, &())?, Some(()));
+    let () = rwtxn.commit()?;
+    let rotxn = env.read_txn()?;
+    assert_eq!(db.try_get(&rotxn, &())?, Some(()));
+    drop(rotxn);
+    // Clones of an env share its brand
+    let env_clone = env.clone();
+    let rotxn = env_clone.read_txn()?;
+    assert_eq!(db.try_get(&rotxn, &())?, Some(()));
+    Ok(())
+}
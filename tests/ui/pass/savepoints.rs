@@ -0,0 +1,21 @@
+use sneed::{make_guard, DatabaseUnique, Env, EnvOpenOptions, UnitKey};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }?;
+    let mut rwtxn = env.write_txn()?;
+    let db: DatabaseUnique<UnitKey, UnitKey> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db")?;
+    {
+        let mut savepoints = rwtxn.savepoints();
+        let () = savepoints.push("a")?;
+        let () = db.put(savepoints.txn(), &(), &())?;
+        let () = savepoints.release("a")?;
+    }
+    assert_eq!(db.try_get(&rwtxn, &())?, Some(()));
+    let () = rwtxn.commit()?;
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use sneed::databases;
+
+databases! {
+    pub struct Schema {
+        users: DatabaseUnique<u8, u8> = "users",
+        events: RoDatabaseUnique<u8, u8> = "events",
+    }
+}
+
+fn main() {}
@@ -0,0 +1,69 @@
+use fallible_iterator::FallibleIterator as _;
+use sneed::{
+    db::constraint::{error, Unique},
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+type Codec = U32<BigEndian>;
+
+/// Records are `(name << 16) | data`, keyed by name
+fn name(record: &u32) -> u32 {
+    record >> 16
+}
+
+#[test]
+fn unique_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let primary: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env, &mut rwtxn, "primary").unwrap();
+    let lookup: DatabaseUnique<Codec, Codec> =
+        DatabaseUnique::create(&env, &mut rwtxn, "lookup").unwrap();
+    let unique = Unique::new(primary, lookup, name);
+    let () = unique.insert(&mut rwtxn, &1, &0x0001_0000).unwrap();
+    let () = unique.insert(&mut rwtxn, &2, &0x0002_0000).unwrap();
+    // Replacing a record with the same key
+    let () = unique.insert(&mut rwtxn, &1, &0x0001_0005).unwrap();
+    let () = rwtxn.commit().unwrap();
+
+    // Duplicate rejection
+    let mut rwtxn = env.write_txn().unwrap();
+    let err = unique.insert(&mut rwtxn, &3, &0x0001_0000).unwrap_err();
+    assert!(matches!(err, error::Unique::DuplicateKey(_)), "{err:?}");
+    assert_eq!(unique.primary().try_get(&rwtxn, &3).unwrap(), None);
+    drop(rwtxn);
+
+    // Rename to a taken name
+    let mut rwtxn = env.write_txn().unwrap();
+    let err = unique.rename(&mut rwtxn, &2, &0x0001_0000).unwrap_err();
+    assert!(matches!(err, error::Unique::DuplicateKey(_)), "{err:?}");
+    assert_eq!(
+        unique.primary().try_get(&rwtxn, &2).unwrap(),
+        Some(0x0002_0000)
+    );
+    assert_eq!(unique.lookup().try_get(&rwtxn, &1).unwrap(), Some(1));
+    assert_eq!(unique.lookup().try_get(&rwtxn, &2).unwrap(), Some(2));
+    drop(rwtxn);
+
+    // Rename to a free name releases the old name
+    let mut rwtxn = env.write_txn().unwrap();
+    assert!(unique.rename(&mut rwtxn, &2, &0x0003_0000).unwrap());
+    assert!(!unique.rename(&mut rwtxn, &9, &0x0009_0000).unwrap());
+    let () = unique.insert(&mut rwtxn, &4, &0x0002_0000).unwrap();
+    assert!(unique.remove(&mut rwtxn, &1).unwrap());
+    let () = rwtxn.commit().unwrap();
+
+    let rotxn = env.read_txn().unwrap();
+    let lookup: Vec<_> =
+        unique.lookup().iter(&rotxn).unwrap().collect().unwrap();
+    assert_eq!(lookup, vec![(2, 4), (3, 2)]);
+    let primary: Vec<_> =
+        unique.primary().iter(&rotxn).unwrap().collect().unwrap();
+    assert_eq!(primary, vec![(2, 0x0003_0000), (4, 0x0002_0000)]);
+}
@@ -0,0 +1,53 @@
+use sneed::{
+    make_guard,
+    types::{BigEndian, Bytes, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+#[test]
+fn usage_report() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(2);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let small: DatabaseUnique<U32<BigEndian>, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "small").unwrap();
+    let big: DatabaseUnique<U32<BigEndian>, Bytes> =
+        DatabaseUnique::create(&env, &mut rwtxn, "big").unwrap();
+    // Larger than a page, so stored in overflow pages
+    let () = small.put(&mut rwtxn, &0, &[0; 10_000]).unwrap();
+    for key in 0..1000 {
+        let () = big.put(&mut rwtxn, &key, &[1; 16]).unwrap();
+    }
+    let () = rwtxn.commit().unwrap();
+    let rotxn = env.read_txn().unwrap();
+    let report = env.usage_report(&rotxn).unwrap();
+    let names: Vec<&str> =
+        report.databases.iter().map(|db| db.name.as_str()).collect();
+    assert_eq!(names, ["big", "small"]);
+    let [big, small] = &report.databases[..] else {
+        panic!("expected 2 dbs")
+    };
+    assert_eq!(big.entries, 1000);
+    assert!(big.depth > 1);
+    assert!(big.branch_pages > 0);
+    assert!(big.leaf_pages > 1);
+    assert_eq!(big.overflow_pages, 0);
+    assert_eq!(small.entries, 1);
+    assert_eq!(small.depth, 1);
+    assert!(small.overflow_pages * u64::from(report.page_size) >= 10_000);
+    for db in &report.databases {
+        assert_eq!(db.bytes, db.pages() * u64::from(report.page_size));
+    }
+    assert_eq!(
+        report.used_pages + report.free_pages,
+        report.map_size / u64::from(report.page_size)
+    );
+    assert!(report.used_pages >= big.pages() + small.pages());
+    let display = report.to_string();
+    assert!(display.contains("database"), "{display}");
+    assert!(display.contains("big"), "{display}");
+    assert!(display.contains("small"), "{display}");
+}
@@ -0,0 +1,54 @@
+use std::{sync::mpsc, thread, time::Duration};
+
+use sneed::{
+    env::{write_guarded::error, WriteGuarded},
+    make_guard, Env, EnvOpenOptions,
+};
+
+#[test]
+fn writer_busy_reports_holder() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let env = unsafe { Env::open(guard, &EnvOpenOptions::new(), dir.path()) }
+        .unwrap();
+    let guarded = WriteGuarded::new(env);
+    let (acquired_tx, acquired_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        let holder = thread::Builder::new()
+            .name("holder".to_owned())
+            .spawn_scoped(scope, {
+                let guarded = guarded.clone();
+                move || {
+                    let rwtxn =
+                        guarded.write_txn("deliberate hold", None).unwrap();
+                    let () = acquired_tx.send(()).unwrap();
+                    let () = release_rx.recv().unwrap();
+                    rwtxn.abort();
+                }
+            })
+            .unwrap();
+        let () = acquired_rx.recv().unwrap();
+        let writer = guarded.current_writer().unwrap();
+        assert_eq!(writer.label(), "deliberate hold");
+        assert_eq!(writer.thread_name(), Some("holder"));
+        assert_eq!(writer.thread_id(), holder.thread().id());
+        let timeout = Duration::from_millis(50);
+        let err = match guarded.write_txn("contender", Some(timeout)) {
+            Ok(_) => panic!("write txn should be held"),
+            Err(err) => err,
+        };
+        let error::WriteTxn::WriterBusy(err) = err else {
+            panic!("expected WriterBusy, got {err:?}")
+        };
+        assert_eq!(err.holder_label(), "deliberate hold");
+        assert!(err.held_for() >= timeout);
+        let () = release_tx.send(()).unwrap();
+        let () = holder.join().unwrap();
+    });
+    assert!(guarded.current_writer().is_none());
+    let rwtxn = guarded
+        .write_txn("after release", Some(Duration::from_secs(10)))
+        .unwrap();
+    rwtxn.abort();
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "tokio")]
+
+use std::{sync::Barrier, thread, time::Duration};
+
+use sneed::{
+    env::{
+        writer::{self, error::Submit},
+        Writer,
+    },
+    make_guard,
+    types::{BigEndian, U32},
+    DatabaseUnique, Env, EnvOpenOptions,
+};
+
+const SUBMITTERS: u32 = 8;
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn concurrent_submitters_coalesce() {
+    let dir = tempfile::tempdir().unwrap();
+    make_guard!(guard);
+    let mut opts = EnvOpenOptions::new();
+    let _ = opts.max_dbs(1);
+    let env = unsafe { Env::open(guard, &opts, dir.path()) }.unwrap();
+    let mut rwtxn = env.write_txn().unwrap();
+    let db: DatabaseUnique<U32<BigEndian>, U32<BigEndian>> =
+        DatabaseUnique::create(&env, &mut rwtxn, "db").unwrap();
+    let initial_seq = rwtxn.commit_with_seq().unwrap();
+    let config = writer::Config {
+        max_batch_size: SUBMITTERS as usize,
+        max_latency: Duration::from_secs(10),
+    };
+    let barrier = Barrier::new(SUBMITTERS as usize);
+    thread::scope(|scope| {
+        let handle = Writer::spawn(scope, env.clone(), config);
+        let submitters: Vec<_> = (0..SUBMITTERS)
+            .map(|i| {
+                let (handle, db, barrier) =
+                    (handle.clone(), db.clone(), &barrier);
+                scope.spawn(move || {
+                    let _wait = barrier.wait();
+                    block_on(handle.submit(move |rwtxn| {
+                        if i == 0 {
+                            // A failing closure does not affect the batch
+                            let () = db.put(rwtxn, &i, &i)?;
+                            return Err("closure failed".into());
+                        }
+                        let () = db.put(rwtxn, &i, &i)?;
+                        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(i)
+                    }))
+                })
+            })
+            .collect();
+        drop(handle);
+        for (i, submitter) in submitters.into_iter().enumerate() {
+            let res = submitter.join().unwrap();
+            if i == 0 {
+                assert!(matches!(res, Err(Submit::Closure(_))));
+            } else {
+                assert_eq!(res.unwrap(), i as u32);
+            }
+        }
+    });
+    // Every submitter filled a single batch, so only one txn was committed
+    assert_eq!(env.last_commit_seq(), initial_seq + 1);
+    let rotxn = env.read_txn().unwrap();
+    assert_eq!(db.try_get(&rotxn, &0).unwrap(), None);
+    for i in 1..SUBMITTERS {
+        assert_eq!(db.try_get(&rotxn, &i).unwrap(), Some(i));
+    }
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "writer-lock")]
+
+use sneed::{env::error::OpenWithWriterLock, make_guard, Env, EnvOpenOptions};
+
+#[test]
+fn second_open_fails_while_locked() {
+    let dir = tempfile::tempdir().unwrap();
+    let lock_dir = tempfile::tempdir().unwrap();
+    let lock_path = lock_dir.path().join("writer.lock");
+    make_guard!(guard0);
+    let env = unsafe {
+        Env::open_with_writer_lock(
+            guard0,
+            &EnvOpenOptions::new(),
+            dir.path(),
+            &lock_path,
+        )
+    }
+    .unwrap();
+    let clone = env.clone();
+    make_guard!(guard1);
+    let err = unsafe {
+        Env::open_with_writer_lock(
+            guard1,
+            &EnvOpenOptions::new(),
+            dir.path(),
+            &lock_path,
+        )
+    }
+    .unwrap_err();
+    assert!(matches!(err, OpenWithWriterLock::WriteLocked(_)), "{err:?}");
+    // The lock is held until every clone is dropped
+    drop(env);
+    make_guard!(guard2);
+    let err = unsafe {
+        Env::open_with_writer_lock(
+            guard2,
+            &EnvOpenOptions::new(),
+            dir.path(),
+            &lock_path,
+        )
+    }
+    .unwrap_err();
+    assert!(matches!(err, OpenWithWriterLock::WriteLocked(_)), "{err:?}");
+    drop(clone);
+    make_guard!(guard3);
+    let _env = unsafe {
+        Env::open_with_writer_lock(
+            guard3,
+            &EnvOpenOptions::new(),
+            dir.path(),
+            &lock_path,
+        )
+    }
+    .unwrap();
+}